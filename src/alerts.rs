@@ -0,0 +1,273 @@
+use std::path::Path;
+
+use bigdecimal::{BigDecimal, Zero};
+use notify_rust::Notification;
+
+use crate::{
+    balances::OpeningBalances,
+    config::Config,
+    file::BookkeepingFile,
+    forecast::Forecast,
+    i18n::tf,
+    stats::{Stats, Tally},
+    Result,
+};
+
+/// A limit the month went over
+#[derive(Debug, PartialEq)]
+pub enum Breach {
+    /// A category spent more than its share of the income
+    Budget {
+        category: String,
+        spent: BigDecimal,
+        budget: BigDecimal,
+    },
+    /// The month spent more than `spending_target`
+    Target {
+        spent: BigDecimal,
+        target: BigDecimal,
+    },
+}
+
+impl Breach {
+    pub fn message(&self, config: &Config) -> String {
+        match self {
+            Self::Budget {
+                category,
+                spent,
+                budget,
+            } => format!(
+                "{} went over its budget: {} of {}",
+                category,
+                config.format_amount(spent),
+                config.format_amount(budget)
+            ),
+            Self::Target { spent, target } => format!(
+                "This month went over the spending target: {} of {}",
+                config.format_amount(spent),
+                config.format_amount(target)
+            ),
+        }
+    }
+
+    /// How far over the limit the month went, shown as the take is recorded
+    pub fn overshoot(&self, config: &Config) -> String {
+        let (name, spent, limit) = match self {
+            Self::Budget {
+                category,
+                spent,
+                budget,
+            } => (category.as_str(), spent, budget),
+            Self::Target { spent, target } => ("spending target", spent, target),
+        };
+
+        format!(
+            "{}: {}/{}, {} over",
+            name,
+            config.format_amount(spent),
+            config.format_amount(limit),
+            config.format_amount(&(spent - limit))
+        )
+    }
+}
+
+/// The limits a month went over from `before` to `after`, the same month read before and
+/// after recording something in it
+///
+/// Limits that were already broken before are left out, so each one is only pointed out once.
+pub fn breaches(before: &Stats, after: &Stats, config: &Config) -> Vec<Breach> {
+    let over_budget = |stats: &Stats, category: &str| {
+        let spent = stats.spending.get(category).cloned().unwrap_or_default();
+        let budget = stats.budget(category, config)?;
+        (spent > budget).then_some((spent, budget))
+    };
+
+    let mut breaches: Vec<_> = config
+        .budgets
+        .keys()
+        .filter(|category| over_budget(before, category).is_none())
+        .filter_map(|category| {
+            let (spent, budget) = over_budget(after, category)?;
+            Some(Breach::Budget {
+                category: category.clone(),
+                spent,
+                budget,
+            })
+        })
+        .collect();
+
+    if let Some(target) = &config.spending_target {
+        let (spent_before, spent) = (
+            before.lifetime_total().outgoing,
+            after.lifetime_total().outgoing,
+        );
+        if &spent_before <= target && &spent > target && !target.is_zero() {
+            breaches.push(Breach::Target {
+                spent,
+                target: target.clone(),
+            });
+        }
+    }
+
+    breaches
+}
+
+/// Every limit `stats` is over, whenever it went over it
+pub fn over_limits(stats: &Stats, config: &Config) -> Vec<Breach> {
+    breaches(&Tally::new(config).finish(), stats, config)
+}
+
+/// Points out the cash on hand being below `low_balance` on `day` of `file`, or being on track
+/// to end the month below it
+///
+/// A month without an opening balance is taken to have started from zero.
+pub fn low_balance(
+    dir: &Path,
+    file: BookkeepingFile,
+    day: u8,
+    config: &Config,
+) -> Result<Option<String>> {
+    let threshold = match &config.low_balance {
+        Some(threshold) => threshold,
+        None => return Ok(None),
+    };
+
+    let opening = OpeningBalances::load(dir, config)?
+        .get(&file)
+        .cloned()
+        .unwrap_or_default();
+    let forecast = Forecast::compute(dir, file, day, config)?;
+    let cash = &opening + forecast.total.balance();
+    let projected = opening + forecast.projected_balance();
+
+    let message = if &cash < threshold {
+        tf("the cash on hand is {}", &[&config.format_amount(&cash)])
+    } else if &projected < threshold {
+        tf(
+            "the cash on hand is on track to be {} by {}",
+            &[&config.format_amount(&projected), &forecast.end()],
+        )
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(tf(
+        "{}, below the low balance of {}",
+        &[&message, &config.format_amount(threshold)],
+    )))
+}
+
+/// Sends a desktop notification for each breach
+///
+/// Not being able to notify isn't worth failing over, as what was recorded already was.
+pub fn notify(breaches: &[Breach], config: &Config) {
+    for breach in breaches {
+        let result = Notification::new()
+            .summary("porquinho")
+            .body(&breach.message(config))
+            .show();
+
+        if let Err(err) = result {
+            eprintln!("Couldn't send a desktop notification: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{breaches, low_balance, Breach};
+    use crate::{
+        config::Config,
+        file::{BookkeepingFile, StoredFile},
+        rules::Rule,
+        stats::Stats,
+    };
+
+    #[test]
+    fn points_out_each_breach_once() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("10-2024");
+        let config = Config {
+            rules: vec![Rule {
+                pattern: "lunch".to_owned(),
+                category: "food".to_owned(),
+            }],
+            budgets: BTreeMap::from([("food".to_owned(), BigDecimal::from(10))]),
+            spending_target: Some(BigDecimal::from(100)),
+            ..Config::default()
+        };
+        let stats = || {
+            let stored = StoredFile::in_data(dir.path(), BookkeepingFile::new(10, 2024));
+            Stats::from_files(vec![stored], &config).unwrap()
+        };
+
+        fs::write(&path, "01 + 500 Salary\n02 - 40 Lunch\n").unwrap();
+        let before = stats();
+        fs::write(&path, "01 + 500 Salary\n02 - 40 Lunch\n03 - 20 Lunch\n").unwrap();
+        let after = stats();
+        let found = breaches(&before, &after, &config);
+        assert_eq!(
+            found,
+            [Breach::Budget {
+                category: "food".to_owned(),
+                spent: BigDecimal::from(60),
+                budget: BigDecimal::from(50),
+            }]
+        );
+        assert_eq!(
+            found[0].overshoot(&config),
+            "food: R$ 60.00/R$ 50.00, R$ 10.00 over"
+        );
+
+        fs::write(
+            &path,
+            "01 + 500 Salary\n02 - 40 Lunch\n03 - 20 Lunch\n04 - 50 Rent\n",
+        )
+        .unwrap();
+        assert_eq!(
+            breaches(&after, &stats(), &config),
+            [Breach::Target {
+                spent: BigDecimal::from(110),
+                target: BigDecimal::from(100),
+            }]
+        );
+    }
+
+    #[test]
+    fn points_out_a_low_balance() {
+        let dir = TempDir::new().unwrap();
+        let file = BookkeepingFile::new(10, 2024);
+        fs::write(
+            dir.path().join("10-2024"),
+            "01 + 1000 Salary\n02 - 200 Market\n",
+        )
+        .unwrap();
+        let config = |threshold| Config {
+            low_balance: Some(BigDecimal::from(threshold)),
+            ..Config::default()
+        };
+
+        let warning =
+            |threshold| low_balance(dir.path(), file.clone(), 10, &config(threshold)).unwrap();
+        assert_eq!(warning(100), None);
+        // Spending 20 a day so far, it's heading towards 380
+        assert_eq!(
+            warning(500),
+            Some(
+                "the cash on hand is on track to be R$ 380.00 by the end of October, below the \
+                 low balance of R$ 500.00"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            warning(900),
+            Some("the cash on hand is R$ 800.00, below the low balance of R$ 900.00".to_owned())
+        );
+    }
+}
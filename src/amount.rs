@@ -0,0 +1,176 @@
+use std::{iter::Peekable, str::Chars};
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::locale::{round, Locale};
+
+/// Parses an amount given in the command line, which may be an arithmetic expression such as
+/// `3*12.90+4.50`, with `+`, `-`, `*`, `/` and parentheses
+///
+/// Numbers are read as `Locale::parse_decimal` does, and may end in `k` for thousands or `m`
+/// for millions, as in `1.2k`. Results of divisions are rounded to cents, as `100/3` couldn't
+/// be written down otherwise.
+pub fn parse_amount(input: &str, locale: Locale) -> Option<BigDecimal> {
+    let mut parser = Parser {
+        chars: input.chars().peekable(),
+        locale,
+    };
+
+    let amount = parser.expression()?;
+    parser.skip_whitespace();
+
+    parser.chars.peek().is_none().then_some(amount)
+}
+
+/// A recursive descent parser over the characters of an expression
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    locale: Locale,
+}
+
+impl Parser<'_> {
+    /// Terms added or subtracted
+    fn expression(&mut self) -> Option<BigDecimal> {
+        let mut value = self.term()?;
+
+        loop {
+            match self.next_operator(&['+', '-']) {
+                Some('+') => value += self.term()?,
+                Some(_) => value -= self.term()?,
+                None => return Some(value),
+            }
+        }
+    }
+
+    /// Factors multiplied or divided
+    fn term(&mut self) -> Option<BigDecimal> {
+        let mut value = self.factor()?;
+
+        loop {
+            match self.next_operator(&['*', '/']) {
+                Some('*') => value *= self.factor()?,
+                Some(_) => {
+                    let divisor = self.factor()?;
+                    if divisor.is_zero() {
+                        return None;
+                    }
+                    value = round(&(value / divisor), 2);
+                }
+                None => return Some(value),
+            }
+        }
+    }
+
+    /// A number, a negated factor, or an expression in parentheses
+    fn factor(&mut self) -> Option<BigDecimal> {
+        self.skip_whitespace();
+
+        match self.chars.peek()? {
+            '-' => {
+                self.chars.next();
+                Some(-self.factor()?)
+            }
+            '(' => {
+                self.chars.next();
+                let value = self.expression()?;
+                self.next_operator(&[')'])?;
+                Some(value)
+            }
+            _ => {
+                let mut number = String::new();
+                while let Some(&ch) = self.chars.peek() {
+                    if ch.is_whitespace() || "+-*/()".contains(ch) {
+                        break;
+                    }
+                    number.push(ch);
+                    self.chars.next();
+                }
+
+                parse_number(&number, self.locale)
+            }
+        }
+    }
+
+    /// Consumes the next character if it's one of `operators`
+    fn next_operator(&mut self, operators: &[char]) -> Option<char> {
+        self.skip_whitespace();
+
+        let next = *self.chars.peek()?;
+        if operators.contains(&next) {
+            self.chars.next();
+            Some(next)
+        } else {
+            None
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+    }
+}
+
+/// A decimal in `locale`, possibly followed by a `k` or `m` suffix
+fn parse_number(number: &str, locale: Locale) -> Option<BigDecimal> {
+    let (digits, zeros) = match number.char_indices().last()? {
+        (idx, 'k' | 'K') => (&number[..idx], 3),
+        (idx, 'm' | 'M') => (&number[..idx], 6),
+        _ => (number, 0),
+    };
+
+    let decimal = locale.parse_decimal(digits)?;
+    let (_, scale) = decimal.as_bigint_and_exponent();
+    let multiplier = BigDecimal::from(10_u32.pow(zeros));
+
+    // So that `1.2k` is written down as `1200`, not `1200.0`
+    Some((decimal * multiplier).with_scale((scale - i64::from(zeros)).max(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
+    use super::parse_amount;
+    use crate::locale::Locale;
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn evaluates_expressions() {
+        let english = Locale::English;
+        assert_eq!(parse_amount("12.50", english), Some(decimal("12.50")));
+        assert_eq!(
+            parse_amount("3*12.90+4.50", english),
+            Some(decimal("43.20"))
+        );
+        assert_eq!(
+            parse_amount("(100 - 10) / 4", english),
+            Some(decimal("22.50"))
+        );
+        assert_eq!(parse_amount("100/3", english), Some(decimal("33.33")));
+        assert_eq!(parse_amount("-20", english), Some(decimal("-20")));
+        assert_eq!(
+            parse_amount("2*12,90", Locale::BrazilianPortuguese),
+            Some(decimal("25.80"))
+        );
+
+        assert_eq!(parse_amount("1.2k", english), Some(decimal("1200")));
+        assert_eq!(parse_amount("2m", english), Some(decimal("2000000")));
+        assert_eq!(parse_amount("2k/3", english), Some(decimal("666.67")));
+        assert_eq!(
+            parse_amount("1,5K", Locale::BrazilianPortuguese),
+            Some(decimal("1500"))
+        );
+
+        assert_eq!(parse_amount("k", english), None);
+        assert_eq!(parse_amount("2km", english), None);
+        assert_eq!(parse_amount("1/0", english), None);
+        assert_eq!(parse_amount("(1+2", english), None);
+        assert_eq!(parse_amount("1+", english), None);
+        assert_eq!(parse_amount("12 pizzas", english), None);
+        assert_eq!(parse_amount("", english), None);
+    }
+}
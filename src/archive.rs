@@ -0,0 +1,146 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use fs_err as fs;
+
+use crate::{
+    file::{list_bookkeeping_files, BookkeepingFile, Location, StoredFile},
+    Error, Result,
+};
+
+/// Path of the archive holding every bookkeeping file of `year`
+fn archive_path(dir: &Path, year: i32) -> PathBuf {
+    dir.join(format!("archive-{year}.tar.zst"))
+}
+
+/// The year of an archive, given its file name
+pub fn archive_year(file_name: &str) -> Option<i32> {
+    file_name
+        .strip_prefix("archive-")?
+        .strip_suffix(".tar.zst")?
+        .parse()
+        .ok()
+}
+
+/// Moves every bookkeeping file of `year` into a compressed archive, or only lists them in a
+/// dry run
+pub fn archive_year_files(dir: &Path, year: i32, dry_run: bool) -> Result<PathBuf> {
+    let path = archive_path(dir, year);
+    if path.exists() {
+        return Err(Error::AlreadyArchived(year));
+    }
+
+    let files: Vec<_> = list_bookkeeping_files(dir)?
+        .into_iter()
+        .filter(|file| file.year() == year)
+        .collect();
+
+    if files.is_empty() {
+        return Err(Error::NothingToArchive(year));
+    }
+
+    if dry_run {
+        println!("Would move into {}:", path.display());
+        for file in &files {
+            println!("\t{}", file.as_str());
+        }
+        return Ok(path);
+    }
+
+    // Write to a temporary name, so a failure never leaves a half-written archive behind
+    let partial_path = path.with_extension("partial");
+    let encoder = zstd::Encoder::new(fs::File::create(&partial_path)?, 0)?;
+    let mut builder = tar::Builder::new(encoder);
+    for file in &files {
+        builder.append_path_with_name(dir.join(file.as_path()), file.as_path())?;
+    }
+    builder.into_inner()?.finish()?.sync_all()?;
+    fs::rename(&partial_path, &path)?;
+
+    for file in &files {
+        fs::remove_file(dir.join(file.as_path()))?;
+    }
+
+    Ok(path)
+}
+
+/// Lists every bookkeeping file, both the ones in `dir` and the ones inside of its archives,
+/// sorted chronologically
+pub fn list_stored_files(dir: &Path) -> Result<Vec<StoredFile>> {
+    let mut files: Vec<_> = list_bookkeeping_files(dir)?
+        .into_iter()
+        .map(|file| StoredFile::in_data(dir, file))
+        .collect();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let is_archive = entry.file_name().to_str().and_then(archive_year).is_some();
+
+        if is_archive {
+            files.extend(read_archive(&entry.path())?);
+        }
+    }
+
+    files.sort_by_key(|stored| (stored.file.year(), stored.file.month()));
+
+    Ok(files)
+}
+
+/// Extracts every bookkeeping file of an archive into memory
+fn read_archive(path: &Path) -> Result<Vec<StoredFile>> {
+    let decoder = zstd::Decoder::new(fs::File::open(path)?)?;
+    let mut archive = tar::Archive::new(decoder);
+    let mut files = Vec::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let file = entry
+            .path()?
+            .to_str()
+            .and_then(BookkeepingFile::from_file_name);
+
+        if let Some(file) = file {
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            files.push(StoredFile {
+                file,
+                location: Location::Archived(contents),
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{archive_year_files, list_stored_files};
+    use crate::file::Location;
+
+    #[test]
+    fn archives_a_year_and_reads_it_back() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("11-2022"), "05 + 100.00 Salary\n").unwrap();
+        fs::write(dir.path().join("12-2022"), "10 - 150.00 Rent\n").unwrap();
+        fs::write(dir.path().join("01-2023"), "12 - 20.00 Lunch\n").unwrap();
+
+        let archive = archive_year_files(dir.path(), 2022, false).unwrap();
+        assert!(archive.exists());
+        assert!(!dir.path().join("11-2022").exists());
+        assert!(archive_year_files(dir.path(), 2022, false).is_err());
+
+        let files = list_stored_files(dir.path()).unwrap();
+        let names: Vec<_> = files.iter().map(|stored| stored.file.as_str()).collect();
+        assert_eq!(names, ["11-2022", "12-2022", "01-2023"]);
+
+        match &files[1].location {
+            Location::Archived(contents) => assert_eq!(contents, b"10 - 150.00 Rent\n"),
+            Location::Data(_) => panic!("12-2022 should have been archived"),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use chrono::Local;
+use fs_err as fs;
+
+use crate::Result;
+
+/// Copies `path` into the `backups` folder next to it, keeping only its `keep` most recent
+/// backups. Does nothing if `keep` is zero or `path` doesn't exist yet.
+pub fn backup(path: &Path, keep: usize) -> Result<()> {
+    if keep == 0 || !path.exists() {
+        return Ok(());
+    }
+
+    // Safety: bookkeeping files always have a name
+    let file_name = path.file_name().unwrap().to_string_lossy();
+
+    let dir = path.with_file_name("backups");
+    fs::create_dir_all(&dir)?;
+
+    // The timestamp sorts chronologically, so the oldest backups come first by name
+    let timestamp = Local::now().format("%Y%m%dT%H%M%S%.6f");
+    fs::copy(path, dir.join(format!("{file_name}.{timestamp}")))?;
+
+    let prefix = format!("{file_name}.");
+    let mut backups: Vec<_> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&prefix))
+        })
+        .collect();
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(keep);
+    for old in &backups[..excess] {
+        fs::remove_file(old)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::backup;
+
+    #[test]
+    fn keeps_only_the_most_recent_backups() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("10-2024");
+
+        for version in 1..=4 {
+            fs::write(&path, format!("{version}")).unwrap();
+            backup(&path, 2).unwrap();
+        }
+
+        let mut contents: Vec<_> = fs::read_dir(dir.path().join("backups"))
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        contents.sort();
+
+        assert_eq!(contents, ["3", "4"]);
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use bigdecimal::{BigDecimal, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config, currency::Converter, file::BookkeepingFile, locale::round, reader::Reader,
+    sidecar, Error, Result,
+};
+
+/// The description of the put `close` records for the interest a month earned
+pub const INTEREST: &str = "Interest #interest";
+
+/// The balance each month started with, kept in the data folder
+pub const BALANCES_FILE: &str = ".porquinho-balances.toml";
+
+/// The cash on hand at the start of each month, keyed by `MM-YYYY`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OpeningBalances {
+    #[serde(flatten)]
+    months: BTreeMap<String, BigDecimal>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl OpeningBalances {
+    pub fn load(dir: &Path, config: &Config) -> Result<Self> {
+        let path = dir.join(BALANCES_FILE);
+
+        let mut balances: Self = sidecar::load(&path, config, Error::Balances)?;
+        balances.path = path;
+
+        Ok(balances)
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        sidecar::save(&self.path, self, config, Error::Balances)
+    }
+
+    /// The balance `month` started with, if it was set or carried over
+    pub fn get(&self, month: &BookkeepingFile) -> Option<&BigDecimal> {
+        self.months.get(month.as_str())
+    }
+
+    pub fn set(&mut self, month: &BookkeepingFile, balance: BigDecimal) {
+        self.months.insert(month.as_str().to_owned(), balance);
+    }
+}
+
+/// How a month ended, as `close` carried it into the next one
+#[derive(Debug, Serialize)]
+pub struct Closing {
+    /// The month closed, in the `MM-YYYY` format
+    pub month: String,
+    pub opening: BigDecimal,
+    pub incoming: BigDecimal,
+    pub outgoing: BigDecimal,
+    /// The balance carried into the next month
+    pub closing: BigDecimal,
+}
+
+/// Carries the balance `month` ended with into the month after it
+///
+/// A month without an opening balance is taken to have started from zero.
+pub fn close(dir: &Path, month: &BookkeepingFile, config: &Config) -> Result<Closing> {
+    let mut balances = OpeningBalances::load(dir, config)?;
+    let total =
+        Reader::new(config).total_from_file(dir.join(month.as_path()), &Converter::new(config))?;
+
+    let opening = balances.get(month).cloned().unwrap_or_default();
+    let closing = &opening + total.balance();
+
+    balances.set(&month.next(), closing.clone());
+    if !config.dry_run {
+        balances.save(config)?;
+    }
+
+    Ok(Closing {
+        month: month.as_str().to_owned(),
+        opening,
+        incoming: total.incoming,
+        outgoing: total.outgoing,
+        closing,
+    })
+}
+
+/// What `month` earns at the `interest_rate` of config.toml over the balance it'd end with
+///
+/// Returns `None` without a rate, without a positive balance to earn on, or once the month's
+/// interest was recorded. Fails for months that aren't over, as their balance may still change.
+pub fn interest(
+    dir: &Path,
+    month: &BookkeepingFile,
+    config: &Config,
+) -> Result<Option<BigDecimal>> {
+    let rate = match &config.interest_rate {
+        Some(rate) => rate,
+        None => return Ok(None),
+    };
+
+    let path = dir.join(month.as_path());
+    let mut reader = Reader::new(config);
+    if reader
+        .operations(&path)?
+        .iter()
+        .any(|operation| operation.description == INTEREST)
+    {
+        return Ok(None);
+    }
+    if month.ordinal() >= BookkeepingFile::current_file().ordinal() {
+        return Err(Error::MonthNotOver(month.as_str().to_owned()));
+    }
+
+    let total = reader.total_from_file(&path, &Converter::new(config))?;
+    let opening = OpeningBalances::load(dir, config)?
+        .get(month)
+        .cloned()
+        .unwrap_or_default();
+    let balance = opening + total.balance();
+    if balance <= BigDecimal::zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(round(&(balance * rate / BigDecimal::from(100)), 2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{close, interest, OpeningBalances, INTEREST};
+    use crate::{config::Config, file::BookkeepingFile};
+
+    #[test]
+    fn carries_balances_forward() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("11-2023"),
+            "05 + 100 Salary\n06 - 30 Rent\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("12-2023"),
+            "05 + 100 Salary\n06 - 50 Rent\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let (november, december) = (
+            BookkeepingFile::new(11, 2023),
+            BookkeepingFile::new(12, 2023),
+        );
+
+        let mut balances = OpeningBalances::load(dir.path(), &config).unwrap();
+        balances.set(&november, BigDecimal::from(1000));
+        balances.save(&config).unwrap();
+
+        assert_eq!(
+            close(dir.path(), &november, &config).unwrap().closing,
+            BigDecimal::from(1070)
+        );
+        assert_eq!(
+            close(dir.path(), &december, &config).unwrap().closing,
+            BigDecimal::from(1120)
+        );
+
+        let balances = OpeningBalances::load(dir.path(), &config).unwrap();
+        assert_eq!(balances.get(&december), Some(&BigDecimal::from(1070)));
+        assert_eq!(
+            balances.get(&BookkeepingFile::new(1, 2024)),
+            Some(&BigDecimal::from(1120))
+        );
+    }
+
+    #[test]
+    fn earns_interest_once() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("03-2024");
+        fs::write(&path, "05 + 1000 Deposit\n06 - 10 Fee\n").unwrap();
+        let march = BookkeepingFile::new(3, 2024);
+
+        assert_eq!(
+            interest(dir.path(), &march, &Config::default()).unwrap(),
+            None
+        );
+
+        let config = Config {
+            interest_rate: Some("0.8".parse().unwrap()),
+            ..Config::default()
+        };
+        let earned = interest(dir.path(), &march, &config).unwrap();
+        assert_eq!(earned, Some("7.92".parse().unwrap()));
+
+        fs::write(
+            &path,
+            format!("05 + 1000 Deposit\n31 + 7.92 {}\n", INTEREST),
+        )
+        .unwrap();
+        assert_eq!(interest(dir.path(), &march, &config).unwrap(), None);
+
+        let current = BookkeepingFile::current_file();
+        fs::write(dir.path().join(current.as_path()), "05 + 1000 Deposit\n").unwrap();
+        interest(dir.path(), &current, &config).unwrap_err();
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::Config, i18n::t, parser::EntryType, reader::Reader, sidecar, Error, Result};
+
+/// Bills due every month, kept in the data folder
+pub const BILLS_FILE: &str = ".porquinho-bills.toml";
+
+/// Something paid every month, by a given day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bill {
+    pub name: String,
+    /// How much it usually costs
+    pub amount: BigDecimal,
+    /// Day of the month it's due
+    pub due: u8,
+}
+
+/// Where a bill stands in a month
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BillState {
+    Paid,
+    Upcoming,
+    Overdue,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bills {
+    #[serde(default, rename = "bills")]
+    pub list: Vec<Bill>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Bills {
+    pub fn load(dir: &Path, config: &Config) -> Result<Self> {
+        let path = dir.join(BILLS_FILE);
+
+        let mut bills: Self = sidecar::load(&path, config, |err| {
+            Error::Bills(format!("invalid {}: {}", BILLS_FILE, err))
+        })?;
+        bills.path = path;
+
+        Ok(bills)
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        sidecar::save(&self.path, self, config, Error::Bills)
+    }
+
+    /// Every bill with where it stands on `day` of the month kept at `path`
+    ///
+    /// A bill counts as paid once a take whose description contains its name is recorded.
+    pub fn states(&self, path: &Path, day: u8, config: &Config) -> Result<Vec<(&Bill, BillState)>> {
+        let takes: Vec<String> = if path.exists() {
+            Reader::new(config)
+                .operations(path)?
+                .into_iter()
+                .filter(|operation| operation.typ == EntryType::Debit)
+                .map(|operation| config.matching.normalize(&operation.description))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut states: Vec<_> = self
+            .list
+            .iter()
+            .map(|bill| {
+                let name = config.matching.normalize(&bill.name);
+                let state = if takes.iter().any(|take| take.contains(&name)) {
+                    BillState::Paid
+                } else if bill.due < day {
+                    BillState::Overdue
+                } else {
+                    BillState::Upcoming
+                };
+                (bill, state)
+            })
+            .collect();
+        states.sort_by_key(|(bill, _)| bill.due);
+
+        Ok(states)
+    }
+
+    /// Prints the bills of the month kept at `path`, as they stand on `day`
+    pub fn print(&self, path: &Path, day: u8, config: &Config) -> Result<()> {
+        if self.list.is_empty() {
+            println!("{}", t("No bills yet, add one with `porquinho bill add`"));
+            return Ok(());
+        }
+
+        println!("{}", t("Bills"));
+        for (bill, state) in self.states(path, day, config)? {
+            let state = match state {
+                BillState::Paid => t("paid"),
+                BillState::Upcoming => t("upcoming"),
+                BillState::Overdue => t("OVERDUE"),
+            };
+            println!(
+                "\t{:02} {}: {}, {}",
+                bill.due,
+                bill.name,
+                config.format_amount(&bill.amount),
+                state
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{Bill, BillState, Bills};
+    use crate::config::Config;
+
+    #[test]
+    fn marks_bills_paid_by_their_takes() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::default();
+        let path = dir.path().join("10-2024");
+        fs::write(
+            &path,
+            "08 - 182.40 Electricity bill\n09 + 180 Electricity\n",
+        )
+        .unwrap();
+
+        let mut bills = Bills::load(dir.path(), &config).unwrap();
+        let bill = |name: &str, due| Bill {
+            name: name.to_owned(),
+            amount: BigDecimal::from(180),
+            due,
+        };
+        bills.list.push(bill("Water", 20));
+        bills.list.push(bill("Internet", 5));
+        bills.list.push(bill("electricity", 10));
+        bills.save(&config).unwrap();
+
+        let bills = Bills::load(dir.path(), &config).unwrap();
+        let states: Vec<_> = bills
+            .states(&path, 12, &Config::default())
+            .unwrap()
+            .into_iter()
+            .map(|(bill, state)| (bill.name.as_str(), state))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                ("Internet", BillState::Overdue),
+                ("electricity", BillState::Paid),
+                ("Water", BillState::Upcoming),
+            ]
+        );
+    }
+}
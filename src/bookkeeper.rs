@@ -0,0 +1,430 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    balances::OpeningBalances,
+    config::Config,
+    currency::Converter,
+    export::Record,
+    file::BookkeepingFile,
+    hooks,
+    migrations::migrate,
+    parser::{Entry, Operation},
+    sqlite::{Backend, Database},
+    stats::{lifetime_total, MonthTotal, Stats, Tally},
+    status::Status,
+    storage::{Files, Scanned, Storage},
+    writer::check_description,
+    Error, Result, Total,
+};
+
+/// A book kept in a data folder, the entry point for programs embedding porquinho
+///
+/// Unlike the `porquinho` binary, it never prompts, and leaves committing to git to the caller.
+pub struct Bookkeeper {
+    dir: PathBuf,
+    config: Config,
+    /// Where entries are kept, as the `storage` setting says unless given to `with_storage`
+    storage: Box<dyn Storage>,
+    /// The format the data folder was in, if `open` upgraded it
+    upgraded_from: Option<u32>,
+}
+
+impl Bookkeeper {
+    /// Opens the book kept in `dir`, upgrading the folder's format first if it's outdated
+    ///
+    /// Encrypted books need `config` to be unlocked with `Config::unlock` beforehand.
+    pub fn open(dir: impl Into<PathBuf>, config: Config) -> Result<Self> {
+        // The database would keep the book in plaintext
+        if config.storage == Backend::Sqlite && config.encryption.is_some() {
+            return Err(Error::Database(
+                "`storage = \"sqlite\"` can't be used along with `encryption`".into(),
+            ));
+        }
+
+        let dir = dir.into();
+        let upgraded_from = migrate(&dir, &config)?;
+        let storage: Box<dyn Storage> = match config.storage {
+            Backend::Files => Box::new(Files::new(&dir)),
+            Backend::Sqlite => Box::new(Database::open(&dir)?),
+        };
+
+        Ok(Self {
+            dir,
+            config,
+            storage,
+            upgraded_from,
+        })
+    }
+
+    /// A book whose entries are kept in `storage`, with the rest of it, like opening balances,
+    /// in `dir`
+    ///
+    /// Unlike `open`, the data folder's format is left as it is.
+    pub fn with_storage(
+        dir: impl Into<PathBuf>,
+        config: Config,
+        storage: impl Storage + 'static,
+    ) -> Self {
+        Self {
+            dir: dir.into(),
+            config,
+            storage: Box::new(storage),
+            upgraded_from: None,
+        }
+    }
+
+    /// The format the data folder was in before `open` upgraded it, if it did
+    pub fn upgraded_from(&self) -> Option<u32> {
+        self.upgraded_from
+    }
+
+    /// The data folder
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The path of `month`'s bookkeeping file, which might not exist yet, nor be used if the
+    /// book isn't kept in files
+    pub fn path(&self, month: &BookkeepingFile) -> PathBuf {
+        self.dir.join(month.as_path())
+    }
+
+    /// Where `month` is kept: its bookkeeping file, or the database
+    pub fn location(&self, month: &BookkeepingFile) -> PathBuf {
+        self.storage.location(month)
+    }
+
+    /// Every month with entries, not counting archived ones, oldest first
+    pub fn months(&self) -> Result<Vec<BookkeepingFile>> {
+        self.storage.months()
+    }
+
+    /// Every entry recorded in `month`, numbered by line
+    pub fn operations(&self, month: &BookkeepingFile) -> Result<Vec<Operation>> {
+        Ok(self.storage.load(month, &self.config)?.unwrap_or_default())
+    }
+
+    /// Appends `entry` to `month`, creating its file if needed, then tells the `on_add` hook
+    /// and the `webhook` about it
+    pub fn record(&self, month: &BookkeepingFile, entry: Entry) -> Result<()> {
+        check_description(entry.description)?;
+        let record = Record::from_entry(month.as_str(), &entry);
+        self.storage.append(month, entry, &self.config)?;
+        hooks::recorded(&[record], &self.config);
+
+        Ok(())
+    }
+
+    /// Changes the entry numbered `id` in `month` with `edit`
+    pub fn edit(
+        &self,
+        month: &BookkeepingFile,
+        id: usize,
+        edit: impl FnOnce(&mut Operation) -> Result<()>,
+    ) -> Result<()> {
+        let mut edit = Some(edit);
+        self.storage
+            .edit(month, id, &self.config, &mut |operation| {
+                // Storages call it once, for the entry numbered `id`
+                edit.take().map_or(Ok(()), |edit| edit(operation))
+            })
+    }
+
+    /// Replaces every entry of `month` with `operations`, renumbering them in order
+    pub fn rewrite(&self, month: &BookkeepingFile, operations: &[Operation]) -> Result<()> {
+        self.storage.rewrite(month, operations, &self.config)
+    }
+
+    /// The totals of `month` in the default currency, compared to the previous month up to `day`
+    pub fn status(&self, month: BookkeepingFile, day: u8) -> Result<Status> {
+        self.status_in(month, day, &Converter::new(&self.config))
+    }
+
+    /// Like `status`, with amounts converted by `converter`
+    ///
+    /// Fails if nothing was recorded in `month`.
+    pub fn status_in(
+        &self,
+        month: BookkeepingFile,
+        day: u8,
+        converter: &Converter,
+    ) -> Result<Status> {
+        let operations = self
+            .storage
+            .load(&month, &self.config)?
+            .ok_or_else(|| Error::NoSuchMonth(month.as_str().to_owned()))?;
+        let previous = self.storage.load(&month.previous(), &self.config)?;
+        let opening_balance = OpeningBalances::load(&self.dir, &self.config)?
+            .get(&month)
+            .cloned();
+
+        Status::from_operations(month, operations, previous, opening_balance, day, converter)
+    }
+
+    /// The totals of every month, archived ones included, oldest first
+    ///
+    /// Quicker than `stats` when that's all that's needed, as books kept in files cache them.
+    pub fn month_totals(&self) -> Result<Vec<MonthTotal>> {
+        self.storage.totals(&self.config)
+    }
+
+    /// The totals of every month added up, from `month_totals`
+    pub fn lifetime_total(&self) -> Result<Total> {
+        Ok(lifetime_total(&self.month_totals()?))
+    }
+
+    /// Feeds every month, archived ones included, oldest first, to `each`: as the month
+    /// starts, then entry by entry
+    pub fn scan(&self, each: &mut dyn FnMut(Scanned) -> Result<()>) -> Result<()> {
+        self.storage.scan(&self.config, each)
+    }
+
+    /// Statistics over every month, archived ones included
+    ///
+    /// Entries are counted as they're read, so years of them are never all in memory at once.
+    pub fn stats(&self) -> Result<Stats> {
+        let mut tally = Tally::new(&self.config);
+        self.storage
+            .scan(&self.config, &mut |scanned| match scanned {
+                Scanned::Month(month) => {
+                    tally.month(month);
+                    Ok(())
+                }
+                Scanned::Entry(entry) => tally.add(entry),
+            })?;
+
+        Ok(tally.finish())
+    }
+
+    /// Statistics over `month`, whether it's archived or not
+    pub fn month_stats(&self, month: &BookkeepingFile) -> Result<Stats> {
+        let mut tally = Tally::new(&self.config);
+
+        if let Some(operations) = self.storage.load(month, &self.config)? {
+            tally.month(month.clone());
+            for operation in &operations {
+                tally.add(operation.as_entry())?;
+            }
+            return Ok(tally.finish());
+        }
+
+        // Only looked for among archived months when it's not in the data folder
+        let (mut found, mut counting) = (false, false);
+        self.storage
+            .scan(&self.config, &mut |scanned| match scanned {
+                Scanned::Month(scanned) => {
+                    counting = scanned.as_str() == month.as_str();
+                    if counting {
+                        found = true;
+                        tally.month(scanned);
+                    }
+                    Ok(())
+                }
+                Scanned::Entry(entry) if counting => tally.add(entry),
+                Scanned::Entry(_) => Ok(()),
+            })?;
+
+        match found {
+            true => Ok(tally.finish()),
+            false => Err(Error::NoSuchMonth(month.as_str().to_owned())),
+        }
+    }
+
+    /// Statistics over `months`, which are expected to be in order, leaving out the ones
+    /// without entries
+    pub fn period_stats(&self, months: &[BookkeepingFile]) -> Result<Stats> {
+        let mut loaded = Vec::with_capacity(months.len());
+        for month in months {
+            loaded.push(self.storage.load(month, &self.config)?);
+        }
+
+        let mut tally = Tally::new(&self.config);
+        if loaded.iter().all(Option::is_some) {
+            for (month, operations) in months.iter().zip(loaded.into_iter().flatten()) {
+                tally.month(month.clone());
+                for operation in &operations {
+                    tally.add(operation.as_entry())?;
+                }
+            }
+            return Ok(tally.finish());
+        }
+
+        // Archived months are only in the scan of the whole book
+        let mut counting = false;
+        self.storage
+            .scan(&self.config, &mut |scanned| match scanned {
+                Scanned::Month(scanned) => {
+                    counting = months
+                        .iter()
+                        .any(|month| month.as_str() == scanned.as_str());
+                    if counting {
+                        tally.month(scanned);
+                    }
+                    Ok(())
+                }
+                Scanned::Entry(entry) if counting => tally.add(entry),
+                Scanned::Entry(_) => Ok(()),
+            })?;
+
+        Ok(tally.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use tempfile::TempDir;
+
+    use super::Bookkeeper;
+    use crate::{
+        config::Config,
+        crypt::Encryption,
+        file::BookkeepingFile,
+        parser::{Entry, EntryType},
+        sqlite::{Backend, DATABASE_FILE},
+        storage::Memory,
+    };
+
+    #[test]
+    fn records_and_reads_back_entries() {
+        let dir = TempDir::new().unwrap();
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        let book = Bookkeeper::open(dir.path(), config).unwrap();
+        let january = BookkeepingFile::new(1, 2024);
+
+        book.record(
+            &january,
+            Entry::new(5, EntryType::Credit, 100.into(), "Salary"),
+        )
+        .unwrap();
+        book.record(
+            &january,
+            Entry::new(6, EntryType::Debit, 30.into(), "Groceries"),
+        )
+        .unwrap();
+        book.edit(&january, 2, |operation| {
+            operation.amount = 35.into();
+            Ok(())
+        })
+        .unwrap();
+
+        let operations = book.operations(&january).unwrap();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[1].amount, BigDecimal::from(35));
+
+        assert_eq!(book.months().unwrap().len(), 1);
+        let status = book.status(january, 31).unwrap();
+        assert_eq!(status.total.balance(), BigDecimal::from(65));
+    }
+
+    /// Records two months, edits and rewrites them, then checks what's read back
+    fn keep_entries(book: &Bookkeeper) {
+        let (december, january) = (
+            BookkeepingFile::new(12, 2023),
+            BookkeepingFile::new(1, 2024),
+        );
+
+        book.record(
+            &december,
+            Entry::new(3, EntryType::Debit, 50.into(), "Groceries"),
+        )
+        .unwrap();
+        book.record(
+            &january,
+            Entry::new(5, EntryType::Credit, 100.into(), "Salary"),
+        )
+        .unwrap();
+        book.record(
+            &january,
+            Entry::new(6, EntryType::Debit, 30.into(), "Groceries"),
+        )
+        .unwrap();
+        book.edit(&january, 2, |operation| {
+            operation.amount = 35.into();
+            Ok(())
+        })
+        .unwrap();
+
+        let months = book.months().unwrap();
+        let months: Vec<_> = months.iter().map(BookkeepingFile::as_str).collect();
+        assert_eq!(months, ["12-2023", "01-2024"]);
+
+        let operations = book.operations(&january).unwrap();
+        assert_eq!(operations[1].id, 2);
+        assert_eq!(operations[1].amount, BigDecimal::from(35));
+
+        let status = book.status(january.clone(), 31).unwrap();
+        assert_eq!(status.total.balance(), BigDecimal::from(65));
+        let stats = book.stats().unwrap();
+        assert_eq!(stats.months.len(), 2);
+        let totals = book.month_totals().unwrap();
+        assert_eq!(totals[1].total.outgoing, BigDecimal::from(35));
+        assert_eq!(
+            book.lifetime_total().unwrap().balance(),
+            stats.lifetime_total().balance()
+        );
+        assert_eq!(stats.takes.len(), 2);
+        let january_stats = book.month_stats(&january).unwrap();
+        assert_eq!(
+            january_stats.lifetime_total().balance(),
+            BigDecimal::from(65)
+        );
+
+        // Dropping the salary renumbers the groceries
+        book.rewrite(&january, &operations[1..]).unwrap();
+        let operations = book.operations(&january).unwrap();
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].id, 1);
+        assert_eq!(operations[0].description, "Groceries");
+    }
+
+    #[test]
+    fn keeps_entries_in_files() {
+        let dir = TempDir::new().unwrap();
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        let book = Bookkeeper::open(dir.path(), config).unwrap();
+
+        keep_entries(&book);
+        assert!(book.path(&BookkeepingFile::new(1, 2024)).exists());
+    }
+
+    #[test]
+    fn keeps_entries_in_a_database() {
+        let dir = TempDir::new().unwrap();
+        let config = Config {
+            storage: Backend::Sqlite,
+            ..Config::default()
+        };
+        let book = Bookkeeper::open(dir.path(), config).unwrap();
+
+        keep_entries(&book);
+        assert!(dir.path().join(DATABASE_FILE).exists());
+        assert!(!book.path(&BookkeepingFile::new(1, 2024)).exists());
+
+        let encrypted = Config {
+            storage: Backend::Sqlite,
+            encryption: Some(Encryption::Passphrase),
+            ..Config::default()
+        };
+        assert!(Bookkeeper::open(dir.path(), encrypted).is_err());
+    }
+
+    #[test]
+    fn keeps_entries_in_memory() {
+        let dir = TempDir::new().unwrap();
+        let book = Bookkeeper::with_storage(dir.path(), Config::default(), Memory::default());
+
+        keep_entries(&book);
+        assert_eq!(dir.path().read_dir().unwrap().count(), 0);
+    }
+}
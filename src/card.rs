@@ -0,0 +1,219 @@
+use std::path::{Path, PathBuf};
+
+use bigdecimal::{BigDecimal, Zero};
+use serde::Deserialize;
+
+use crate::{
+    config::Config,
+    currency::Converter,
+    export::Record,
+    file::BookkeepingFile,
+    hooks,
+    parser::{Entry, EntryType, Operation},
+    reader::Reader,
+    writer::Writer,
+    Error, Result,
+};
+
+/// Holds a folder per credit card, with its purchases kept in bookkeeping files of their own
+pub const CARDS_FOLDER: &str = "cards";
+
+/// A credit card, as written in the `[cards.NAME]` table of the config file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Card {
+    /// Day of the month the statement closes, purchases after it going into the next one
+    pub closing_day: u8,
+    /// Day of the month the statement is paid, taking it out of the account
+    pub due_day: u8,
+}
+
+impl Card {
+    /// The statement a purchase made on `day` of `month` goes into, by the month it closes in
+    pub fn statement_of(&self, month: &BookkeepingFile, day: u8) -> BookkeepingFile {
+        if day > self.closing_day {
+            month.next()
+        } else {
+            month.clone()
+        }
+    }
+
+    /// The month the statement closing in `closing` is paid in
+    pub fn due_month(&self, closing: &BookkeepingFile) -> BookkeepingFile {
+        if self.due_day > self.closing_day {
+            closing.clone()
+        } else {
+            closing.next()
+        }
+    }
+}
+
+/// The folder keeping the purchases made with the card called `name`
+pub fn card_dir(data: &Path, name: &str) -> PathBuf {
+    data.join(CARDS_FOLDER).join(name)
+}
+
+/// The purchases and refunds of a card that close on the same day
+pub struct Statement {
+    /// The month the statement closes in
+    pub closing: BookkeepingFile,
+    /// Every operation, along with the month it was made in
+    pub operations: Vec<(BookkeepingFile, Operation)>,
+    /// What's owed, in the default currency
+    pub total: BigDecimal,
+}
+
+impl Statement {
+    /// Reads the statement of the card kept in `dir` that closes in `closing`
+    pub fn read(
+        dir: &Path,
+        card: &Card,
+        closing: BookkeepingFile,
+        config: &Config,
+    ) -> Result<Self> {
+        let converter = Converter::new(config);
+        let mut reader = Reader::new(config);
+        let mut operations = Vec::new();
+        let mut total = BigDecimal::zero();
+
+        for month in [closing.previous(), closing.clone()] {
+            let path = dir.join(month.as_path());
+            if !path.exists() {
+                continue;
+            }
+
+            for operation in reader.operations(&path)? {
+                if card.statement_of(&month, operation.day).as_str() != closing.as_str() {
+                    continue;
+                }
+
+                let amount =
+                    converter.convert(operation.amount.clone(), operation.currency.as_deref())?;
+                match operation.typ {
+                    EntryType::Debit => total += amount,
+                    EntryType::Credit | EntryType::Refund => total -= amount,
+                }
+                operations.push((month.clone(), operation));
+            }
+        }
+
+        Ok(Self {
+            closing,
+            operations,
+            total,
+        })
+    }
+
+    /// Description of the take paying the statement of the card called `name`
+    pub fn description(&self, name: &str) -> String {
+        format!("Card {} statement {}", name, self.closing.as_str())
+    }
+
+    /// Records the statement as a single take from the account on the card's due date,
+    /// returning the month it was recorded in
+    ///
+    /// The purchases stay in the card's files, for the detail.
+    pub fn close(
+        &self,
+        data: &Path,
+        name: &str,
+        card: &Card,
+        config: &Config,
+    ) -> Result<BookkeepingFile> {
+        let due = card.due_month(&self.closing);
+        let path = data.join(due.as_path());
+        let description = self.description(name);
+
+        if path.exists() {
+            let operations = Reader::new(config).operations(&path)?;
+            if operations
+                .iter()
+                .any(|operation| operation.description == description)
+            {
+                return Err(Error::Card(format!("{} was already paid", description)));
+            }
+        }
+        if self.total <= BigDecimal::zero() {
+            return Err(Error::Card(format!("{} owes nothing", description)));
+        }
+
+        let day = card.due_day.min(due.days());
+        let entry = Entry::new(day, EntryType::Debit, self.total.clone(), &description);
+        let record = Record::from_entry(due.as_str(), &entry);
+        Writer::write_entry(&path, entry, config)?;
+        hooks::recorded(&[record], config);
+
+        Ok(due)
+    }
+
+    pub fn print(&self, name: &str, config: &Config) {
+        println!("Statement of {} closing in {}", name, self.closing.as_str());
+
+        for (month, operation) in &self.operations {
+            let sign = operation.typ.sign();
+            let currency = operation.currency.as_deref().unwrap_or(&config.currency);
+
+            println!(
+                "\t{:02}/{:02} {} {} {}",
+                operation.day,
+                month.month(),
+                sign,
+                config.format_amount_in(&operation.amount, currency),
+                operation.description
+            );
+        }
+        println!("\tTotal: {}", config.format_amount(&self.total));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{card_dir, Card, Statement};
+    use crate::{config::Config, file::BookkeepingFile, reader::Reader};
+
+    #[test]
+    fn closes_statements_into_one_take() {
+        let data = TempDir::new().unwrap();
+        let dir = card_dir(data.path(), "nubank");
+        fs::create_dir_all(&dir).unwrap();
+        // Closes on the 3rd, so purchases from September 4th to October 3rd are paid together
+        fs::write(dir.join("09-2024"), "02 - 10 Old\n20 - 100 Dinner\n").unwrap();
+        fs::write(
+            dir.join("10-2024"),
+            "01 - 50 Books\n02 + 20 Refund\n15 - 7 Next\n",
+        )
+        .unwrap();
+
+        let card = Card {
+            closing_day: 3,
+            due_day: 10,
+        };
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        let october = BookkeepingFile::new(10, 2024);
+        let statement = Statement::read(&dir, &card, october, &config).unwrap();
+        assert_eq!(statement.operations.len(), 3);
+        assert_eq!(statement.total, BigDecimal::from(130));
+
+        let due = statement
+            .close(data.path(), "nubank", &card, &config)
+            .unwrap();
+        assert_eq!(due.as_str(), "10-2024");
+        let operations = Reader::new(&config)
+            .operations(data.path().join("10-2024"))
+            .unwrap();
+        assert_eq!(operations[0].day, 10);
+        assert_eq!(operations[0].description, "Card nubank statement 10-2024");
+
+        // Paying it twice would be a mistake
+        assert!(statement
+            .close(data.path(), "nubank", &card, &config)
+            .is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use std::{path::Path, str};
+
+use crate::{
+    config::Config, crypt, file::BookkeepingFile, parser::Entry, rules::RuleSet, writer::Writer,
+    Result,
+};
+
+/// What `categorize_file` found in a bookkeeping file
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Categorized {
+    /// Entries a rule gave a tag to
+    pub tagged: usize,
+    /// Entries without a tag that no rule matches
+    pub uncategorized: usize,
+}
+
+/// Tags every entry of `file` that has no `#tag` yet but matches a rule, printing each of them
+///
+/// The file is only changed if `apply` is set.
+pub fn categorize_file(
+    dir: &Path,
+    file: &BookkeepingFile,
+    rules: &RuleSet,
+    config: &Config,
+    apply: bool,
+) -> Result<Categorized> {
+    let path = dir.join(file.as_path());
+    let contents = crypt::read_file(&path, config.cipher.as_ref())?;
+    let contents = str::from_utf8(&contents)?;
+
+    let mut categorized = Categorized::default();
+    let mut output = String::with_capacity(contents.len());
+
+    for (idx, line) in contents.lines().enumerate() {
+        let entry = Entry::from_str(line, config.locale)?;
+
+        match rules.tag(entry.description) {
+            Some(tagged) => {
+                println!("{}:{} {}", file.as_str(), idx + 1, tagged);
+                categorized.tagged += 1;
+
+                // The rest of the line is kept as it was written
+                let kept = line
+                    .trim_end()
+                    .strip_suffix(entry.description)
+                    .unwrap_or(line);
+                output.push_str(&format!("{}{}\n", kept, tagged));
+            }
+            None => {
+                if rules.categorize(entry.description).is_none() {
+                    categorized.uncategorized += 1;
+                }
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+    }
+
+    if apply && categorized.tagged > 0 {
+        Writer::replace_contents(&path, output.into_bytes(), config)?;
+    }
+
+    Ok(categorized)
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{categorize_file, Categorized};
+    use crate::{config::Config, file::BookkeepingFile, rules::RuleSet};
+
+    #[test]
+    fn tags_old_entries() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01-2024");
+        fs::write(
+            &path,
+            "05 - 20 UBER TRIP\n06 - 12 Coffee\n07 - 9 99 app #taxi\n",
+        )
+        .unwrap();
+
+        let config: Config = toml::from_str(
+            r#"
+            backups = 0
+
+            [[rules]]
+            match = "uber|99"
+            category = "transport"
+            "#,
+        )
+        .unwrap();
+        let rules = RuleSet::from_config(&config);
+        let file = BookkeepingFile::new(1, 2024);
+
+        let expected = Categorized {
+            tagged: 1,
+            uncategorized: 1,
+        };
+        assert_eq!(
+            categorize_file(dir.path(), &file, &rules, &config, false).unwrap(),
+            expected
+        );
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("05 - 20 UBER TRIP\n"));
+
+        categorize_file(dir.path(), &file, &rules, &config, true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "05 - 20 UBER TRIP #transport\n06 - 12 Coffee\n07 - 9 99 app #taxi\n"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use std::{ffi::OsStr, fmt::Display, path::Path};
+
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use chrono::{Datelike, Duration, NaiveDate};
+use plotters::{coord::Shift, prelude::*};
+
+use crate::{
+    config::Config,
+    currency::Converter,
+    dates::first_date,
+    file::StoredFile,
+    i18n::tf,
+    parser::EntryType,
+    reader::Reader,
+    stats::{MonthTotal, Stats},
+    Error, Result,
+};
+
+/// How many characters the longest bar takes
+const WIDTH: usize = 40;
+
+/// Size of the images drawn by `draw`, in pixels
+const SIZE: (u32, u32) = (1200, 500);
+
+/// Partial blocks, from one to seven eighths of a character
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// How much was spent on each day of a month
+pub struct DailySpending {
+    /// Spending of the first day of the month onwards, in the default currency
+    pub days: Vec<BigDecimal>,
+}
+
+impl DailySpending {
+    pub fn compute(stored: &StoredFile, config: &Config) -> Result<Self> {
+        let converter = Converter::new(config);
+        let mut days = vec![BigDecimal::zero(); stored.file.days().into()];
+
+        Reader::new(config).for_each_entry_in(stored.open()?, |entry| {
+            let day = usize::from(entry.day).checked_sub(1);
+            // Days outside of the month are left for `doctor` to point out
+            if let (EntryType::Debit, Some(spent)) =
+                (entry.typ, day.and_then(|day| days.get_mut(day)))
+            {
+                *spent += converter.convert(entry.amount, entry.currency)?;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(Self { days })
+    }
+
+    pub fn print(&self, stored: &StoredFile, config: &Config) {
+        println!("{}", tf("Spending per day in {}", &[&stored.file.label()]));
+
+        let max = self.days.iter().max().cloned().unwrap_or_default();
+        for (date, spent) in self.in_order(first_date(&stored.file)) {
+            match spent.filter(|spent| !spent.is_zero()) {
+                Some(spent) => {
+                    let amount = config.format_amount(spent);
+                    println!("\t{:02} {} {}", date.day(), bar(spent, &max, WIDTH), amount);
+                }
+                None => println!("\t{:02}", date.day()),
+            }
+        }
+    }
+
+    /// Prints the month as a calendar, each day shaded by how much was spent on it
+    pub fn print_heatmap(&self, stored: &StoredFile, config: &Config) {
+        println!("{}", tf("Spending per day in {}", &[&stored.file.label()]));
+        println!("\tMon  Tue  Wed  Thu  Fri  Sat  Sun");
+
+        for week in self.weeks(first_date(&stored.file)) {
+            println!("\t{}", week);
+        }
+
+        let max = self.days.iter().max().cloned().unwrap_or_default();
+        let quarter = &max / BigDecimal::from(4);
+        for (level, shade) in SHADES.iter().enumerate().skip(1) {
+            let limit = (&quarter * BigDecimal::from(level as u64)).with_scale(2);
+            println!(
+                "\t{}{} up to {}",
+                shade,
+                shade,
+                config.format_amount(&limit)
+            );
+        }
+    }
+
+    /// Every day of the budget month starting on `first`, in order, with what was spent on it
+    ///
+    /// Days before the one months start on come last, as they're of the next month.
+    fn in_order(&self, first: NaiveDate) -> Vec<(NaiveDate, Option<&BigDecimal>)> {
+        (0..self.days.len())
+            .map(|offset| {
+                let date = first + Duration::days(offset as i64);
+                (date, self.days.get(date.day0() as usize))
+            })
+            .collect()
+    }
+
+    /// The rows of the calendar of the budget month starting on `first`, a week each
+    fn weeks(&self, first: NaiveDate) -> Vec<String> {
+        let max = self.days.iter().max().cloned().unwrap_or_default();
+        let padding = first.weekday().num_days_from_monday() as usize;
+
+        let mut weeks = Vec::new();
+        let mut row = "     ".repeat(padding);
+        for (offset, (date, spent)) in self.in_order(first).into_iter().enumerate() {
+            let shade = spent.map_or(SHADES[0], |spent| SHADES[shade(spent, &max)]);
+            row.push_str(&format!("{:02}{}{} ", date.day(), shade, shade));
+
+            if (padding + offset + 1).is_multiple_of(7) {
+                weeks.push(row.trim_end().to_owned());
+                row.clear();
+            }
+        }
+        if !row.is_empty() {
+            weeks.push(row.trim_end().to_owned());
+        }
+
+        weeks
+    }
+}
+
+/// How much was spent on a day, from nothing to the most spent on any day of the month
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Which of `SHADES` a day on which `amount` was spent gets, `max` being the darkest
+pub fn shade(amount: &BigDecimal, max: &BigDecimal) -> usize {
+    if amount.is_zero() || max.is_zero() {
+        return 0;
+    }
+
+    let quarters = (amount * BigDecimal::from(4) / max)
+        .with_scale(0)
+        .to_usize()
+        .unwrap_or_default();
+
+    // Spending anything at all shows, and spending `max` is still the darkest
+    (quarters + 1).min(SHADES.len() - 1)
+}
+
+/// A bar `width` characters long when `amount` is `max`, and proportionally shorter otherwise
+pub fn bar(amount: &BigDecimal, max: &BigDecimal, width: usize) -> String {
+    if max.is_zero() {
+        return String::new();
+    }
+
+    let eighths = (amount * BigDecimal::from(width as u64 * 8) / max)
+        .with_scale(0)
+        .to_usize()
+        .unwrap_or_default();
+
+    let mut bar = "█".repeat(eighths / 8);
+    match eighths % 8 {
+        // Spending something always shows up, even if it's tiny next to `max`
+        0 if eighths == 0 => bar.push(EIGHTHS[0]),
+        0 => {}
+        rest => bar.push(EIGHTHS[rest - 1]),
+    }
+
+    bar
+}
+
+/// Draws the spending per category of `month` as a pie chart, next to the incoming and
+/// outgoing of every month in `months` as lines, into an SVG or PNG image at `path`
+pub fn draw(path: &Path, month: &Stats, months: &[MonthTotal], config: &Config) -> Result<()> {
+    match path.extension().and_then(OsStr::to_str) {
+        Some("svg") => draw_on(
+            SVGBackend::new(path, SIZE).into_drawing_area(),
+            month,
+            months,
+            config,
+        ),
+        Some("png") => draw_on(
+            BitMapBackend::new(path, SIZE).into_drawing_area(),
+            month,
+            months,
+            config,
+        ),
+        _ => Err(Error::Chart(format!(
+            "{} should end in .svg or .png",
+            path.display()
+        ))),
+    }
+}
+
+fn draw_on<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    month: &Stats,
+    months: &[MonthTotal],
+    config: &Config,
+) -> Result<()> {
+    root.fill(&WHITE).map_err(chart_error)?;
+    let (left, right) = root.split_horizontally(SIZE.0 / 3);
+
+    draw_categories(&left, month)?;
+    draw_trend(&right, months, config)?;
+
+    root.present().map_err(chart_error)
+}
+
+/// A pie chart of how much each category took of the spending
+fn draw_categories<DB: DrawingBackend>(area: &DrawingArea<DB, Shift>, month: &Stats) -> Result<()> {
+    let title = match month.months.first() {
+        Some(first) => format!("Spending in {}", first.file.label()),
+        None => "Spending".to_owned(),
+    };
+    let area = area
+        .titled(&title, ("sans-serif", 24))
+        .map_err(chart_error)?;
+
+    let spending = month.spending_by_category();
+    if spending.is_empty() {
+        let center = area.get_pixel_range();
+        let position = (center.0.start + 20, center.1.start + 20);
+        return area
+            .draw_text(
+                "Nothing was spent",
+                &("sans-serif", 18).into_text_style(&area),
+                position,
+            )
+            .map_err(chart_error);
+    }
+
+    let (width, height) = area.dim_in_pixel();
+    let center = (width as i32 / 2, height as i32 / 2);
+    let radius = f64::from(width.min(height)) * 0.35;
+    let sizes: Vec<f64> = spending.iter().map(|(_, amount)| to_f64(amount)).collect();
+    let labels: Vec<&str> = spending.iter().map(|(category, _)| *category).collect();
+    let colors: Vec<RGBColor> = (0..spending.len())
+        .map(|idx| {
+            let (r, g, b) = Palette99::pick(idx).rgb();
+            RGBColor(r, g, b)
+        })
+        .collect();
+
+    let mut pie = Pie::new(&center, &radius, &sizes, &colors, &labels);
+    pie.label_style(("sans-serif", 14));
+    pie.percentages(("sans-serif", 12).into_font().color(&WHITE));
+    area.draw(&pie).map_err(chart_error)
+}
+
+/// Lines of the incoming and outgoing of each month
+fn draw_trend<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    months: &[MonthTotal],
+    config: &Config,
+) -> Result<()> {
+    let incoming: Vec<f64> = months
+        .iter()
+        .map(|month| to_f64(&month.total.incoming))
+        .collect();
+    let outgoing: Vec<f64> = months
+        .iter()
+        .map(|month| to_f64(&month.total.outgoing))
+        .collect();
+    let highest = incoming
+        .iter()
+        .chain(&outgoing)
+        .copied()
+        .fold(1.0, f64::max);
+    let last = months.len().saturating_sub(1).max(1);
+
+    let mut chart = ChartBuilder::on(area)
+        .caption("Incoming and outgoing per month", ("sans-serif", 24))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(90)
+        .build_cartesian_2d(0..last, 0.0..highest * 1.1)
+        .map_err(chart_error)?;
+
+    chart
+        .configure_mesh()
+        .x_labels(months.len().min(12))
+        .x_label_formatter(&|idx| {
+            months
+                .get(*idx)
+                .map(|month| month.file.label())
+                .unwrap_or_default()
+        })
+        .y_label_formatter(&|amount| config.format_amount(&BigDecimal::from(*amount as i64)))
+        .draw()
+        .map_err(chart_error)?;
+
+    for (label, values, color) in [("Incoming", &incoming, GREEN), ("Outgoing", &outgoing, RED)] {
+        chart
+            .draw_series(LineSeries::new(
+                values.iter().copied().enumerate(),
+                color.stroke_width(2),
+            ))
+            .map_err(chart_error)?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(chart_error)
+}
+
+fn to_f64(amount: &BigDecimal) -> f64 {
+    amount.to_f64().unwrap_or_default()
+}
+
+fn chart_error(err: impl Display) -> Error {
+    Error::Chart(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::{Datelike, NaiveDate};
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{bar, draw, shade, DailySpending};
+    use crate::{
+        archive::list_stored_files,
+        config::Config,
+        file::{BookkeepingFile, StoredFile},
+        stats::Stats,
+    };
+
+    #[test]
+    fn draws_proportional_bars() {
+        let max = BigDecimal::from(100);
+
+        assert_eq!(bar(&max, &max, 4), "████");
+        assert_eq!(bar(&BigDecimal::from(50), &max, 4), "██");
+        assert_eq!(bar(&BigDecimal::from(30), &max, 4), "█▏");
+        assert_eq!(bar(&BigDecimal::from(1), &max, 4), "▏");
+    }
+
+    #[test]
+    fn places_days_under_their_weekday() {
+        // February 2024 starts on a Thursday
+        let spending = DailySpending {
+            days: vec![BigDecimal::from(0); 29],
+        };
+        let weeks = spending.weeks(NaiveDate::from_ymd(2024, 2, 1));
+        assert_eq!(weeks[0], format!("{}01   02   03   04", " ".repeat(15)));
+        assert_eq!(weeks.last().unwrap(), "26   27   28   29");
+
+        // January 5th 2024 is a Friday, and the month runs up to February 4th
+        let mut days = vec![BigDecimal::from(0); 31];
+        days[4] = BigDecimal::from(10);
+        let spending = DailySpending { days };
+        let first = NaiveDate::from_ymd(2024, 1, 5);
+        let weeks = spending.weeks(first);
+        assert_eq!(weeks[0], format!("{}05██ 06   07", " ".repeat(20)));
+        assert_eq!(weeks.last().unwrap(), "29   30   31   01   02   03   04");
+
+        let days: Vec<_> = spending
+            .in_order(first)
+            .iter()
+            .map(|(date, _)| date.day())
+            .collect();
+        assert_eq!(days[..2], [5, 6]);
+        assert_eq!(days[26..], [31, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn draws_images() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 100 Salary\n06 - 30 Rent\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("02-2024"), "05 + 100 Salary\n").unwrap();
+
+        let config = Config::default();
+        let book = Stats::from_files(list_stored_files(dir.path()).unwrap(), &config).unwrap();
+        let january = StoredFile::in_data(dir.path(), BookkeepingFile::new(1, 2024));
+        let month = Stats::from_files(vec![january], &config).unwrap();
+
+        let svg = dir.path().join("chart.svg");
+        draw(&svg, &month, &book.months, &config).unwrap();
+        assert!(fs::read_to_string(&svg).unwrap().contains("Rent"));
+
+        assert!(draw(&dir.path().join("chart.txt"), &month, &book.months, &config).is_err());
+    }
+
+    #[test]
+    fn shades_days_by_spending() {
+        let max = BigDecimal::from(100);
+
+        assert_eq!(shade(&BigDecimal::from(0), &max), 0);
+        assert_eq!(shade(&BigDecimal::from(1), &max), 1);
+        assert_eq!(shade(&BigDecimal::from(50), &max), 3);
+        assert_eq!(shade(&max, &max), 4);
+    }
+}
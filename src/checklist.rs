@@ -0,0 +1,183 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    file::BookkeepingFile,
+    i18n::{is_yes, t},
+    sidecar, Error, Result,
+};
+
+/// Which steps were done in each month, kept in the data folder
+pub const CHECKLIST_FILE: &str = ".porquinho-checklist.toml";
+
+/// A step of the monthly closing, as written in the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    pub name: String,
+    /// porquinho arguments run by `checklist run`, e.g. `export json --output backup.json`
+    pub command: Option<String>,
+}
+
+/// The steps used when config.toml has none
+pub fn default_steps() -> Vec<Step> {
+    let step = |name: &str, command: Option<&str>| Step {
+        name: name.to_owned(),
+        command: command.map(str::to_owned),
+    };
+
+    vec![
+        step("Import bank statements", None),
+        step("Check the book for problems", Some("doctor")),
+        step("Review uncategorized spending", Some("stats")),
+        step("Back up the book", Some("selftest")),
+        step("Carry the balance into the next month", Some("close")),
+    ]
+}
+
+/// Names of the steps done in each month, keyed by `MM-YYYY`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Progress {
+    #[serde(flatten)]
+    months: BTreeMap<String, Vec<String>>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Progress {
+    pub fn load(dir: &Path, config: &Config) -> Result<Self> {
+        let path = dir.join(CHECKLIST_FILE);
+
+        let mut progress: Self = sidecar::load(&path, config, Error::Checklist)?;
+        progress.path = path;
+
+        Ok(progress)
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        sidecar::save(&self.path, self, config, Error::Checklist)
+    }
+
+    pub fn is_done(&self, month: &BookkeepingFile, step: &Step) -> bool {
+        self.months
+            .get(month.as_str())
+            .is_some_and(|done| done.contains(&step.name))
+    }
+
+    pub fn set_done(&mut self, month: &BookkeepingFile, step: &Step, done: bool) {
+        let steps = self.months.entry(month.as_str().to_owned()).or_default();
+        steps.retain(|name| *name != step.name);
+        if done {
+            steps.push(step.name.clone());
+        }
+    }
+}
+
+/// Prints every step, marking the ones done in `month`
+pub fn print_checklist(steps: &[Step], progress: &Progress, month: &BookkeepingFile) {
+    println!("Closing checklist for {}", month.as_str());
+
+    for (idx, step) in steps.iter().enumerate() {
+        let mark = if progress.is_done(month, step) {
+            'x'
+        } else {
+            ' '
+        };
+        let command = step
+            .command
+            .as_deref()
+            .map(|command| format!(" (porquinho {})", command))
+            .unwrap_or_default();
+
+        println!("\t{}. [{}] {}{}", idx + 1, mark, step.name, command);
+    }
+}
+
+/// Goes through every step not yet done in `month`, running its command and
+/// asking whether it's done
+///
+/// Commands run with `options` before them, the global ones porquinho was given, such as
+/// `--profile` or `--dry-run`. In a dry run, nothing is marked as done.
+pub fn run_checklist(
+    steps: &[Step],
+    progress: &mut Progress,
+    month: &BookkeepingFile,
+    mut input: impl BufRead,
+    options: &[String],
+    config: &Config,
+) -> Result<()> {
+    let dry_run = config.dry_run;
+    for (idx, step) in steps.iter().enumerate() {
+        if progress.is_done(month, step) {
+            continue;
+        }
+
+        println!("{}. {}", idx + 1, step.name);
+        if let Some(command) = &step.command {
+            println!("$ porquinho {}", command);
+            // Steps run as separate processes, so one failing doesn't end the checklist
+            let status = Command::new(std::env::current_exe()?)
+                .args(options)
+                .args(command.split_whitespace())
+                .status()?;
+            if !status.success() {
+                println!("The command failed");
+            }
+        }
+
+        print!("{} ", t("Done? [y/N/q]"));
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        match answer.trim() {
+            answer if is_yes(answer) => {
+                progress.set_done(month, step, true);
+                if !dry_run {
+                    progress.save(config)?;
+                }
+            }
+            "q" | "Q" => break,
+            _ => {}
+        }
+    }
+
+    print_checklist(steps, progress, month);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::{default_steps, Progress};
+    use crate::{config::Config, file::BookkeepingFile};
+
+    #[test]
+    fn tracks_progress_per_month() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::default();
+        let steps = default_steps();
+        let october = BookkeepingFile::new(10, 2023);
+        let november = BookkeepingFile::new(11, 2023);
+
+        let mut progress = Progress::load(dir.path(), &config).unwrap();
+        progress.set_done(&october, &steps[0], true);
+        progress.set_done(&october, &steps[1], true);
+        progress.set_done(&october, &steps[1], false);
+        progress.save(&config).unwrap();
+
+        let progress = Progress::load(dir.path(), &config).unwrap();
+        assert!(progress.is_done(&october, &steps[0]));
+        assert!(!progress.is_done(&october, &steps[1]));
+        assert!(!progress.is_done(&november, &steps[0]));
+    }
+}
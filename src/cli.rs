@@ -1,5 +1,13 @@
+use std::path::PathBuf;
+
 use bigdecimal::BigDecimal;
-use clap::Parser;
+use std::mem;
+
+use clap::{ArgGroup, Command, CommandFactory, FromArgMatches, Parser};
+
+use porquinho::{
+    color::ColorChoice, export::Format, i18n::Language, importers::App, report, status,
+};
 
 #[derive(Parser, Debug)]
 #[clap(about, version)]
@@ -9,26 +17,1559 @@ use clap::Parser;
 pub struct Opts {
     #[clap(subcommand)]
     pub cmd: Subcommand,
+
+    /// Use the separate book of this profile, created with `porquinho profile create`
+    ///
+    /// Given before the command, as in `porquinho --profile business status`, as `import csv`
+    /// has a --profile of its own.
+    #[clap(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Run against a throwaway copy of the data folder, leaving the real one untouched
+    #[clap(long, global = true)]
+    pub ephemeral: bool,
+
+    /// Show what a command would change in the book, without changing anything
+    #[clap(long, global = true)]
+    pub dry_run: bool,
+
+    /// Act as if today were this date, to backfill past months: YYYY-MM-DD, yesterday, or a
+    /// weekday such as monday
+    #[clap(long, global = true, value_name = "DATE")]
+    pub today: Option<String>,
+
+    /// Don't ask before recording amounts above `confirm_above`
+    #[clap(long, short, global = true)]
+    pub yes: bool,
+
+    /// Color the output: always, never, or only when printing to a terminal and `NO_COLOR`
+    /// isn't set
+    #[clap(
+        long,
+        global = true,
+        arg_enum,
+        default_value = "auto",
+        value_name = "WHEN"
+    )]
+    pub color: ColorChoice,
+
+    /// Print nothing besides what was asked for, leaving out messages like "Updated ..."
+    #[clap(long, short, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Also log which files are read and written, and how their lines are parsed
+    #[clap(long, short, global = true)]
+    pub verbose: bool,
+
+    /// Silence a warning, by its code (e.g. P101)
+    #[clap(long, global = true, multiple_occurrences = true, value_name = "CODE")]
+    pub allow: Vec<String>,
+
+    /// Turn a warning into an error, by its code, or every warning with `warnings`
+    #[clap(long, global = true, multiple_occurrences = true, value_name = "CODE")]
+    pub deny: Vec<String>,
+}
+
+impl Opts {
+    /// Parses the arguments, with the help text in `language`
+    pub fn parse_in(language: Language) -> Self {
+        let command = translate(Self::command(), language);
+        Self::from_arg_matches(&command.get_matches()).unwrap_or_else(|err| err.exit())
+    }
+
+    /// The options given that aren't the command's, for running porquinho again with them
+    pub fn global_options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+
+        if let Some(profile) = &self.profile {
+            options.extend(["--profile".to_owned(), profile.clone()]);
+        }
+        if let Some(today) = &self.today {
+            options.extend(["--today".to_owned(), today.clone()]);
+        }
+        for (given, flag) in [
+            (self.ephemeral, "--ephemeral"),
+            (self.dry_run, "--dry-run"),
+            (self.yes, "--yes"),
+            (self.quiet, "--quiet"),
+            (self.verbose, "--verbose"),
+        ] {
+            if given {
+                options.push(flag.to_owned());
+            }
+        }
+        for code in &self.allow {
+            options.extend(["--allow".to_owned(), code.clone()]);
+        }
+        for code in &self.deny {
+            options.extend(["--deny".to_owned(), code.clone()]);
+        }
+
+        options
+    }
+}
+
+/// Translates the summary of `command` and of its options, then does the same for its
+/// subcommands
+///
+/// Longer descriptions are left out once translated, as they'd be shown in English instead.
+fn translate(mut command: Command<'static>, language: Language) -> Command<'static> {
+    if let Some(about) = command.get_about() {
+        let translated = language.text(about);
+        if translated != about {
+            command = command.about(translated).long_about(None);
+        }
+    }
+
+    // The ones clap adds itself, like `--help`, can't be changed
+    let args: Vec<_> = command
+        .get_arguments()
+        .filter(|arg| !matches!(arg.get_id(), "help" | "version"))
+        .filter_map(|arg| Some((arg.get_id(), arg.get_help()?)))
+        .collect();
+    for (id, help) in args {
+        let translated = language.text(help);
+        if translated != help {
+            command = command.mut_arg(id, |arg| arg.help(translated).long_help(None));
+        }
+    }
+
+    for subcommand in command.get_subcommands_mut() {
+        *subcommand = translate(mem::take(subcommand), language);
+    }
+
+    command
+}
+
+#[derive(Parser, PartialEq, Eq, Debug)]
+pub enum ChecklistAction {
+    /// Go through the steps not done yet, running their commands
+    Run,
+    /// Mark a step as done, by its number
+    Done { step: usize },
+    /// Mark a step as not done, by its number
+    Undo { step: usize },
+}
+
+#[derive(Parser, PartialEq, Eq, Debug)]
+pub enum GoalAction {
+    /// Start saving for something
+    Add {
+        #[clap(required = true)]
+        name: String,
+
+        /// How much it takes
+        #[clap(required = true)]
+        target: String,
+
+        /// The month to reach it by, in the MM-YYYY format
+        #[clap(long, required = true)]
+        by: String,
+    },
+    /// Put money into a goal, found by its name or part of it
+    Put {
+        #[clap(required = true)]
+        name: String,
+
+        #[clap(required = true)]
+        amount: String,
+    },
+}
+
+#[derive(Parser, PartialEq, Eq, Debug)]
+pub enum ProfileAction {
+    /// List every profile, marking the one in use, with the balance of each in its own currency
+    List,
+    /// Start a profile, with its own data and config folders
+    Create {
+        #[clap(required = true)]
+        name: String,
+    },
+}
+
+#[derive(Parser, PartialEq, Eq, Debug)]
+pub enum BillAction {
+    /// Add a bill due every month
+    Add {
+        #[clap(required = true)]
+        name: String,
+
+        /// How much it usually costs
+        #[clap(required = true)]
+        amount: String,
+
+        /// Day of the month it's due
+        #[clap(long, required = true)]
+        due: u8,
+    },
+    /// Stop reminding of a bill
+    Remove {
+        #[clap(required = true)]
+        name: String,
+    },
+}
+
+#[derive(Parser, PartialEq, Eq, Debug)]
+pub enum CardAction {
+    /// Record a purchase made with a card
+    Take {
+        #[clap(required = true)]
+        card: String,
+
+        #[clap(required = true)]
+        amount: String,
+
+        #[clap(required = true)]
+        description: String,
+
+        /// Currency of the amount, if not the default one (e.g. USD)
+        #[clap(long)]
+        currency: Option<String>,
+    },
+    /// Record a refund to a card
+    Put {
+        #[clap(required = true)]
+        card: String,
+
+        #[clap(required = true)]
+        amount: String,
+
+        #[clap(required = true)]
+        description: String,
+
+        /// Currency of the amount, if not the default one (e.g. USD)
+        #[clap(long)]
+        currency: Option<String>,
+    },
+    /// Pay a card's statement, recording it in the book as a single take on its due day
+    Close {
+        #[clap(required = true)]
+        card: String,
+
+        /// The month the statement closes in, in the MM-YYYY format (defaults to the current
+        /// one)
+        #[clap(long)]
+        month: Option<String>,
+    },
 }
 
 #[derive(Parser, PartialEq, Eq, Debug)]
 pub enum Subcommand {
+    /// Set up starter categories and budgets in config.toml
+    #[clap(after_help = INIT_EXAMPLES)]
+    Init,
     /// Record a debit transaction from your account
+    #[clap(after_help = TAKE_EXAMPLES)]
     Take {
         #[clap(required = true)]
-        amount: BigDecimal,
+        amount: String,
 
         #[clap(required = true)]
         description: String,
+
+        /// Currency of the amount, if not the default one (e.g. USD)
+        #[clap(long)]
+        currency: Option<String>,
+
+        /// When it happened, if not today: YYYY-MM-DD, yesterday, or a weekday such as monday
+        #[clap(long)]
+        date: Option<String>,
+
+        /// Day of the current month it happened, if not today
+        #[clap(long, conflicts_with = "date")]
+        day: Option<u8>,
+
+        /// Who it's from, in a shared book, added to the description as `@person`
+        #[clap(long, value_name = "PERSON")]
+        by: Option<String>,
     },
     /// Record a new credit to your account
+    #[clap(after_help = PUT_EXAMPLES)]
     Put {
         #[clap(required = true)]
-        amount: BigDecimal,
+        amount: String,
+
+        #[clap(required = true)]
+        description: String,
+
+        /// Currency of the amount, if not the default one (e.g. USD)
+        #[clap(long)]
+        currency: Option<String>,
+
+        /// When it happened, if not today: YYYY-MM-DD, yesterday, or a weekday such as monday
+        #[clap(long)]
+        date: Option<String>,
+
+        /// Day of the current month it happened, if not today
+        #[clap(long, conflicts_with = "date")]
+        day: Option<u8>,
+
+        /// Who it's from, in a shared book, added to the description as `@person`
+        #[clap(long, value_name = "PERSON")]
+        by: Option<String>,
+    },
+    /// Record money given back for something taken, which lowers the spending instead of
+    /// counting as income
+    #[clap(after_help = REFUND_EXAMPLES)]
+    Refund {
+        #[clap(required = true)]
+        amount: String,
 
         #[clap(required = true)]
         description: String,
+
+        /// Currency of the amount, if not the default one (e.g. USD)
+        #[clap(long)]
+        currency: Option<String>,
+
+        /// When it happened, if not today: YYYY-MM-DD, yesterday, or a weekday such as monday
+        #[clap(long)]
+        date: Option<String>,
+
+        /// Day of the current month it happened, if not today
+        #[clap(long, conflicts_with = "date")]
+        day: Option<u8>,
+
+        /// Who it's from, in a shared book, added to the description as `@person`
+        #[clap(long, value_name = "PERSON")]
+        by: Option<String>,
     },
     /// Current status for your
-    Status,
+    #[clap(after_help = STATUS_EXAMPLES)]
+    Status {
+        /// Also list every entry of the month
+        #[clap(short, long)]
+        complete: bool,
+
+        /// List every entry with only these columns, in this order
+        ///
+        /// Any of id (or op), day, amount, balance and description, separated by commas.
+        #[clap(
+            long,
+            arg_enum,
+            use_value_delimiter = true,
+            require_value_delimiter = true
+        )]
+        columns: Vec<status::Column>,
+
+        /// Show every amount converted into this currency (e.g. USD)
+        #[clap(long)]
+        convert: Option<String>,
+
+        /// List every entry sorted by this, instead of by day
+        #[clap(long, arg_enum)]
+        sort: Option<status::SortKey>,
+
+        /// Sort from the largest to the smallest, or from the last day
+        #[clap(long, requires = "sort")]
+        desc: bool,
+
+        /// Merge the entries with the same description, or category, into one row each
+        #[clap(long, arg_enum, conflicts_with_all = &["columns", "sort"])]
+        group_by: Option<status::GroupBy>,
+
+        /// Also show how much each person put in and spent, by the `@person` of each entry
+        #[clap(long)]
+        by_person: bool,
+
+        /// Print as text, as markdown tables to paste into notes, or as plain tab-separated
+        /// lines for scripts
+        ///
+        /// Plain output has an operation per line, with the month, day, number, kind, amount,
+        /// currency and description, and never changes between versions. With --group-by or
+        /// --by-person, it has their rows instead.
+        #[clap(long, arg_enum, default_value = "text")]
+        format: status::Format,
+
+        /// Show this month instead of the current one, as MM-YYYY, YYYY-MM or `last`
+        #[clap(long)]
+        month: Option<String>,
+
+        /// Keep showing the status, redrawing it whenever a file in the data folder changes
+        #[clap(short, long)]
+        watch: bool,
+    },
+    /// Write a report of a month, with every entry and the spending of each category
+    ///
+    /// With --auto, meant to be run by cron, writes the report of the month that just ended
+    /// into the reports folder, unless it's already there. It prints nothing, and exits with
+    /// 0 once the report is there, or if that month has no entries, and with 127 otherwise.
+    #[clap(after_help = REPORT_EXAMPLES)]
+    Report {
+        /// The month to report, as MM-YYYY, YYYY-MM or `last` (defaults to the current one)
+        #[clap(long)]
+        month: Option<String>,
+
+        /// Write the report into this file, instead of printing it
+        #[clap(long, short)]
+        output: Option<PathBuf>,
+
+        #[clap(long, arg_enum, default_value = "markdown")]
+        format: report::Format,
+
+        /// Report last month into the reports folder, silently
+        #[clap(long, conflicts_with_all = &["month", "output"])]
+        auto: bool,
+    },
+    /// Project the balance this month will end with, from the spending so far
+    ///
+    /// Entries of last month that weren't recorded again yet, like bills, are expected to
+    /// happen again
+    #[clap(after_help = FORECAST_EXAMPLES)]
+    Forecast,
+    /// Print just the balance of this month, as a bare number, for status bars
+    #[clap(after_help = BALANCE_EXAMPLES)]
+    Balance {
+        /// The balance of every month together, instead of this month's
+        #[clap(long)]
+        all: bool,
+    },
+    /// Change an entry of this month, by its number in `status --complete`
+    #[clap(after_help = EDIT_EXAMPLES)]
+    Edit {
+        #[clap(required = true)]
+        id: usize,
+
+        /// New amount, or an adjustment to the current one when prefixed with + or -
+        #[clap(long, allow_hyphen_values = true)]
+        amount: Option<String>,
+
+        /// New description
+        #[clap(long)]
+        description: Option<String>,
+    },
+    /// Record several operations at once, with undo and redo, writing them all on exit
+    #[clap(after_help = INTERACTIVE_EXAMPLES)]
+    Interactive,
+    /// Record `take`, `put` and `refund` commands read from stdin, one per line, all at once
+    #[clap(after_help = BATCH_EXAMPLES)]
+    Batch,
+    /// Record lines of a bookkeeping file read from stdin, as in `05 - 12.50 Coffee`, all at
+    /// once
+    ///
+    /// Nothing is recorded if any line isn't valid, and those are listed by their numbers
+    #[clap(after_help = PASTE_EXAMPLES)]
+    Paste,
+    /// Statistics for the current month, or for the whole book
+    #[clap(after_help = STATS_EXAMPLES)]
+    Stats {
+        /// Compute statistics over every bookkeeping file
+        #[clap(long, alias = "all")]
+        all_time: bool,
+
+        /// Compute statistics for this month instead of the current one, as MM-YYYY, YYYY-MM
+        /// or `last`
+        #[clap(long, conflicts_with = "all-time")]
+        month: Option<String>,
+    },
+    /// Compare two months, by their totals and by how much each category took
+    ///
+    /// With --year, compares two whole years instead, month by month
+    #[clap(after_help = COMPARE_EXAMPLES)]
+    Compare {
+        /// The earlier month, in the MM-YYYY format, or year with --year
+        #[clap(required = true)]
+        before: String,
+
+        /// The later month, in the MM-YYYY format, or year with --year
+        #[clap(required = true)]
+        after: String,
+
+        /// Compare years, archived ones included
+        #[clap(long)]
+        year: bool,
+    },
+    /// Summarize a quarter of the fiscal year, for tax periods
+    ///
+    /// Fiscal years start in January, unless `fiscal_year_starts_in` is set in config.toml,
+    /// and are named after the year they start in.
+    #[clap(after_help = QUARTER_EXAMPLES)]
+    Quarter {
+        /// The quarter, as YYYY-QN (defaults to the current one)
+        quarter: Option<String>,
+    },
+    /// Sum a year's deductible expenses and taxable income by category, listing their
+    /// entries, as asked for when filing income tax
+    ///
+    /// Which categories count is set in the [tax] table of config.toml.
+    #[clap(after_help = TAX_REPORT_EXAMPLES)]
+    TaxReport {
+        #[clap(required = true)]
+        year: String,
+    },
+    /// Answer a question about every operation recorded, archived ones included
+    ///
+    /// A query starts with `list`, `count`, or `sum`, `avg`, `min` or `max` followed by
+    /// `amount`, where takes count as negative, and may end with `where` and conditions joined
+    /// by `and`. Conditions compare `kind`, `amount`, `description`, `category`, `currency`,
+    /// `month` or `day` with `=`, `!=`, `<`, `<=`, `>`, `>=`, or `~` and `!~` for containing
+    /// text.
+    #[clap(after_help = QUERY_EXAMPLES)]
+    Query {
+        #[clap(required = true)]
+        query: String,
+    },
+    /// Run an SQL statement over every operation recorded, archived ones included, loaded into
+    /// an in-memory table called `ops`
+    ///
+    /// `ops` has the columns date (YYYY-MM-DD), year, month, day, kind (take, put or refund),
+    /// amount (in the default currency), currency, description and category. Nothing done to
+    /// it changes the book.
+    #[clap(after_help = SQL_EXAMPLES)]
+    Sql {
+        #[clap(required = true)]
+        statement: String,
+    },
+    /// Check this month's finances, printing nothing and exiting with 1 if they're off, for
+    /// shell prompts and cron jobs
+    #[clap(
+        after_help = CHECK_EXAMPLES,
+        group = ArgGroup::new("conditions").required(true).multiple(true)
+    )]
+    Check {
+        /// Fail if the cash on hand isn't above this, counting the opening balance
+        #[clap(long, allow_hyphen_values = true, group = "conditions")]
+        balance_above: Option<String>,
+
+        /// Fail if a category went over its budget, or the month over the spending target
+        #[clap(long, group = "conditions")]
+        under_budget: bool,
+    },
+    /// The largest takes, and their share of the spending
+    #[clap(after_help = TOP_EXAMPLES)]
+    Top {
+        /// How many takes to show
+        #[clap(default_value = "10")]
+        count: usize,
+
+        /// Only this month's takes, the default
+        #[clap(long, conflicts_with_all = &["year", "all"])]
+        month: bool,
+
+        /// Every take of this year, archived months included
+        #[clap(long, conflicts_with = "all")]
+        year: bool,
+
+        /// Every take of the book
+        #[clap(long)]
+        all: bool,
+    },
+    /// Flag takes far above the typical amount of their category, or of their description
+    ///
+    /// Useful to catch typos, such as an extra zero, and charges that weren't yours.
+    #[clap(after_help = OUTLIERS_EXAMPLES)]
+    Outliers {
+        /// How many times the median a take must be to stand out, above zero
+        #[clap(long, default_value = "3")]
+        factor: BigDecimal,
+
+        /// Flag takes in every month, not only in the current one
+        #[clap(long)]
+        all: bool,
+    },
+    /// Tell whether the spending is rising or falling, and by how much a month
+    ///
+    /// Fits a line over the last months, leaving out the current one as it isn't over.
+    #[clap(after_help = TREND_EXAMPLES)]
+    Trend {
+        /// Follow the spending of this category only
+        #[clap(long)]
+        category: Option<String>,
+
+        /// How many months to look back on
+        #[clap(long, default_value = "6")]
+        months: usize,
+    },
+    /// Draw a bar chart of how much was spent on each day of a month
+    #[clap(after_help = CHART_EXAMPLES)]
+    ///
+    /// With --output, draws its spending per category along with the incoming and outgoing of
+    /// every month into an image instead
+    Chart {
+        /// The month to draw, in the MM-YYYY format (defaults to the current one)
+        #[clap(long)]
+        month: Option<String>,
+
+        /// Write the chart into this .svg or .png file
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show a month as a calendar, each day shaded by how much was spent on it
+    #[clap(after_help = HEATMAP_EXAMPLES)]
+    Heatmap {
+        /// The month to show, in the MM-YYYY format (defaults to the current one)
+        #[clap(long)]
+        month: Option<String>,
+    },
+    /// How much a small habit costs, and what it would be worth if saved instead
+    #[clap(after_help = LATTE_EXAMPLES)]
+    Latte {
+        /// A category, or part of a description
+        #[clap(required = true)]
+        habit: String,
+
+        /// Sum the spending of the last N months, can be given many times
+        #[clap(long = "months", default_values = &["1", "3", "12"])]
+        horizons: Vec<u32>,
+
+        /// Compare with saving the money at this yearly interest rate, in percent
+        #[clap(long)]
+        invested_at: Option<String>,
+    },
+    /// Compress every bookkeeping file of a past year into a single archive
+    ///
+    /// Archived files are still read by `stats --all-time`
+    #[clap(after_help = ARCHIVE_EXAMPLES)]
+    Archive {
+        #[clap(required = true)]
+        year: i32,
+    },
+    /// Export every operation of the book
+    ///
+    /// With `ical`, exports the bills and the recurring entries of last month instead, as
+    /// events repeating every month
+    #[clap(after_help = EXPORT_EXAMPLES)]
+    Export {
+        #[clap(arg_enum)]
+        format: Format,
+
+        /// Write to this file instead of the standard output
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import operations previously exported with `export`
+    #[clap(after_help = IMPORT_EXAMPLES)]
+    Import {
+        #[clap(arg_enum)]
+        format: Format,
+
+        #[clap(required = true)]
+        path: PathBuf,
+
+        /// Read a bank statement laid out as the `[profiles.NAME]` table of config.toml says,
+        /// or as a preset: nubank, nubank-conta, inter or itau
+        #[clap(long, value_name = "NAME")]
+        profile: Option<String>,
+    },
+    /// Import the history of another budgeting app, its categories becoming #tags
+    ///
+    /// Nothing is written until you're happy with how categories are mapped and pass --write
+    #[clap(after_help = IMPORT_FROM_EXAMPLES)]
+    ImportFrom {
+        #[clap(arg_enum)]
+        app: App,
+
+        #[clap(required = true)]
+        path: PathBuf,
+
+        /// Map one of the app's categories into a porquinho one, can be given many times
+        #[clap(long = "map", value_name = "FROM=TO", multiple_occurrences = true)]
+        mappings: Vec<String>,
+
+        /// Write the operations into the book, instead of only reviewing them
+        #[clap(long)]
+        write: bool,
+    },
+    /// Show a month as a QR code, to grab it from a phone or another machine
+    ///
+    /// The QR code holds the month's JSON export, compressed with zstd and encoded in base64
+    #[clap(after_help = QR_EXAMPLES)]
+    Qr {
+        /// The month to show, in the MM-YYYY format (defaults to the current one)
+        #[clap(long)]
+        month: Option<String>,
+    },
+    /// Show the history of the book, when it's tracked with git
+    Log,
+    /// Undo the changes of a commit from `log`, when the book is tracked with git
+    #[clap(after_help = REVERT_EXAMPLES)]
+    Revert {
+        #[clap(required = true)]
+        commit: String,
+    },
+    /// Encrypt every month written before `encryption` was set in config.toml
+    ///
+    /// Once it's set, months are encrypted whenever they're written anyway
+    #[clap(after_help = ENCRYPT_EXAMPLES)]
+    Encrypt,
+    /// Track the steps of closing a month, configured in config.toml
+    #[clap(after_help = CHECKLIST_EXAMPLES)]
+    Checklist {
+        #[clap(subcommand)]
+        action: Option<ChecklistAction>,
+
+        /// The month being closed, in the MM-YYYY format (defaults to the current one)
+        #[clap(long, global = true)]
+        month: Option<String>,
+    },
+    /// Add or remove bills due every month, see `bills` for where they stand
+    #[clap(after_help = BILL_EXAMPLES)]
+    Bill {
+        #[clap(subcommand)]
+        action: BillAction,
+    },
+    /// List the bills of the current month, as paid, upcoming or overdue
+    ///
+    /// A bill is paid once a take whose description contains its name is recorded
+    #[clap(after_help = BILL_EXAMPLES)]
+    Bills,
+    /// Show the open statement of every credit card in config.toml
+    ///
+    /// Card purchases are kept apart from the book, which only gets the statement on its
+    /// due day
+    #[clap(after_help = CARD_EXAMPLES)]
+    Card {
+        #[clap(subcommand)]
+        action: Option<CardAction>,
+    },
+    /// Record an expense paid for a group, taking only my share and keeping track of what the
+    /// others owe
+    ///
+    /// The amount is split evenly, and the others' shares aren't part of the spending, as
+    /// they're expected back. See what they owe with `settle`.
+    #[clap(after_help = SPLIT_EXAMPLES)]
+    Split {
+        #[clap(required = true)]
+        amount: String,
+
+        #[clap(required = true)]
+        description: String,
+
+        /// Who it's split with, separated by commas
+        #[clap(
+            long,
+            required = true,
+            use_value_delimiter = true,
+            require_value_delimiter = true
+        )]
+        with: Vec<String>,
+
+        /// When it happened, if not today: YYYY-MM-DD, yesterday, or a weekday such as monday
+        #[clap(long)]
+        date: Option<String>,
+
+        /// Day of the current month it happened, if not today
+        #[clap(long, conflicts_with = "date")]
+        day: Option<u8>,
+    },
+    /// Show what everyone owes from split expenses, or record someone paying you back
+    #[clap(after_help = SETTLE_EXAMPLES)]
+    Settle {
+        /// Who paid you back
+        person: Option<String>,
+
+        /// How much they paid (defaults to everything they owe)
+        #[clap(requires = "person")]
+        amount: Option<String>,
+    },
+    /// Show savings goals, their progress and how much they need each month to stay on track
+    #[clap(after_help = GOAL_EXAMPLES)]
+    Goal {
+        #[clap(subcommand)]
+        action: Option<GoalAction>,
+    },
+    /// Carry the balance a month ended with into the next one, as its opening balance
+    ///
+    /// `status` then shows the cash on hand, instead of only what came in and went out. With
+    /// `interest_rate` set in config.toml, the interest the month earned is recorded first, as
+    /// a put on its last day, once the month is over.
+    #[clap(after_help = CLOSE_EXAMPLES)]
+    Close {
+        /// The month being closed, in the MM-YYYY format (defaults to the current one)
+        #[clap(long)]
+        month: Option<String>,
+    },
+    /// Set how much cash a month started with, for the first month `close` carries from
+    #[clap(after_help = CLOSE_EXAMPLES)]
+    Opening {
+        #[clap(required = true, allow_hyphen_values = true)]
+        amount: String,
+
+        /// The month it's the opening balance of, in the MM-YYYY format (defaults to the
+        /// current one)
+        #[clap(long)]
+        month: Option<String>,
+    },
+    /// Edit a month's file in $VISUAL or $EDITOR, checking every line afterwards
+    #[clap(after_help = OPEN_EXAMPLES)]
+    Open {
+        /// The month to edit, in the MM-YYYY format (defaults to the current one)
+        month: Option<String>,
+    },
+    /// Serve a JSON API over the book on localhost, for building other interfaces on top
+    #[clap(after_help = SERVE_EXAMPLES)]
+    Serve {
+        #[clap(long, default_value = "8631")]
+        port: u16,
+    },
+    /// Tag operations recorded before their rule existed, with the category of the rule
+    #[clap(after_help = CATEGORIZE_EXAMPLES)]
+    Categorize {
+        /// Go through every month, not only the current one
+        #[clap(long)]
+        all: bool,
+
+        /// Write the tags, instead of only showing them
+        #[clap(long)]
+        apply: bool,
+    },
+    /// Change the description of every operation described as FROM, across the whole book
+    ///
+    /// Descriptions are compared ignoring case and accents, as configured for rules.
+    #[clap(after_help = RENAME_EXAMPLES)]
+    Rename {
+        /// The description to replace
+        from: String,
+        /// The description to write instead
+        to: String,
+    },
+    /// Find identical entries in a month, choosing which ones to merge
+    #[clap(after_help = DEDUPE_EXAMPLES)]
+    Dedupe {
+        /// The month to look in, in the MM-YYYY format (defaults to the current one)
+        month: Option<String>,
+    },
+    /// Check every bookkeeping file for problems, such as malformed entries or February 30
+    #[clap(after_help = DOCTOR_EXAMPLES)]
+    Doctor {
+        /// Fix what can be fixed safely, such as blank lines and misnamed files
+        #[clap(long)]
+        fix: bool,
+    },
+    /// Check that exporting and re-importing the book gives back the same book
+    Selftest,
+    /// Keep separate books, such as personal and business ones, each in its own folders
+    #[clap(after_help = PROFILE_EXAMPLES)]
+    Profile {
+        #[clap(subcommand)]
+        action: Option<ProfileAction>,
+    },
+    /// Explain a warning or error code, or list them all
+    #[clap(after_help = EXPLAIN_EXAMPLES)]
+    Explain {
+        /// A code such as P001, as printed in `error[P001]`
+        code: Option<String>,
+    },
+    /// Print copy-pasteable workflows
+    Examples,
+}
+
+const TAKE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho take 12.50 Coffee
+    porquinho take 1200 \"Rent for October\"
+    porquinho take 35 Museum --currency EUR
+    # Let porquinho do the math
+    porquinho take \"3*12.90+4.50\" \"Pizza night\"
+    porquinho take 1.2k \"New phone\"
+    # Forgot to record it when it happened
+    porquinho take 40 Groceries --date yesterday
+    porquinho take 90 Dinner --date friday
+    porquinho take 15 Parking --date 2024-09-30
+    porquinho take 50 Groceries --day 3
+    # Pick the description from the ones used before
+    porquinho take 20 @
+    # Skip confirming amounts above `confirm_above`, set in config.toml
+    porquinho take 5000 \"New laptop\" --yes";
+
+const PUT_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho put 3000 Salary
+    porquinho put 150 \"Sold old bike\"";
+
+const PASTE_EXAMPLES: &str = "\
+EXAMPLES:
+    # Copy lines from notes or another book, then
+    porquinho paste
+    05 - 12.50 Coffee
+    05 + 3000 Salary
+    ^D
+    printf '03 - 40 Groceries\\n04 ~ 10 Groceries refund\\n' | porquinho paste";
+
+const REFUND_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho take 120 \"Shoes #clothing\"
+    # Recorded as `~` in the file, and taken off the spending on clothing
+    porquinho refund 120 \"Returned shoes #clothing\"";
+
+const STATUS_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho status
+    porquinho status --complete
+    porquinho status --convert USD
+    porquinho status --month last
+    porquinho status --month 2024-10
+    porquinho status --complete --format markdown > october.md
+    porquinho status --columns day,amount,description
+    # The most expensive entries first
+    porquinho status --sort amount --desc
+    # How much went into coffee, in one row
+    porquinho status --group-by description
+    porquinho status --group-by category
+    # Keep it open while editing a month by hand, or syncing from another machine
+    porquinho status --complete --watch
+    porquinho status --ephemeral";
+
+const REPORT_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho report --month last --output september.md
+    porquinho report --format html --output report.html
+    # In crontab, to have last month's report in the data folder's reports folder
+    0 8 1 * * porquinho report --auto --format html";
+
+const FORECAST_EXAMPLES: &str = "\
+EXAMPLES:
+    # Will this month end in the red?
+    porquinho forecast";
+
+const BALANCE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho balance
+    porquinho balance --all
+    # As a waybar module, in ~/.config/waybar/config
+    \"custom/porquinho\": { \"exec\": \"porquinho balance\", \"interval\": 60 }";
+
+const EDIT_EXAMPLES: &str = "\
+EXAMPLES:
+    # Add a 5.50 delivery fee to entry #3
+    porquinho edit 3 --amount +5.50
+    # Entry #4 was 3 cheaper than recorded
+    porquinho edit 4 --amount -3
+    porquinho edit 4 --amount 20 --description Groceries";
+
+const ENCRYPT_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho encrypt
+    # Skip the passphrase prompt, e.g. in scripts
+    PORQUINHO_PASSPHRASE=hunter2 porquinho status";
+
+const CHECKLIST_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho checklist
+    porquinho checklist run
+    porquinho checklist done 1 --month 09-2024";
+
+const BILL_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho bill add \"Electricity\" 180 --due 10
+    porquinho bills
+    # Paying it marks it as paid
+    porquinho take 182.40 \"Electricity bill\"
+    porquinho bill remove electricity";
+
+const CARD_EXAMPLES: &str = "\
+EXAMPLES:
+    # In config.toml, a card whose statement closes on the 3rd and is due on the 10th
+    [cards.nubank]
+    closing_day = 3
+    due_day = 10
+    # Record purchases and refunds as they happen
+    porquinho card take nubank 89.90 \"Dinner\"
+    porquinho card put nubank 20 \"Refund\"
+    # Once the statement closes, pay it from the account
+    porquinho card close nubank";
+
+const GOAL_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho goal add \"Trip to Chile\" 5000 --by 12-2025
+    porquinho goal put trip 300
+    porquinho goal";
+
+const PROFILE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho profile create business
+    porquinho --profile business put 8000 \"Invoice 42\"
+    porquinho --profile business status
+    # The default book is used without --profile
+    porquinho profile list";
+
+const SPLIT_EXAMPLES: &str = "\
+EXAMPLES:
+    # Records 30 as a take, and that each of them owes 30
+    porquinho split 90 Dinner --with João,Ana
+    porquinho split 240 \"Beach house\" --with bia --date 2024-12-28";
+
+const SETTLE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho settle
+    # João paid back everything
+    porquinho settle joão
+    # Ana paid back part of it
+    porquinho settle ana 10";
+
+const CLOSE_EXAMPLES: &str = "\
+EXAMPLES:
+    # Start from what's in the account today
+    porquinho opening 1520,30
+    # At the end of the month, carry what's left into the next one
+    porquinho close
+    porquinho close --month 09-2024
+    # In config.toml, hand how the month ended to a script, as JSON on its stdin
+    [hooks]
+    on_close_month = \"/home/me/bin/month-closed.sh\"";
+
+const OPEN_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho open
+    porquinho open 01-2024
+    # With a specific editor
+    EDITOR=nano porquinho open";
+
+const SERVE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho serve --port 8631
+    # Then, from another terminal
+    curl localhost:8631/months
+    curl localhost:8631/months/current/operations
+    curl localhost:8631/months/01-2024/status
+    # Recording needs `serve_token = \"...\"` in config.toml, sent along as a bearer token
+    curl localhost:8631/take -H \"Authorization: Bearer $TOKEN\" -H 'Content-Type: application/json' \\
+        -d '{\"amount\": \"12.50\", \"description\": \"Coffee\"}'
+    curl localhost:8631/put -H \"Authorization: Bearer $TOKEN\" -H 'Content-Type: application/json' \\
+        -d '{\"amount\": \"100\", \"description\": \"Refund\", \"currency\": \"USD\"}'";
+
+const CATEGORIZE_EXAMPLES: &str = "\
+EXAMPLES:
+    # See which operations of this month the rules would tag
+    porquinho categorize
+    # Tag the whole book
+    porquinho categorize --all --apply";
+
+const RENAME_EXAMPLES: &str = "\
+EXAMPLES:
+    # Fix a typo everywhere it was made
+    porquinho rename Ubber Uber
+    # See what would change first
+    porquinho --dry-run rename \"Padaria Sao Jose\" \"Padaria São José\"";
+
+const DEDUPE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho dedupe
+    porquinho dedupe 01-2024
+    # See which lines would be removed
+    porquinho dedupe --dry-run";
+
+const DOCTOR_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho doctor
+    porquinho doctor --fix
+    # Learn more about a problem it found
+    porquinho explain P103";
+
+const EXPLAIN_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho explain
+    porquinho explain P101
+    # Fail in scripts when overspending, ignore the comparison with last month
+    porquinho status --deny P101 --allow P102";
+
+const INIT_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho init
+    # See what would be added, without changing config.toml
+    porquinho init --dry-run";
+
+const INTERACTIVE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho interactive
+    # Commands can also be piped in
+    printf 'take 12 Coffee\\ntake 30 Lunch\\nundo\\nsave\\n' | porquinho interactive";
+
+const BATCH_EXAMPLES: &str = "\
+EXAMPLES:
+    printf 'take 12.50 Coffee\\nput 3000 Salary\\n' | porquinho batch
+    # Lines starting with # are skipped, so batches can be kept as annotated files
+    porquinho batch < catch-up.txt";
+
+const STATS_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho stats
+    porquinho stats --month last
+    porquinho stats --all-time";
+
+const COMPARE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho compare 09-2024 10-2024
+    porquinho compare --year 2023 2024";
+
+const QUARTER_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho quarter 2024-Q3
+    # With fiscal_year_starts_in = 4 in config.toml, January to March of 2025
+    porquinho quarter 2024-Q4";
+
+const TAX_REPORT_EXAMPLES: &str = "\
+EXAMPLES:
+    # With this in config.toml, health and education are deducted, and salary is taxed
+    [tax]
+    deductible = [\"health\", \"education\"]
+    taxable = [\"salary\"]
+    porquinho tax-report 2024";
+
+const QUERY_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho query \"sum amount where kind=take and description~'uber' and month>=01-2024\"
+    porquinho query \"count where category=food and day>=25\"
+    porquinho query \"max amount where kind=put\"
+    porquinho query \"list where amount>500 and description!~'rent'\"";
+
+const SQL_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho sql \"SELECT description, SUM(amount) FROM ops GROUP BY 1 ORDER BY 2 DESC\"
+    # Spending per category in 2024
+    porquinho sql \"SELECT category, SUM(amount) FROM ops WHERE kind = 'take' AND year = 2024 GROUP BY 1\"
+    # Saved per month
+    porquinho sql \"SELECT substr(date, 1, 7), SUM(CASE kind WHEN 'put' THEN amount ELSE -amount END) FROM ops GROUP BY 1\"";
+
+const CHECK_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho check --balance-above 0 || echo \"Out of money\"
+    # Both must hold
+    porquinho check --balance-above 500 --under-budget
+    # In a crontab, mailing whenever a budget was broken
+    0 20 * * * porquinho check --under-budget || echo \"Over budget\" | mail -s porquinho me";
+
+const TOP_EXAMPLES: &str = "\
+EXAMPLES:
+    # The 10 largest takes of this month
+    porquinho top
+    porquinho top 5 --year
+    porquinho top 20 --all";
+
+const OUTLIERS_EXAMPLES: &str = "\
+EXAMPLES:
+    # Takes of this month at least 3 times their usual amount
+    porquinho outliers
+    porquinho outliers --factor 2 --all";
+
+const TREND_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho trend
+    # Is eating out getting more expensive this year?
+    porquinho trend --category food --months 12";
+
+const CHART_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho chart
+    porquinho chart --month 09-2024
+    # Share it as an image
+    porquinho chart --output spending.svg
+    porquinho chart --month 09-2024 --output september.png";
+
+const HEATMAP_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho heatmap
+    porquinho heatmap --month 09-2024";
+
+const LATTE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho latte coffee
+    porquinho latte food --months 6 --invested-at 10,5";
+
+const ARCHIVE_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho archive 2022";
+
+const EXPORT_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho export json --output book.json
+    porquinho export csv > book.csv
+    # Bills and recurring entries, for a calendar app to remind of them
+    porquinho export ical --output porquinho.ics
+    # A spreadsheet with a sheet per month, for Excel or LibreOffice
+    porquinho export xlsx --output book.xlsx";
+
+const IMPORT_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho import json book.json
+    # A bank statement, from one of the built-in presets
+    porquinho import csv nubank.csv --profile nubank
+    porquinho import csv extrato.csv --profile inter
+    # Or from a layout described in config.toml as [profiles.mybank]
+    porquinho import csv statement.csv --profile mybank";
+
+const IMPORT_FROM_EXAMPLES: &str = "\
+EXAMPLES:
+    # Review how categories will be mapped
+    porquinho import-from ynab register.csv
+    porquinho import-from organizze lancamentos.csv --map \"Alimentação: Restaurantes=food\"
+    # Then write everything into the book
+    porquinho import-from organizze lancamentos.csv --map \"Alimentação: Restaurantes=food\" --write";
+
+const QR_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho qr
+    porquinho qr --month 10-2024
+    # To decode what was scanned
+    base64 -d scanned.txt | zstd -d";
+
+const REVERT_EXAMPLES: &str = "\
+EXAMPLES:
+    porquinho log
+    porquinho revert 3f2a9c1";
+
+impl Subcommand {
+    /// Whether the command works with books kept in a database, with `storage = "sqlite"`
+    pub fn works_on_database(&self) -> bool {
+        matches!(
+            self,
+            Self::Take { .. }
+                | Self::Put { .. }
+                | Self::Refund { .. }
+                | Self::Split { .. }
+                | Self::Settle { .. }
+                | Self::Edit { .. }
+                | Self::Status { .. }
+                | Self::Stats { .. }
+                | Self::Quarter { .. }
+                | Self::Check { .. }
+                | Self::Balance { .. }
+                | Self::Sql { .. }
+                | Self::Init
+                | Self::Explain { .. }
+                | Self::Profile { .. }
+                | Self::Examples
+        )
+    }
+
+    /// Whether the command reads the book at all, which explaining codes and showing examples
+    /// don't, so they needn't unlock it
+    pub fn reads_book(&self) -> bool {
+        !matches!(self, Self::Explain { .. } | Self::Examples)
+    }
+
+    /// Describes the change made by this command, `None` if it doesn't change the book
+    pub fn commit_message(&self) -> Option<String> {
+        let message = match self {
+            Self::Take {
+                amount,
+                description,
+                currency,
+                date,
+                day,
+                ..
+            }
+            | Self::Put {
+                amount,
+                description,
+                currency,
+                date,
+                day,
+                ..
+            }
+            | Self::Refund {
+                amount,
+                description,
+                currency,
+                date,
+                day,
+                ..
+            } => {
+                let verb = match self {
+                    Self::Take { .. } => "take",
+                    Self::Put { .. } => "put",
+                    _ => "refund",
+                };
+                let currency = currency
+                    .as_deref()
+                    .map(|code| format!("{code} "))
+                    .unwrap_or_default();
+
+                let date = match (date, day) {
+                    (Some(date), _) => format!(" on {date}"),
+                    (None, Some(day)) => format!(" on day {day}"),
+                    (None, None) => String::new(),
+                };
+
+                format!("{verb} {currency}{amount} {description}{date}")
+            }
+            Self::Edit {
+                id,
+                amount,
+                description,
+            } => {
+                let mut message = format!("edit #{id}");
+                if let Some(amount) = amount {
+                    message += &format!(" amount {amount}");
+                }
+                if let Some(description) = description {
+                    message += &format!(" description {description:?}");
+                }
+                message
+            }
+            Self::Archive { year } => format!("archive {year}"),
+            Self::Encrypt => "encrypt".to_owned(),
+            Self::Doctor { fix: true } => "doctor --fix".to_owned(),
+            Self::Rename { from, to } => format!("rename {from:?} {to:?}"),
+            Self::Categorize { all, apply: true } => {
+                if *all {
+                    "categorize --all".to_owned()
+                } else {
+                    "categorize".to_owned()
+                }
+            }
+            Self::Bill {
+                action: BillAction::Add { name, amount, due },
+            } => format!("bill add {name:?} {amount} --due {due}"),
+            Self::Bill {
+                action: BillAction::Remove { name },
+            } => format!("bill remove {name:?}"),
+            Self::Card {
+                action:
+                    Some(CardAction::Take {
+                        card,
+                        amount,
+                        description,
+                        ..
+                    }),
+            } => format!("card take {card} {amount} {description}"),
+            Self::Card {
+                action:
+                    Some(CardAction::Put {
+                        card,
+                        amount,
+                        description,
+                        ..
+                    }),
+            } => format!("card put {card} {amount} {description}"),
+            Self::Card {
+                action: Some(CardAction::Close { card, month }),
+            } => match month {
+                Some(month) => format!("card close {card} {month}"),
+                None => format!("card close {card}"),
+            },
+            Self::Goal {
+                action: Some(GoalAction::Add { name, target, by }),
+            } => format!("goal add {name:?} {target} --by {by}"),
+            Self::Goal {
+                action: Some(GoalAction::Put { name, amount }),
+            } => format!("goal put {name:?} {amount}"),
+            Self::Split {
+                amount,
+                description,
+                with,
+                ..
+            } => format!("split {amount} {description} with {}", with.join(", ")),
+            Self::Settle {
+                person: Some(person),
+                amount: Some(amount),
+            } => format!("settle {person:?} {amount}"),
+            Self::Settle {
+                person: Some(person),
+                amount: None,
+            } => format!("settle {person:?}"),
+            Self::Close { month: Some(month) } => format!("close {month}"),
+            Self::Close { month: None } => "close".to_owned(),
+            Self::Opening {
+                amount,
+                month: Some(month),
+            } => format!("opening {amount} {month}"),
+            Self::Opening {
+                amount,
+                month: None,
+            } => format!("opening {amount}"),
+            Self::Open { month: Some(month) } => format!("open {month}"),
+            Self::Open { month: None } => "open".to_owned(),
+            Self::Dedupe { month: Some(month) } => format!("dedupe {month}"),
+            Self::Dedupe { month: None } => "dedupe".to_owned(),
+            Self::Interactive => "interactive session".to_owned(),
+            Self::Batch => "batch".to_owned(),
+            Self::Paste => "paste".to_owned(),
+            Self::ImportFrom {
+                app,
+                path,
+                write: true,
+                ..
+            } => {
+                let app = format!("{:?}", app).to_lowercase();
+                format!("import from {} {}", app, path.display())
+            }
+            Self::Import { format, path, .. } => {
+                let format = format!("{:?}", format).to_lowercase();
+                format!("import {} {}", format, path.display())
+            }
+            _ => return None,
+        };
+
+        Some(message)
+    }
 }
+
+/// Printed by `porquinho examples`
+pub const WORKFLOWS: &str = "\
+Monthly routine
+    # Record income as soon as it arrives
+    porquinho put 3000 Salary
+    # Record expenses as they happen
+    porquinho take 45.90 Groceries
+    porquinho take 12.50 Coffee
+    # Or record a whole receipt at once, undoing any typo before saving
+    porquinho interactive
+    # Forgot the tip? Find the entry's number and adjust it
+    porquinho status --complete
+    porquinho edit 3 --amount +2
+    # Or fix several entries at once in your editor, which checks them on save
+    porquinho open
+    # Check how the month is going, compared to last month
+    porquinho status
+    # Paid on the 5th? Start budget months on payday, with this at the top of config.toml
+    month_starts_on = 5
+    # And how it's going to end, at this pace
+    porquinho forecast
+    # At the end of the month, see where most of the money went
+    porquinho top
+    # And look at the bigger picture
+    porquinho stats --all-time
+    # Then go through the closing checklist, step by step
+    porquinho checklist run
+    # Once a year is over, tidy its files up into an archive
+    porquinho archive 2023
+
+Customizing the closing checklist
+    # In config.toml, each step can run a porquinho command
+    [[checklist]]
+    name = \"Import bank statements\"
+    [[checklist]]
+    name = \"Back up\"
+    command = \"export json --output /mnt/backup/porquinho.json\"
+
+Categorization setup
+    # Start from common Brazilian household categories, with budgets
+    porquinho init
+    # Or add rules to config.toml, in porquinho's config folder
+    [[rules]]
+    match = \"uber|99\"
+    category = \"transport\"
+    # Matching ignores case and accents, so \"Açaí\" also matches \"ACAI LTDA\"
+    [[rules]]
+    pattern = \"acai\"
+    category = \"food\"
+    # New entries get the #tag of the rule they match, unless auto_tag = false
+    porquinho take 25 \"Uber home\"
+    # Tag what was recorded before the rule existed
+    porquinho categorize --all --apply
+    # Spending is then grouped by category
+    porquinho stats
+    # Budgets are a share of the income, shown next to each category's spending
+    [budgets]
+    transport = \"10\"
+    # A take going over one, or over the month's target, is pointed out as it's recorded,
+    # and also sent as a desktop notification with these at the top of config.toml
+    notify = true
+    spending_target = \"4000\"
+    # Be warned after every command when the cash on hand is, or is heading, below this
+    low_balance = \"500\"
+
+Wiring porquinho into other programs
+    # In config.toml, run a command after recording each entry, or closing a month,
+    # which gets the entry, or the month's totals, as JSON on its stdin
+    [hooks]
+    on_add = \"/home/me/bin/notify-phone.sh\"
+    on_close_month = \"python3 /home/me/bin/update-sheet.py\"
+    # Or post each entry, as JSON, to a dashboard or chat bot as it's recorded, with this
+    # at the top of config.toml
+    webhook = \"https://dashboard.home.arpa/porquinho\"
+
+Keeping separate books
+    # A book of its own for the business, in folders apart from the personal one
+    porquinho profile create business
+    porquinho --profile business put 8000 \"Invoice 42\"
+    # Each profile has its own config.toml, started as a copy of the default one, so a
+    # savings book kept in dollars only needs these at the top of it
+    currency = \"USD\"
+    currency_symbol = \"US$\"
+    # Then every book's balance shows side by side, each in its own currency and format
+    porquinho profile list
+    # And earning 0.4% a month, recorded as a put when closing each month
+    interest_rate = \"0.4\"
+
+Formatting amounts
+    # In config.toml, print amounts as 1 234,56 R$ and negative ones in parentheses
+    locale = \"pt-BR\"
+    # And talk in Portuguese, instead of following LANG
+    language = \"pt-BR\"
+    [amounts]
+    symbol_position = \"after\"
+    thousands_separator = \" \"
+    negative = \"parentheses\"
+    # For currencies without cents, such as the yen, rounding halves to the even digit
+    decimals = 0
+    rounding = \"half-even\"
+
+Traveling abroad
+    # Tell porquinho how much each currency is worth, in config.toml
+    [rates]
+    USD = \"5.00\"
+    # Record expenses in the currency they were made in
+    porquinho take 20 Taxi --currency USD
+    # Totals are converted into your default currency, or any other
+    porquinho status
+    porquinho status --complete
+    porquinho status --convert USD
+
+Backing up and restoring
+    # Make sure the book is healthy and exports hold everything, then export it
+    porquinho doctor
+    porquinho selftest
+    porquinho export json --output porquinho-backup.json
+    # Later, on another machine
+    porquinho import json porquinho-backup.json
+    # If a month ended up with entries recorded twice, merge them
+    porquinho dedupe 01-2024
+
+Keeping a history with git
+    # In config.toml, every change becomes a commit in the data folder
+    git = true
+    # Look back, and undo a mistake
+    porquinho log
+    porquinho revert 3f2a9c1
+
+Coming from another app
+    # Export your history from YNAB or Organizze as CSV, then review it
+    porquinho import-from organizze lancamentos.csv
+    # Their categories become #tags, which stats groups spending by
+    porquinho import-from organizze lancamentos.csv --map \"Mercado=groceries\" --write
+    porquinho stats --all-time
+
+Importing bank statements
+    # Nubank, Inter and Itaú statements are understood out of the box
+    porquinho import csv nubank-2024-01.csv --profile nubank
+    # For other banks, describe how they lay out CSV statements in config.toml
+    [profiles.mybank]
+    columns = [\"date\", \"category\", \"description\", \"amount\"]
+    date_format = \"%Y-%m-%d\"
+    decimal_separator = \".\"
+    # Card statements list spending as positive amounts
+    spending_sign = \"positive\"
+    # Then import each statement with it
+    porquinho import csv statement.csv --profile mybank
+
+Encrypting the book
+    # In config.toml, encrypt with a key locked by a passphrase...
+    encryption = \"passphrase\"
+    # ...or with a key made by age-keygen
+    [encryption]
+    identity = \"/home/me/.config/porquinho/key.txt\"
+    # Then encrypt the months written so far
+    porquinho encrypt
+
+Scripting
+    # Warnings and errors carry a code, such as warning[P101]
+    porquinho explain P101
+    # Turn every warning into an error, making porquinho exit with failure
+    porquinho status --deny warnings
+    # Or just hide one of them
+    porquinho status --allow P102
+    # Record amounts above `confirm_above` without being asked
+    porquinho take 5000 Rent --yes
+    # Record many operations in one go, from a file or another program
+    porquinho batch < catch-up.txt
+    # Talk to porquinho over HTTP instead, e.g. from a phone shortcut
+    porquinho serve --port 8631
+
+Trying things out safely
+    # Any command can run against a throwaway copy of your data
+    porquinho --ephemeral take 5000 \"What if I bought a TV?\"
+    porquinho --ephemeral status
+    # Or just see which lines a command would write
+    porquinho --dry-run edit 3 --amount +2";
@@ -0,0 +1,66 @@
+use std::{
+    env,
+    fmt::Display,
+    io::{self, IsTerminal},
+};
+
+use clap::ArgEnum;
+
+/// Whether output is colored, as chosen with `--color`
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorChoice {
+    /// Only when printing to a terminal, and `NO_COLOR` isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self) -> bool {
+        match self {
+            Self::Auto => env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal(),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// How a piece of text stands out
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Style {
+    Bold,
+    /// For money coming in
+    Green,
+    /// For money going out
+    Red,
+}
+
+impl Style {
+    /// `text` wrapped in this style's escape codes, or as is when `enabled` is off
+    pub fn paint(self, text: impl Display, enabled: bool) -> String {
+        if !enabled {
+            return text.to_string();
+        }
+
+        let code = match self {
+            Self::Bold => "1",
+            Self::Green => "32",
+            Self::Red => "31",
+        };
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Style;
+
+    #[test]
+    fn paints_only_when_enabled() {
+        assert_eq!(
+            Style::Green.paint("R$ 10.00", true),
+            "\x1b[32mR$ 10.00\x1b[0m"
+        );
+        assert_eq!(Style::Red.paint("R$ 10.00", false), "R$ 10.00");
+    }
+}
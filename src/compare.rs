@@ -0,0 +1,225 @@
+use std::collections::BTreeSet;
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::{
+    config::Config,
+    file::BookkeepingFile,
+    i18n::{t, tf},
+    stats::Stats,
+    status::percent_change,
+};
+
+/// Two periods of the book side by side, each being one or more months
+pub struct Comparison {
+    pub before: Stats,
+    pub after: Stats,
+}
+
+impl Comparison {
+    /// Spending per category in both periods, the ones that grew the most first
+    pub fn categories(&self) -> Vec<(&str, BigDecimal, BigDecimal)> {
+        let names: BTreeSet<&str> = self
+            .before
+            .spending
+            .keys()
+            .chain(self.after.spending.keys())
+            .map(String::as_str)
+            .collect();
+
+        let mut categories: Vec<_> = names
+            .into_iter()
+            .map(|name| {
+                let spent = |stats: &Stats| stats.spending.get(name).cloned().unwrap_or_default();
+                (name, spent(&self.before), spent(&self.after))
+            })
+            .collect();
+
+        categories.sort_by(|(_, a_before, a_after), (_, b_before, b_after)| {
+            (b_after - b_before).cmp(&(a_after - a_before))
+        });
+
+        categories
+    }
+
+    pub fn print(&self, before: &str, after: &str, config: &Config) {
+        let (before_total, after_total) =
+            (self.before.lifetime_total(), self.after.lifetime_total());
+
+        println!("{}", tf("Comparing {} to {}", &[&before, &after]));
+        let rows = [
+            ("Incoming", &before_total.incoming, &after_total.incoming),
+            ("Outgoing", &before_total.outgoing, &after_total.outgoing),
+        ];
+        for (name, before, after) in rows {
+            println!("\t{}: {}", t(name), change(before, after, config));
+        }
+        println!(
+            "\t{}: {}",
+            t("Balance"),
+            change(&before_total.balance(), &after_total.balance(), config)
+        );
+
+        let categories = self.categories();
+        if !categories.is_empty() {
+            println!("{}", t("Spending by category"));
+            for (category, before, after) in &categories {
+                println!("\t{}: {}", category, change(before, after, config));
+            }
+        }
+    }
+
+    /// Spending in each month of the year in both periods, which are expected to be years
+    pub fn months(&self) -> Vec<(u32, BigDecimal, BigDecimal)> {
+        let spent = |stats: &Stats, month: u32| {
+            stats
+                .months
+                .iter()
+                .filter(|total| total.file.month() == month)
+                .map(|total| &total.total.outgoing)
+                .sum::<BigDecimal>()
+        };
+
+        (1..=12)
+            .map(|month| (month, spent(&self.before, month), spent(&self.after, month)))
+            .collect()
+    }
+
+    /// Prints the spending of each month aligned with the same month of the other year,
+    /// pointing out where it grew the most
+    pub fn print_by_month(&self, config: &Config) {
+        let months = self.months();
+        let largest_growth = months
+            .iter()
+            .map(|(month, before, after)| (after - before, *month))
+            .filter(|(growth, _)| growth > &BigDecimal::zero())
+            .max()
+            .map(|(_, month)| month);
+
+        println!("{}", t("Outgoing by month"));
+        for (month, before, after) in &months {
+            let name = BookkeepingFile::new(*month, 0).month_name();
+            let mark = if largest_growth == Some(*month) {
+                t(", grew the most")
+            } else {
+                ""
+            };
+            println!("\t{}: {}{}", name, change(before, after, config), mark);
+        }
+    }
+}
+
+/// `before → after`, followed by the difference between them
+pub fn change(before: &BigDecimal, after: &BigDecimal, config: &Config) -> String {
+    let delta = after - before;
+    let sign = if delta > BigDecimal::zero() { "+" } else { "" };
+
+    let percent = match percent_change(after, before) {
+        // Growing over a negative balance would read backwards
+        _ if before < &BigDecimal::zero() => String::new(),
+        Some(percent) if percent > BigDecimal::zero() => format!(", +{}%", percent),
+        Some(percent) => format!(", {}%", percent),
+        None if after.is_zero() => String::new(),
+        None => ", new".to_owned(),
+    };
+
+    format!(
+        "{} → {} ({}{}{})",
+        config.format_amount(before),
+        config.format_amount(after),
+        sign,
+        config.format_amount(&delta),
+        percent
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{change, Comparison};
+    use crate::{
+        archive::list_stored_files,
+        config::Config,
+        file::{BookkeepingFile, StoredFile},
+        stats::Stats,
+    };
+
+    #[test]
+    fn compares_two_months() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("09-2024"),
+            "05 + 100 Salary\n06 - 50 Rent\n07 - 10 Lunch #food\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("10-2024"),
+            "05 + 100 Salary\n06 - 50 Rent\n07 - 30 Lunch #food\n08 - 5 Bus\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let stats = |month| {
+            let stored = StoredFile::in_data(dir.path(), BookkeepingFile::new(month, 2024));
+            Stats::from_files(vec![stored], &config).unwrap()
+        };
+        let comparison = Comparison {
+            before: stats(9),
+            after: stats(10),
+        };
+
+        let zero = BigDecimal::from(0);
+        assert_eq!(
+            comparison.categories(),
+            [
+                ("food", BigDecimal::from(10), BigDecimal::from(30)),
+                ("Bus", zero.clone(), BigDecimal::from(5)),
+                ("Rent", BigDecimal::from(50), BigDecimal::from(50)),
+            ]
+        );
+
+        assert_eq!(
+            change(&BigDecimal::from(40), &BigDecimal::from(15), &config),
+            "R$ 40.00 → R$ 15.00 (R$ -25.00, -62%)"
+        );
+        assert_eq!(
+            change(&zero, &BigDecimal::from(5), &config),
+            "R$ 0.00 → R$ 5.00 (+R$ 5.00, new)"
+        );
+        assert_eq!(
+            change(&BigDecimal::from(-10), &BigDecimal::from(5), &config),
+            "R$ -10.00 → R$ 5.00 (+R$ 15.00)"
+        );
+    }
+
+    #[test]
+    fn aligns_years_by_month() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("01-2023"), "05 - 100 Rent\n").unwrap();
+        fs::write(dir.path().join("02-2023"), "05 - 100 Rent\n").unwrap();
+        fs::write(dir.path().join("01-2024"), "05 - 120 Rent\n").unwrap();
+        fs::write(dir.path().join("02-2024"), "05 - 150 Rent\n").unwrap();
+
+        let config = Config::default();
+        let stats = |year| {
+            let files = list_stored_files(dir.path())
+                .unwrap()
+                .into_iter()
+                .filter(|stored| stored.file.year() == year)
+                .collect();
+            Stats::from_files(files, &config).unwrap()
+        };
+        let comparison = Comparison {
+            before: stats(2023),
+            after: stats(2024),
+        };
+
+        let months = comparison.months();
+        assert_eq!(months.len(), 12);
+        assert_eq!(months[1], (2, BigDecimal::from(100), BigDecimal::from(150)));
+        assert_eq!(months[2], (3, BigDecimal::from(0), BigDecimal::from(0)));
+    }
+}
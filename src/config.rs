@@ -0,0 +1,227 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use bigdecimal::BigDecimal;
+use fs_err as fs;
+use serde::Deserialize;
+
+use crate::{
+    card::Card,
+    checklist::{default_steps, Step},
+    crypt::{Cipher, Encryption},
+    formatter::{AmountStyle, Formatter, StyledFormatter},
+    hooks::Hooks,
+    i18n::Language,
+    importers::Profile,
+    locale::Locale,
+    rules::{Normalization, Rule},
+    sqlite::Backend,
+    tax::TaxCategories,
+    writer::Durability,
+    Error, Result,
+};
+
+/// User settings, read from `config.toml` in the config folder
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// ISO 4217 code of the currency entries are recorded in by default
+    pub currency: String,
+    /// Symbol printed before amounts in the default currency
+    pub currency_symbol: String,
+    /// How much one unit of each foreign currency is worth in the default currency
+    pub rates: BTreeMap<String, BigDecimal>,
+    /// Separators accepted when reading amounts and used when printing them
+    pub locale: Locale,
+    /// Language of messages, `en` or `pt-BR`, following `LANG` when unset
+    ///
+    /// `--help` always follows `LANG`, as it's shown before config.toml is read.
+    pub language: Option<Language>,
+    /// How amounts are printed, on top of the locale's separators
+    pub amounts: AmountStyle,
+    /// How descriptions are compared against rule patterns
+    pub matching: Normalization,
+    /// Amounts above this, in the default currency, are only recorded after confirming them
+    pub confirm_above: Option<BigDecimal>,
+    /// Layouts of bank statements, for `porquinho import csv --profile NAME`
+    pub profiles: BTreeMap<String, Profile>,
+    /// Credit cards, whose purchases are paid all at once on the statement's due day
+    pub cards: BTreeMap<String, Card>,
+    /// Auto-categorization rules, tried in order
+    pub rules: Vec<Rule>,
+    /// Add the `#tag` of the matching rule to entries as they're recorded or imported
+    pub auto_tag: bool,
+    /// Percentage of the income each category may take, keyed by category
+    pub budgets: BTreeMap<String, BigDecimal>,
+    /// The most each month should spend, in the default currency
+    pub spending_target: Option<BigDecimal>,
+    /// Send a desktop notification when a `take` puts a category over its budget, or the
+    /// month over `spending_target`
+    pub notify: bool,
+    /// Warn after every command when the cash on hand, now or by the end of the month, is
+    /// below this
+    pub low_balance: Option<BigDecimal>,
+    /// Where `report --auto` writes reports, instead of the reports folder in the data folder
+    pub reports_folder: Option<PathBuf>,
+    /// The day budget months start on, e.g. payday, from 1 to 28
+    ///
+    /// Each month's file then runs up to the day before it in the next month, and is named
+    /// after the month it starts in.
+    pub month_starts_on: u8,
+    /// Monthly interest the book earns, in percent, recorded by `close` over the balance each
+    /// month ends with, e.g. for a savings book kept as a profile
+    pub interest_rate: Option<BigDecimal>,
+    /// The month fiscal years start in, from 1 to 12, for `porquinho quarter`
+    pub fiscal_year_starts_in: u32,
+    /// Categories counted by `porquinho tax-report`
+    pub tax: TaxCategories,
+    /// Where the book is kept: `files`, a file per month, or `sqlite`, a single database, which
+    /// can't be encrypted
+    pub storage: Backend,
+    /// Whether writes are flushed to disk before porquinho exits
+    pub durability: Durability,
+    /// How many backups of each bookkeeping file to keep, `0` disables them
+    pub backups: usize,
+    /// Track the data folder with git, committing after every change
+    pub git: bool,
+    /// Steps of `porquinho checklist`, done when closing each month
+    pub checklist: Vec<Step>,
+    /// Commands run as the book changes, to tell other programs about it
+    pub hooks: Hooks,
+    /// Post every entry recorded, as JSON, to this URL
+    pub webhook: Option<String>,
+    /// Token `porquinho serve` requires to record entries, sent as `Authorization: Bearer
+    /// TOKEN`, so that web pages open in a browser can't write to the book
+    pub serve_token: Option<String>,
+    /// Encrypt bookkeeping files with this key, `None` keeps them in plaintext
+    pub encryption: Option<Encryption>,
+    /// The key of `encryption`, once unlocked
+    #[serde(skip)]
+    pub cipher: Option<Cipher>,
+    /// Set by `--dry-run`: changes are shown instead of written
+    #[serde(skip)]
+    pub dry_run: bool,
+    /// Set by `--color`: whether output is colored
+    #[serde(skip)]
+    pub color: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            currency: "BRL".into(),
+            currency_symbol: "R$".into(),
+            rates: BTreeMap::new(),
+            locale: Locale::default(),
+            language: None,
+            amounts: AmountStyle::default(),
+            matching: Normalization::default(),
+            confirm_above: None,
+            profiles: BTreeMap::new(),
+            cards: BTreeMap::new(),
+            rules: Vec::new(),
+            auto_tag: true,
+            budgets: BTreeMap::new(),
+            spending_target: None,
+            notify: false,
+            low_balance: None,
+            reports_folder: None,
+            month_starts_on: 1,
+            fiscal_year_starts_in: 1,
+            tax: TaxCategories::default(),
+            interest_rate: None,
+            storage: Backend::default(),
+            durability: Durability::default(),
+            backups: 10,
+            git: false,
+            checklist: default_steps(),
+            hooks: Hooks::default(),
+            webhook: None,
+            serve_token: None,
+            encryption: None,
+            cipher: None,
+            dry_run: false,
+            color: false,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file at `path`, falling back to the defaults if it doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if path.exists() {
+            crate::debug!("reading the config from {}", path.display());
+            let contents = fs::read_to_string(path)?;
+            toml::from_str(&contents).map_err(|err| Error::Config(path.to_owned(), err))
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Unlocks the key of `encryption`, which may prompt for a passphrase
+    pub fn unlock(&mut self, data: &Path) -> Result<()> {
+        if let Some(encryption) = &self.encryption {
+            self.cipher = Some(Cipher::unlock(encryption, data, self.dry_run)?);
+        }
+
+        Ok(())
+    }
+
+    /// The formatter amounts are printed with
+    pub fn formatter(&self) -> StyledFormatter<'_> {
+        StyledFormatter {
+            locale: self.locale,
+            style: &self.amounts,
+        }
+    }
+
+    /// The import profile named `name`, either from config.toml or a built-in preset
+    pub fn profile(&self, name: &str) -> Option<Profile> {
+        self.profiles
+            .get(name)
+            .cloned()
+            .or_else(|| Profile::preset(name))
+    }
+
+    /// The card named `name` in the `[cards]` table
+    pub fn card(&self, name: &str) -> Result<&Card> {
+        self.cards
+            .get(name)
+            .ok_or_else(|| Error::Card(format!("there's no [cards.{}] in config.toml", name)))
+    }
+
+    /// Formats `amount` as money, e.g. `R$ 1.234,56`
+    pub fn format_amount(&self, amount: &BigDecimal) -> String {
+        self.formatter().format(amount, &self.currency_symbol)
+    }
+
+    /// Formats `amount` as money in `currency`, e.g. `USD 1,234.56`
+    pub fn format_amount_in(&self, amount: &BigDecimal, currency: &str) -> String {
+        if currency == self.currency {
+            self.format_amount(amount)
+        } else {
+            self.formatter().format(amount, currency)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn fills_missing_fields_with_defaults() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.currency_symbol, "R$");
+
+        let config: Config = toml::from_str("currency_symbol = \"US$\"").unwrap();
+        assert_eq!(config.currency_symbol, "US$");
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        assert!(toml::from_str::<Config>("symbol = \"US$\"").is_err());
+    }
+}
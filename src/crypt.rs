@@ -0,0 +1,189 @@
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use age::{
+    scrypt,
+    secrecy::{ExposeSecret, SecretString},
+    x25519,
+};
+use fs_err as fs;
+use serde::Deserialize;
+
+use crate::{Error, Result};
+
+/// Every age file starts with this line
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1";
+
+/// Holds the generated key of `Encryption::Passphrase`, in the data folder
+pub const KEY_FILE: &str = ".porquinho-key.age";
+
+/// Read before prompting, so scripts can unlock the book
+const PASSPHRASE_VAR: &str = "PORQUINHO_PASSPHRASE";
+
+/// Where the key bookkeeping files are encrypted with comes from
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Encryption {
+    /// A key generated on first use, kept in the data folder and locked with a passphrase
+    Passphrase,
+    /// The age key in this file, as created by `age-keygen`
+    Identity(PathBuf),
+}
+
+/// An unlocked key, which encrypts and decrypts bookkeeping files
+#[derive(Clone)]
+pub struct Cipher {
+    identity: x25519::Identity,
+}
+
+impl fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print the key
+        f.write_str("Cipher")
+    }
+}
+
+impl Cipher {
+    /// Loads the key described by `encryption`, creating it if it's a new passphrase one, or
+    /// only pretending to in a `dry_run`
+    pub fn unlock(encryption: &Encryption, data: &Path, dry_run: bool) -> Result<Self> {
+        let identity = match encryption {
+            Encryption::Identity(path) => parse_identity(&fs::read_to_string(path)?)?,
+            Encryption::Passphrase => {
+                let path = data.join(KEY_FILE);
+
+                if path.exists() {
+                    let passphrase = read_passphrase("Passphrase: ")?;
+                    let locked = fs::read(&path)?;
+                    let unlocked = age::decrypt(&scrypt::Identity::new(passphrase), &locked)
+                        .map_err(|_| Error::Encryption("wrong passphrase".into()))?;
+                    parse_identity(std::str::from_utf8(&unlocked)?)?
+                } else if dry_run {
+                    // Nothing is written in a dry run, so a key that's never kept will do
+                    println!("Would create a key in {}", path.display());
+                    x25519::Identity::generate()
+                } else {
+                    let passphrase = read_passphrase("New passphrase: ")?;
+                    let identity = x25519::Identity::generate();
+                    lock_identity(&identity, passphrase, &path)?;
+                    println!(
+                        "Created a key in {}, keep it along with the book",
+                        path.display()
+                    );
+                    identity
+                }
+            }
+        };
+
+        Ok(Self { identity })
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        age::encrypt(&self.identity.to_public(), plaintext)
+            .map_err(|err| Error::Encryption(err.to_string()))
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        age::decrypt(&self.identity, ciphertext).map_err(|err| Error::Encryption(err.to_string()))
+    }
+}
+
+pub fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(AGE_HEADER)
+}
+
+/// Whether the file at `path` exists and is encrypted, reading only its first line
+pub fn is_encrypted_file(path: &Path) -> Result<bool> {
+    let mut header = Vec::with_capacity(AGE_HEADER.len());
+    match fs::File::open(path) {
+        Ok(file) => file
+            .take(AGE_HEADER.len() as u64)
+            .read_to_end(&mut header)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(is_encrypted(&header))
+}
+
+/// Decrypts `contents` if they're encrypted, files written before encryption
+/// was turned on are returned as they are
+pub fn open(contents: Vec<u8>, cipher: Option<&Cipher>) -> Result<Vec<u8>> {
+    match cipher {
+        _ if !is_encrypted(&contents) => Ok(contents),
+        Some(cipher) => cipher.decrypt(&contents),
+        None => Err(Error::Encryption(
+            "the book is encrypted, but config.toml has no `encryption` set".into(),
+        )),
+    }
+}
+
+/// Encrypts `contents` if there's a `cipher`
+pub fn seal(contents: Vec<u8>, cipher: Option<&Cipher>) -> Result<Vec<u8>> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(&contents),
+        None => Ok(contents),
+    }
+}
+
+/// Reads the file at `path`, decrypted
+pub fn read_file(path: &Path, cipher: Option<&Cipher>) -> Result<Vec<u8>> {
+    open(fs::read(path)?, cipher)
+}
+
+fn parse_identity(contents: &str) -> Result<x25519::Identity> {
+    // Files created by `age-keygen` have comments before the key
+    contents
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("AGE-SECRET-KEY-"))
+        .and_then(|line| x25519::Identity::from_str(line).ok())
+        .ok_or_else(|| Error::Encryption("no age key found in the identity file".into()))
+}
+
+fn lock_identity(identity: &x25519::Identity, passphrase: SecretString, path: &Path) -> Result<()> {
+    let mut locked = Vec::new();
+    let mut writer = age::Encryptor::with_user_passphrase(passphrase).wrap_output(&mut locked)?;
+    writer.write_all(identity.to_string().expose_secret().as_bytes())?;
+    writer.finish()?;
+
+    fs::write(path, locked)?;
+
+    Ok(())
+}
+
+fn read_passphrase(prompt: &str) -> io::Result<SecretString> {
+    let passphrase = match std::env::var(PASSPHRASE_VAR) {
+        Ok(passphrase) => passphrase,
+        Err(_) => rpassword::prompt_password(prompt)?,
+    };
+
+    Ok(SecretString::from(passphrase))
+}
+
+#[cfg(test)]
+mod tests {
+    use age::x25519;
+
+    use super::{is_encrypted, open, seal, Cipher};
+
+    #[test]
+    fn seals_and_opens_files() {
+        let cipher = Cipher {
+            identity: x25519::Identity::generate(),
+        };
+        let plaintext = b"22 + 200.50 Payment\n".to_vec();
+
+        let sealed = seal(plaintext.clone(), Some(&cipher)).unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(open(sealed.clone(), Some(&cipher)).unwrap(), plaintext);
+        assert!(open(sealed, None).is_err());
+
+        // Files from before encryption was turned on are still readable
+        assert_eq!(open(plaintext.clone(), Some(&cipher)).unwrap(), plaintext);
+    }
+}
@@ -0,0 +1,98 @@
+use bigdecimal::{BigDecimal, One};
+
+use crate::{config::Config, Error, Result};
+
+/// Whether `code` looks like an ISO 4217 currency code, e.g. `USD`
+pub fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.bytes().all(|byte| byte.is_ascii_uppercase())
+}
+
+/// Converts amounts into a single currency, using the rates in the config
+pub struct Converter<'a> {
+    config: &'a Config,
+    target: &'a str,
+}
+
+impl<'a> Converter<'a> {
+    /// Converts into the book's default currency
+    pub fn new(config: &'a Config) -> Self {
+        Self::to(config, &config.currency)
+    }
+
+    /// Converts into `target`
+    pub fn to(config: &'a Config, target: &'a str) -> Self {
+        Self { config, target }
+    }
+
+    pub fn target(&self) -> &str {
+        self.target
+    }
+
+    /// Converts `amount` from `currency`, or from the default currency if it's `None`
+    pub fn convert(&self, amount: BigDecimal, currency: Option<&str>) -> Result<BigDecimal> {
+        let currency = currency.unwrap_or(&self.config.currency);
+
+        if currency == self.target {
+            return Ok(amount);
+        }
+
+        Ok(amount * self.rate(currency)? / self.rate(self.target)?)
+    }
+
+    /// How much one unit of `currency` is worth in the default currency
+    fn rate(&self, currency: &str) -> Result<BigDecimal> {
+        if currency == self.config.currency {
+            return Ok(BigDecimal::one());
+        }
+
+        self.config
+            .rates
+            .get(currency)
+            .cloned()
+            .ok_or_else(|| Error::NoExchangeRate(currency.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
+    use super::Converter;
+    use crate::config::Config;
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn converts_between_currencies() {
+        let config: Config = toml::from_str(
+            r#"
+            currency = "BRL"
+
+            [rates]
+            USD = "5.00"
+            EUR = "5.50"
+            "#,
+        )
+        .unwrap();
+
+        let to_brl = Converter::new(&config);
+        assert_eq!(to_brl.convert(decimal("10"), None).unwrap(), decimal("10"));
+        assert_eq!(
+            to_brl.convert(decimal("10"), Some("USD")).unwrap(),
+            decimal("50")
+        );
+
+        let to_usd = Converter::to(&config, "USD");
+        assert_eq!(to_usd.convert(decimal("50"), None).unwrap(), decimal("10"));
+        assert_eq!(
+            to_usd.convert(decimal("10"), Some("EUR")).unwrap(),
+            decimal("11")
+        );
+
+        assert!(to_brl.convert(decimal("10"), Some("JPY")).is_err());
+    }
+}
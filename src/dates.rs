@@ -0,0 +1,187 @@
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    OnceLock,
+};
+
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+
+use crate::file::BookkeepingFile;
+
+/// The date set by `--today`, if it was given
+static TODAY: OnceLock<NaiveDate> = OnceLock::new();
+
+/// Makes `today` return `date` instead of the real date, for the rest of the run
+///
+/// Only the first date set is kept.
+pub fn set_today(date: NaiveDate) {
+    let _ = TODAY.set(date);
+}
+
+/// The date entries are recorded on by default, in the local timezone, unless `--today`
+/// says otherwise
+pub fn today() -> NaiveDate {
+    TODAY
+        .get()
+        .copied()
+        .unwrap_or_else(|| Local::now().naive_local().date())
+}
+
+/// The day of the month of `today`
+pub fn current_day() -> u8 {
+    today().day() as u8
+}
+
+/// The day budget months start on, set by `month_starts_on` in config.toml
+static MONTH_START: AtomicU8 = AtomicU8::new(1);
+
+/// Makes budget months run from `day` of each month up to the day before it in the next one
+///
+/// Days past 28 would make some months have no start, so they're kept to 28.
+pub fn set_month_start(day: u8) {
+    MONTH_START.store(day.clamp(1, 28), Ordering::Relaxed);
+}
+
+/// The day budget months start on, `1` unless they follow a custom cycle
+pub fn month_start() -> u8 {
+    MONTH_START.load(Ordering::Relaxed)
+}
+
+/// The last day of the budget month of `file`, the day before the next one starts
+pub fn last_day(file: &BookkeepingFile) -> u8 {
+    match month_start() {
+        1 => file.days(),
+        start => start - 1,
+    }
+}
+
+/// The date the budget month of `file` starts on
+pub fn first_date(file: &BookkeepingFile) -> NaiveDate {
+    first_date_from(month_start(), file)
+}
+
+/// Like `first_date`, with budget months starting on `start`
+fn first_date_from(start: u8, file: &BookkeepingFile) -> NaiveDate {
+    // Safety: months start on the 28th at the latest, which every month has
+    NaiveDate::from_ymd_opt(file.year(), file.month(), start.into()).unwrap()
+}
+
+/// The date the budget month of `file` ends on, which is in the next month unless months
+/// start on the 1st
+pub fn last_date(file: &BookkeepingFile) -> NaiveDate {
+    last_date_from(month_start(), file)
+}
+
+/// Like `last_date`, with budget months starting on `start`
+fn last_date_from(start: u8, file: &BookkeepingFile) -> NaiveDate {
+    first_date_from(start, &file.next()).pred()
+}
+
+/// How far into the budget month of `file` its `day` is, counting from 1
+///
+/// A budget month is named after the month it starts in, so with months starting on the 5th,
+/// the file `09-2024` runs from September 5th to October 4th, and its day 3 is October 3rd.
+/// Days past the end of the month, such as `u8::MAX`, stand for its whole length.
+pub fn cycle_day(file: &BookkeepingFile, day: u8) -> u8 {
+    cycle_day_from(month_start(), file, day)
+}
+
+/// Like `cycle_day`, with budget months starting on `start`
+fn cycle_day_from(start: u8, file: &BookkeepingFile, day: u8) -> u8 {
+    let days = file.days();
+
+    if day >= start {
+        (day - start + 1).min(days)
+    } else {
+        days - start + 1 + day
+    }
+}
+
+/// Resolves a date given in the command line, relative to `today`
+///
+/// Accepts `today`, `yesterday`, a weekday as in `monday` or `mon` for its last occurrence,
+/// and `YYYY-MM-DD`. Dates after `today` are refused, as they can't have happened yet.
+pub fn parse_date(date: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let date = match date.to_lowercase().as_str() {
+        "today" => today,
+        "yesterday" => today.pred(),
+        other => match other.parse::<Weekday>() {
+            Ok(weekday) => {
+                let days_ago = (7 + today.weekday().num_days_from_monday()
+                    - weekday.num_days_from_monday())
+                    % 7;
+                today - Duration::days(days_ago.into())
+            }
+            Err(_) => NaiveDate::parse_from_str(other, "%Y-%m-%d").ok()?,
+        },
+    };
+
+    (date <= today).then_some(date)
+}
+
+/// The bookkeeping file `date` is recorded in, and its day of the month
+///
+/// Dates before `month_start` go into the budget month that started in the previous month.
+pub fn file_and_day(date: NaiveDate) -> (BookkeepingFile, u8) {
+    file_and_day_from(month_start(), date)
+}
+
+/// Like `file_and_day`, with budget months starting on `start`
+fn file_and_day_from(start: u8, date: NaiveDate) -> (BookkeepingFile, u8) {
+    let file = BookkeepingFile::new(date.month(), date.year());
+    let day = date.day() as u8;
+
+    if day < start {
+        (file.previous(), day)
+    } else {
+        (file, day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{cycle_day_from, file_and_day_from, last_date_from, parse_date};
+    use crate::file::BookkeepingFile;
+
+    #[test]
+    fn resolves_relative_dates() {
+        // A Wednesday
+        let today = NaiveDate::from_ymd(2024, 10, 2);
+        let parse = |date| parse_date(date, today).map(|date| date.to_string());
+
+        assert_eq!(parse("today").as_deref(), Some("2024-10-02"));
+        assert_eq!(parse("yesterday").as_deref(), Some("2024-10-01"));
+        assert_eq!(parse("Monday").as_deref(), Some("2024-09-30"));
+        assert_eq!(parse("wed").as_deref(), Some("2024-10-02"));
+        assert_eq!(parse("thursday").as_deref(), Some("2024-09-26"));
+        assert_eq!(parse("2024-09-15").as_deref(), Some("2024-09-15"));
+        assert_eq!(parse("2024-10-03"), None);
+        assert_eq!(parse("someday"), None);
+    }
+
+    #[test]
+    fn files_dates_into_budget_months() {
+        let september = BookkeepingFile::new(9, 2024);
+        assert_eq!(cycle_day_from(1, &september, 3), 3);
+
+        let (file, day) = file_and_day_from(5, NaiveDate::from_ymd(2024, 10, 3));
+        assert_eq!((file.as_str(), day), ("09-2024", 3));
+        let (file, day) = file_and_day_from(5, NaiveDate::from_ymd(2024, 10, 5));
+        assert_eq!((file.as_str(), day), ("10-2024", 5));
+
+        // September 5th to October 4th
+        assert_eq!(cycle_day_from(5, &september, 5), 1);
+        assert_eq!(cycle_day_from(5, &september, 30), 26);
+        assert_eq!(cycle_day_from(5, &september, 4), 30);
+        assert_eq!(cycle_day_from(5, &september, u8::MAX), 30);
+        assert_eq!(
+            last_date_from(5, &september),
+            NaiveDate::from_ymd(2024, 10, 4)
+        );
+        assert_eq!(
+            last_date_from(1, &september),
+            NaiveDate::from_ymd(2024, 9, 30)
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+
+use bigdecimal::{BigDecimal, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::Config, rules::Normalization, sidecar, Error, Result};
+
+/// What others owe for expenses split with them, kept in the data folder
+pub const DEBTS_FILE: &str = ".porquinho-debts.toml";
+
+/// A change to what someone owes: their share of a split expense, or a repayment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Movement {
+    pub person: String,
+    /// Positive for shares, negative for repayments
+    pub amount: BigDecimal,
+    pub description: String,
+    /// The month it happened in, in the `MM-YYYY` format
+    pub month: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Debts {
+    #[serde(default, rename = "movements")]
+    pub list: Vec<Movement>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// `amount` split evenly between me and `others`, as my share and each of theirs
+///
+/// Theirs are rounded down to cents, so that mine takes whatever's left over.
+pub fn split_evenly(amount: &BigDecimal, others: usize) -> (BigDecimal, BigDecimal) {
+    let people = BigDecimal::from(others as u64 + 1);
+    let theirs = (amount / people).with_scale(2);
+    let mine = amount - &theirs * BigDecimal::from(others as u64);
+
+    (mine, theirs)
+}
+
+/// The people in `with`, trimmed, each once however their name is written
+///
+/// Fails on empty names, as a debt nobody can be named to settle would be kept forever.
+pub fn people(with: &[String], normalization: &Normalization) -> Result<Vec<String>> {
+    let mut people: Vec<String> = Vec::new();
+
+    for person in with {
+        let person = person.trim();
+        if person.is_empty() || person.chars().any(char::is_control) {
+            return Err(Error::Debts(format!(
+                "{:?} isn't a name to split with",
+                person
+            )));
+        }
+
+        let key = normalization.normalize(person);
+        if !people
+            .iter()
+            .any(|other| normalization.normalize(other) == key)
+        {
+            people.push(person.to_owned());
+        }
+    }
+
+    Ok(people)
+}
+
+impl Debts {
+    pub fn load(dir: &Path, config: &Config) -> Result<Self> {
+        let path = dir.join(DEBTS_FILE);
+
+        let mut debts: Self = sidecar::load(&path, config, Error::Debts)?;
+        debts.path = path;
+
+        Ok(debts)
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        sidecar::save(&self.path, self, config, Error::Debts)
+    }
+
+    /// What each person owes, named as first written, leaving out the ones who are settled
+    pub fn balances(&self, normalization: &Normalization) -> Vec<(&str, BigDecimal)> {
+        let mut balances: Vec<(String, &str, BigDecimal)> = Vec::new();
+
+        for movement in &self.list {
+            let key = normalization.normalize(&movement.person);
+            match balances.iter_mut().find(|(other, ..)| *other == key) {
+                Some((_, _, balance)) => *balance += &movement.amount,
+                None => balances.push((key, &movement.person, movement.amount.clone())),
+            }
+        }
+
+        balances
+            .into_iter()
+            .filter(|(.., balance)| !balance.is_zero())
+            .map(|(_, person, balance)| (person, balance))
+            .collect()
+    }
+
+    /// What `person` owes, if anything, and their name as first written
+    pub fn owed_by(
+        &self,
+        person: &str,
+        normalization: &Normalization,
+    ) -> Result<(String, BigDecimal)> {
+        let wanted = normalization.normalize(person);
+
+        self.balances(normalization)
+            .into_iter()
+            .find(|(name, balance)| {
+                normalization.normalize(name) == wanted && *balance > BigDecimal::zero()
+            })
+            .map(|(name, balance)| (name.to_owned(), balance))
+            .ok_or_else(|| Error::Debts(format!("{} doesn't owe you anything", person)))
+    }
+
+    /// Prints what everyone owes
+    pub fn print(&self, config: &Config) {
+        let balances = self.balances(&config.matching);
+        if balances.is_empty() {
+            println!("Nobody owes you anything, split an expense with `porquinho split`");
+            return;
+        }
+
+        println!("Owed to you");
+        for (person, balance) in &balances {
+            println!("\t{}: {}", person, config.format_amount(balance));
+        }
+        let total: BigDecimal = balances.iter().map(|(_, balance)| balance).sum();
+        println!("\tTotal: {}", config.format_amount(&total));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use tempfile::TempDir;
+
+    use super::{people, split_evenly, Debts, Movement};
+    use crate::{config::Config, rules::Normalization};
+
+    fn movement(person: &str, amount: i64) -> Movement {
+        Movement {
+            person: person.into(),
+            amount: BigDecimal::from(amount),
+            description: "Dinner".into(),
+            month: "10-2024".into(),
+        }
+    }
+
+    #[test]
+    fn nets_what_each_one_owes() {
+        let (mine, theirs) = split_evenly(&BigDecimal::from(100), 2);
+        assert_eq!(theirs, "33.33".parse().unwrap());
+        assert_eq!(mine, "33.34".parse().unwrap());
+
+        let dir = TempDir::new().unwrap();
+        let config = Config::default();
+        let mut debts = Debts::load(dir.path(), &config).unwrap();
+        debts.list = vec![
+            movement("João", 30),
+            movement("Ana", 30),
+            movement("joao", 20),
+            movement("Ana", -30),
+        ];
+        debts.save(&config).unwrap();
+
+        let debts = Debts::load(dir.path(), &config).unwrap();
+        let normalization = Normalization::default();
+        assert_eq!(
+            debts.balances(&normalization),
+            [("João", BigDecimal::from(50))]
+        );
+        assert!(debts.owed_by("ana", &normalization).is_err());
+
+        let with = |names: &[&str]| {
+            let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+            people(&names, &normalization)
+        };
+        assert_eq!(
+            with(&[" Ana", "joão", "ANA", "Joao "]).unwrap(),
+            ["Ana", "joão"]
+        );
+        assert!(with(&["Ana", ""]).is_err());
+        assert!(with(&["Ana", "  "]).is_err());
+    }
+}
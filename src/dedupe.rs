@@ -0,0 +1,149 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, BufRead, Write},
+    path::Path,
+    str,
+};
+
+use crate::{
+    config::Config,
+    crypt,
+    i18n::{is_yes, tf},
+    parser::{Entry, Operation},
+    reader::Reader,
+    writer::Writer,
+    Result,
+};
+
+/// Whether `operation` records the same thing as `entry`, wherever it is in the file
+fn is_same(operation: &Operation, entry: &Entry) -> bool {
+    operation.day == entry.day
+        && operation.typ == entry.typ
+        && operation.amount == entry.amount
+        && operation.currency.as_deref() == entry.currency
+        && operation.description == entry.description
+}
+
+/// The first operation of the file at `path` identical to `entry`, if any
+pub fn find_duplicate(path: &Path, entry: &Entry, config: &Config) -> Result<Option<Operation>> {
+    let operations = Reader::new(config).operations(path)?;
+
+    Ok(duplicate_of(operations, entry))
+}
+
+/// The first of `operations` that's the same as `entry`, if any
+pub fn duplicate_of(operations: Vec<Operation>, entry: &Entry) -> Option<Operation> {
+    operations
+        .into_iter()
+        .find(|operation| is_same(operation, entry))
+}
+
+/// Groups of identical operations, each in file order, the ones without duplicates left out
+pub fn duplicate_groups(operations: &[Operation]) -> Vec<Vec<&Operation>> {
+    let mut groups: Vec<Vec<&Operation>> = Vec::new();
+
+    for operation in operations {
+        let entry = operation.as_entry();
+        match groups.iter_mut().find(|group| is_same(group[0], &entry)) {
+            Some(group) => group.push(operation),
+            None => groups.push(vec![operation]),
+        }
+    }
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+/// Goes through the duplicates of the file at `path`, asking whether to keep only the first
+/// of each, and returns how many entries were removed
+pub fn dedupe(path: &Path, config: &Config, mut input: impl BufRead) -> Result<usize> {
+    let operations = Reader::new(config).operations(path)?;
+    let mut removed = BTreeSet::new();
+
+    for group in duplicate_groups(&operations) {
+        println!("Found {} identical entries", group.len());
+        for operation in &group {
+            println!(
+                "\t#{} {:02} {} {}",
+                operation.id,
+                operation.day,
+                config.format_amount_in(
+                    &operation.amount,
+                    operation.currency.as_deref().unwrap_or(&config.currency)
+                ),
+                operation.description
+            );
+        }
+
+        print!("{} ", tf("Keep only #{}? [y/N/q]", &[&group[0].id]));
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        match answer.trim() {
+            answer if is_yes(answer) => {
+                removed.extend(group[1..].iter().map(|operation| operation.id))
+            }
+            "q" | "Q" => break,
+            _ => {}
+        }
+    }
+
+    if removed.is_empty() {
+        return Ok(0);
+    }
+
+    let contents = crypt::read_file(path, config.cipher.as_ref())?;
+    let mut kept = String::with_capacity(contents.len());
+    for (idx, line) in str::from_utf8(&contents)?.lines().enumerate() {
+        if !removed.contains(&(idx + 1)) {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    Writer::replace_contents(path, kept.into_bytes(), config)?;
+
+    Ok(removed.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{dedupe, find_duplicate};
+    use crate::{
+        config::Config,
+        parser::{Entry, EntryType},
+    };
+
+    #[test]
+    fn finds_and_removes_duplicates() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01-2023");
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        fs::write(
+            &path,
+            "05 - 12.00 Coffee\n05 + 12 Coffee\n05 - 12 Coffee\n06 - 3 Bus\n06 - 3 Bus\n",
+        )
+        .unwrap();
+
+        let coffee = Entry::new(5, EntryType::Debit, 12.into(), "Coffee");
+        assert_eq!(
+            find_duplicate(&path, &coffee, &config).unwrap().unwrap().id,
+            1
+        );
+        let lunch = Entry::new(5, EntryType::Debit, 12.into(), "Lunch");
+        assert!(find_duplicate(&path, &lunch, &config).unwrap().is_none());
+
+        // Keeps the coffees, merges the bus rides
+        assert_eq!(dedupe(&path, &config, "n\ny\n".as_bytes()).unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "05 - 12.00 Coffee\n05 + 12 Coffee\n05 - 12 Coffee\n06 - 3 Bus\n"
+        );
+    }
+}
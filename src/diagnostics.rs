@@ -0,0 +1,354 @@
+use std::str::FromStr;
+
+use crate::{i18n::t, parser::ParseError, Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// A stable identifier for everything porquinho may complain about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    MalformedEntry,
+    InvalidEntryType,
+    InvalidDay,
+    InvalidDecimal,
+    MissingDescription,
+    InvalidUtf8,
+    FileSystem,
+    NoHomeFolder,
+    CouldNotCreateFolder,
+    InvalidConfig,
+    InvalidAmount,
+    InvalidCurrency,
+    NoExchangeRate,
+    NoSuchEntry,
+    NegativeAmount,
+    NotConfirmed,
+    InvalidBatchLine,
+    InvalidYear,
+    InvalidDate,
+    DayNotInMonth,
+    AlreadyArchived,
+    NothingToArchive,
+    YearNotOver,
+    MonthNotOver,
+    InvalidMonth,
+    NoSuchMonth,
+    Interchange,
+    SelftestFailed,
+    TooLargeForQr,
+    Checklist,
+    NoSuchStep,
+    Editor,
+    BrokenLines,
+    Server,
+    Git,
+    GitDisabled,
+    Chart,
+    Balances,
+    Goals,
+    UnknownGoal,
+    Card,
+    Bills,
+    Pick,
+    Database,
+    UnknownDiagnostic,
+    Watch,
+    Hook,
+    Webhook,
+    InvalidQuarter,
+    Profile,
+    Debts,
+    CheckFailed,
+    InvalidQuery,
+    Sql,
+    Encryption,
+    InvalidDescription,
+    ProblemsFound,
+    UnknownFormat,
+    Overspent,
+    AheadOfLastMonth,
+    ImpossibleDay,
+    MisnamedFile,
+    Untidy,
+    Duplicate,
+    Misspelled,
+    LowBalance,
+    OverLimit,
+}
+
+/// Every code, its severity and a longer explanation, for `porquinho explain`
+const CODES: &[(Code, &str, Severity, &str)] = &[
+    (Code::MalformedEntry, "P001", Severity::Error, "A line of a bookkeeping file isn't in the `DD +/- AMOUNT DESCRIPTION` format."),
+    (Code::InvalidEntryType, "P002", Severity::Error, "An entry is neither a credit (`+`) nor a debit (`-`)."),
+    (Code::InvalidDay, "P003", Severity::Error, "An entry's day isn't a number between 0 and 255."),
+    (Code::InvalidDecimal, "P004", Severity::Error, "An entry's amount isn't a decimal number in the configured locale, nor arithmetic on such numbers, which may end in k or m."),
+    (Code::MissingDescription, "P005", Severity::Error, "An entry has no description after its amount."),
+    (Code::InvalidUtf8, "P006", Severity::Error, "A bookkeeping file isn't valid UTF-8 text."),
+    (Code::FileSystem, "P010", Severity::Error, "Reading or writing a file failed."),
+    (Code::NoHomeFolder, "P011", Severity::Error, "No home folder was found to keep porquinho's config and data in."),
+    (Code::CouldNotCreateFolder, "P012", Severity::Error, "porquinho's config or data folder couldn't be created."),
+    (Code::InvalidConfig, "P013", Severity::Error, "config.toml couldn't be parsed, or has unknown settings."),
+    (Code::InvalidAmount, "P020", Severity::Error, "An amount given in the command line isn't a decimal number in the configured locale."),
+    (Code::InvalidCurrency, "P021", Severity::Error, "A currency isn't an ISO 4217 code, such as USD or EUR."),
+    (Code::NoExchangeRate, "P022", Severity::Error, "An amount needed converting, but its currency has no rate in the [rates] table of config.toml."),
+    (Code::NoSuchEntry, "P023", Severity::Error, "There's no entry with the given number in this month's file."),
+    (Code::NegativeAmount, "P024", Severity::Error, "An amount, or an edit to one, comes out as zero or negative, such as `take 5-10`."),
+    (Code::NotConfirmed, "P025", Severity::Error, "An amount above `confirm_above` in config.toml, or a duplicate entry, wasn't confirmed, pass --yes to skip asking."),
+    (Code::InvalidBatchLine, "P026", Severity::Error, "A line given to `porquinho batch` isn't a `take` or `put` command, so the whole batch was left out."),
+    (Code::InvalidYear, "P027", Severity::Error, "A year given in the command line isn't a number, such as 2024."),
+    (Code::InvalidDate, "P028", Severity::Error, "A date given in the command line isn't YYYY-MM-DD, today, yesterday or a weekday, or it's in the future."),
+    (Code::DayNotInMonth, "P029", Severity::Error, "A day given with --day isn't in the current month, or hasn't come yet."),
+    (Code::AlreadyArchived, "P030", Severity::Error, "The year was already archived."),
+    (Code::NothingToArchive, "P031", Severity::Error, "The year has no bookkeeping files to archive."),
+    (Code::YearNotOver, "P032", Severity::Error, "Only years that are over can be archived."),
+    (Code::MonthNotOver, "P033", Severity::Error, "`close` only records the interest of months that are over, as their balance may still change."),
+    (Code::InvalidMonth, "P040", Severity::Error, "A month isn't in the MM-YYYY format, nor YYYY-MM or `last` where a `--month` is given."),
+    (Code::NoSuchMonth, "P041", Severity::Error, "There's no bookkeeping file for the month."),
+    (Code::Interchange, "P042", Severity::Error, "An export couldn't be written, or an import couldn't be read."),
+    (Code::SelftestFailed, "P043", Severity::Error, "Exporting and re-importing the book changed it."),
+    (Code::TooLargeForQr, "P044", Severity::Error, "The month has too many entries to fit in a QR code."),
+    (Code::Checklist, "P045", Severity::Error, "The file tracking which checklist steps were done is corrupted."),
+    (Code::NoSuchStep, "P046", Severity::Error, "There's no checklist step with the given number, see `porquinho checklist`."),
+    (Code::Editor, "P047", Severity::Error, "The editor set in $VISUAL or $EDITOR couldn't be run, or failed."),
+    (Code::BrokenLines, "P048", Severity::Error, "A file edited with `porquinho open`, or a block given to `porquinho paste`, had lines that aren't entries, so the changes were discarded."),
+    (Code::Server, "P049", Severity::Error, "`porquinho serve` couldn't listen on the given port, which may be taken."),
+    (Code::Git, "P050", Severity::Error, "A git command failed in the data folder."),
+    (Code::GitDisabled, "P051", Severity::Error, "The command needs `git = true` in config.toml."),
+    (Code::Chart, "P052", Severity::Error, "A chart couldn't be drawn, or its file doesn't end in .svg or .png."),
+    (Code::Balances, "P053", Severity::Error, "The file keeping the opening balance of each month is corrupted."),
+    (Code::Goals, "P054", Severity::Error, "The file keeping the savings goals is corrupted."),
+    (Code::UnknownGoal, "P055", Severity::Error, "No goal is called that, or several goals have it in their names, see `porquinho goal`."),
+    (Code::Card, "P056", Severity::Error, "A card isn't in the [cards] table of config.toml, or its statement was already paid or owes nothing."),
+    (Code::Bills, "P057", Severity::Error, "The file keeping the bills is corrupted, a bill's due day isn't a day of the month, or no bill is called that."),
+    (Code::Pick, "P058", Severity::Error, "A description given as @ couldn't be picked, as there's no terminal, nothing recorded yet, or the finder was closed."),
+    (Code::Database, "P059", Severity::Error, "The SQLite database of the book couldn't be read or written, or the command doesn't work with `storage = \"sqlite\"` yet, which can't be encrypted either."),
+    (Code::UnknownDiagnostic, "P060", Severity::Error, "A code given to `explain`, `--allow` or `--deny` doesn't exist."),
+    (Code::Watch, "P061", Severity::Error, "`status --watch` couldn't be told when files in the data folder change."),
+    (Code::Hook, "P062", Severity::Warning, "A command set in `[hooks]` of config.toml couldn't run or failed, after the change it was told about was made, which is kept."),
+    (Code::Webhook, "P063", Severity::Warning, "The `webhook` in config.toml couldn't be reached or didn't accept an entry, which was recorded anyway."),
+    (Code::InvalidQuarter, "P064", Severity::Error, "A quarter given to `porquinho quarter` isn't YYYY-QN, such as 2024-Q3."),
+    (Code::Profile, "P065", Severity::Error, "A profile given with --profile doesn't exist yet, or can't be created with that name."),
+    (Code::Debts, "P066", Severity::Error, "The file keeping what others owe is corrupted, or `settle` was given someone who owes nothing."),
+    (Code::CheckFailed, "P067", Severity::Error, "`porquinho check` found the balance or the budgets off, and exited with 1 without printing this."),
+    (Code::InvalidQuery, "P068", Severity::Error, "A query given to `porquinho query` couldn't be parsed, see `porquinho query --help` for what it may ask."),
+    (Code::Sql, "P069", Severity::Error, "A statement given to `porquinho sql` isn't valid SQL, or names columns that the `ops` table doesn't have."),
+    (Code::Encryption, "P070", Severity::Error, "A bookkeeping file couldn't be encrypted or decrypted, or the key couldn't be unlocked."),
+    (Code::InvalidDescription, "P071", Severity::Error, "A description is empty, has line breaks, or wouldn't read back as the description of its entry."),
+    (Code::ProblemsFound, "P080", Severity::Error, "`doctor` found problems it couldn't fix, which it listed before this error."),
+    (Code::UnknownFormat, "P090", Severity::Error, "The data folder was written by a newer porquinho, upgrade it before using this folder."),
+    (Code::Overspent, "P101", Severity::Warning, "More was spent than received this month."),
+    (Code::AheadOfLastMonth, "P102", Severity::Warning, "More was spent this month than by the same day of last month."),
+    (Code::ImpossibleDay, "P103", Severity::Warning, "An entry's day doesn't exist in its month, such as February 30."),
+    (Code::MisnamedFile, "P104", Severity::Warning, "A file in the data folder isn't named MM-YYYY, so porquinho ignores it."),
+    (Code::Untidy, "P105", Severity::Warning, "A bookkeeping file has blank lines, stray whitespace or no final line break."),
+    (Code::Duplicate, "P106", Severity::Warning, "An entry being recorded is identical to one already in the month, see `porquinho dedupe`."),
+    (Code::Misspelled, "P107", Severity::Warning, "An entry being recorded has a description very close to one used before, likely misspelled, see `porquinho rename`."),
+    (Code::LowBalance, "P108", Severity::Warning, "The cash on hand, or the one projected for the end of the month, is below `low_balance` in config.toml."),
+    (Code::OverLimit, "P109", Severity::Warning, "A take put its category over its budget, or the month over `spending_target` in config.toml."),
+];
+
+impl Code {
+    fn row(self) -> &'static (Code, &'static str, Severity, &'static str) {
+        // Safety: every code has a row in `CODES`
+        CODES.iter().find(|row| row.0 == self).unwrap()
+    }
+
+    /// Every code, errors first
+    pub fn all() -> impl Iterator<Item = Code> {
+        CODES.iter().map(|row| row.0)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        self.row().1
+    }
+
+    pub fn severity(self) -> Severity {
+        self.row().2
+    }
+
+    pub fn explanation(self) -> &'static str {
+        self.row().3
+    }
+}
+
+impl FromStr for Code {
+    type Err = Error;
+
+    fn from_str(code: &str) -> Result<Self> {
+        CODES
+            .iter()
+            .find(|row| row.1.eq_ignore_ascii_case(code))
+            .map(|row| row.0)
+            .ok_or_else(|| Error::UnknownCode(code.to_owned()))
+    }
+}
+
+impl Error {
+    pub fn code(&self) -> Code {
+        match self {
+            Self::NoValidHomeDirFound => Code::NoHomeFolder,
+            Self::CouldNotCreateFolder(_) => Code::CouldNotCreateFolder,
+            Self::FileSystem(_) => Code::FileSystem,
+            Self::Parse(err) => err.code(),
+            Self::InvalidAmount(_) => Code::InvalidAmount,
+            Self::InvalidCurrency(_) => Code::InvalidCurrency,
+            Self::NoExchangeRate(_) => Code::NoExchangeRate,
+            Self::NoSuchEntry(_) => Code::NoSuchEntry,
+            Self::NegativeAmount(_) => Code::NegativeAmount,
+            Self::NotConfirmed(_) => Code::NotConfirmed,
+            Self::InvalidBatchLine(..) => Code::InvalidBatchLine,
+            Self::InvalidYear(_) => Code::InvalidYear,
+            Self::InvalidDate(_) => Code::InvalidDate,
+            Self::DayNotInMonth(_) => Code::DayNotInMonth,
+            Self::AlreadyArchived(_) => Code::AlreadyArchived,
+            Self::NothingToArchive(_) => Code::NothingToArchive,
+            Self::YearNotOver(_) => Code::YearNotOver,
+            Self::MonthNotOver(_) => Code::MonthNotOver,
+            Self::InvalidMonth(_) => Code::InvalidMonth,
+            Self::Interchange(_) => Code::Interchange,
+            Self::SelftestFailed(_) => Code::SelftestFailed,
+            Self::TooLargeForQr(_) => Code::TooLargeForQr,
+            Self::NoSuchMonth(_) => Code::NoSuchMonth,
+            Self::Git(_) => Code::Git,
+            Self::GitDisabled => Code::GitDisabled,
+            Self::Config(..) => Code::InvalidConfig,
+            Self::Utf8(_) => Code::InvalidUtf8,
+            Self::UnknownCode(_) => Code::UnknownDiagnostic,
+            Self::Encryption(_) => Code::Encryption,
+            Self::ProblemsFound(_) => Code::ProblemsFound,
+            Self::UnknownFormat(_) => Code::UnknownFormat,
+            Self::Checklist(_) => Code::Checklist,
+            Self::NoSuchStep(_) => Code::NoSuchStep,
+            Self::Editor(_) => Code::Editor,
+            Self::BrokenLines(_) => Code::BrokenLines,
+            Self::Server(_) => Code::Server,
+            Self::Chart(_) => Code::Chart,
+            Self::Balances(_) => Code::Balances,
+            Self::Goals(_) => Code::Goals,
+            Self::UnknownGoal(_) => Code::UnknownGoal,
+            Self::Card(_) => Code::Card,
+            Self::Bills(_) => Code::Bills,
+            Self::Pick(_) => Code::Pick,
+            Self::InvalidDescription(_) => Code::InvalidDescription,
+            Self::Database(_) => Code::Database,
+            Self::Watch(_) => Code::Watch,
+            Self::Hook(..) => Code::Hook,
+            Self::Webhook(_) => Code::Webhook,
+            Self::InvalidQuarter(_) => Code::InvalidQuarter,
+            Self::InvalidQuery(_) => Code::InvalidQuery,
+            Self::Sql(_) => Code::Sql,
+            Self::Profile(_) => Code::Profile,
+            Self::Debts(_) => Code::Debts,
+            Self::CheckFailed => Code::CheckFailed,
+            Self::Denied(code, _) => *code,
+        }
+    }
+}
+
+impl ParseError {
+    pub fn code(&self) -> Code {
+        match self {
+            Self::InvalidEntryType(_) => Code::InvalidEntryType,
+            Self::InvalidDay(_) => Code::InvalidDay,
+            Self::InvalidDecimal(_) => Code::InvalidDecimal,
+            Self::NoDescription(_) => Code::MissingDescription,
+            Self::Malformed(_) => Code::MalformedEntry,
+        }
+    }
+}
+
+/// Decides what happens to warnings, following `--allow` and `--deny`
+#[derive(Default)]
+pub struct Diagnostics {
+    allowed: Vec<Code>,
+    denied: Vec<Code>,
+    deny_all: bool,
+}
+
+impl Diagnostics {
+    /// `allow` and `deny` hold codes, or `warnings` to mean all of them
+    pub fn new(allow: &[String], deny: &[String]) -> Result<Self> {
+        let mut this = Self::default();
+
+        for code in allow {
+            this.allowed.push(code.parse()?);
+        }
+        for code in deny {
+            if code == "warnings" {
+                this.deny_all = true;
+            } else {
+                this.denied.push(code.parse()?);
+            }
+        }
+
+        Ok(this)
+    }
+
+    /// Whether warnings with `code` are silenced with `--allow`
+    pub fn is_allowed(&self, code: Code) -> bool {
+        self.allowed.contains(&code)
+    }
+
+    /// Prints a warning, unless it's allowed, or fails if it's denied
+    ///
+    /// Returns whether the warning was printed.
+    pub fn warn(&self, code: Code, message: String) -> Result<bool> {
+        if self.is_allowed(code) {
+            return Ok(false);
+        }
+
+        if self.deny_all || self.denied.contains(&code) {
+            return Err(Error::Denied(code, message));
+        }
+
+        eprintln!("{}[{}]: {}", t("warning"), code.as_str(), message);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Code, Diagnostics, Severity, CODES};
+
+    #[test]
+    fn codes_are_unique_and_parse_back() {
+        // Listed in numeric order by `explain`
+        assert!(CODES.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        for (idx, (code, name, ..)) in CODES.iter().enumerate() {
+            assert!(CODES[idx + 1..].iter().all(|row| row.1 != *name));
+            assert_eq!(name.parse::<Code>().unwrap(), *code);
+        }
+
+        assert_eq!(Code::MalformedEntry.as_str(), "P001");
+        assert_eq!(Code::Overspent.severity(), Severity::Warning);
+        assert!("P999".parse::<Code>().is_err());
+    }
+
+    #[test]
+    fn denies_and_allows_warnings() {
+        let diagnostics = Diagnostics::new(&["P102".into()], &["P101".into()]).unwrap();
+        assert!(diagnostics.warn(Code::Overspent, String::new()).is_err());
+        assert!(!diagnostics
+            .warn(Code::AheadOfLastMonth, String::new())
+            .unwrap());
+
+        let deny_all = Diagnostics::new(&[], &["warnings".into()]).unwrap();
+        assert!(deny_all
+            .warn(Code::AheadOfLastMonth, String::new())
+            .is_err());
+    }
+}
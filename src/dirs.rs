@@ -5,19 +5,58 @@ use std::{
 
 use directories::ProjectDirs;
 use fs_err as fs;
+use tempfile::TempDir;
 
 use crate::{Error, Result};
 
 pub struct Dirs {
-    inner: ProjectDirs,
+    config: PathBuf,
+    data: PathBuf,
+    /// Where the folders of every profile but the default one are kept
+    profiles: ProfileDirs,
+    /// Keeps the throwaway data folder alive when running with `--ephemeral`
+    ephemeral: Option<TempDir>,
+}
+
+/// The folders holding a folder per profile, next to the default config and data folders
+/// rather than inside them, so that the default book never reads, backs up or commits theirs
+struct ProfileDirs {
+    config: PathBuf,
+    data: PathBuf,
 }
 
 impl Dirs {
-    pub fn init() -> Result<Self> {
+    /// The folders of `profile`, or of the default book when it's `None`
+    ///
+    /// Profiles other than the default one must have been created with `create_profile`.
+    pub fn init(profile: Option<&str>) -> Result<Self> {
         let inner =
             ProjectDirs::from("com", "vrmiguel", "porquinho").ok_or(Error::NoValidHomeDirFound)?;
+        let profiles = ProfileDirs {
+            config: sibling(inner.config_dir(), "profiles"),
+            data: sibling(inner.data_dir(), "profiles"),
+        };
 
-        let this = Self { inner };
+        let (config, data) = match profile {
+            Some(name) => {
+                validate_profile_name(name)?;
+                let data = profiles.data.join(name);
+                if !data.exists() {
+                    return Err(Error::Profile(format!(
+                        "there's no profile named {name}, create it with `porquinho profile create {name}`"
+                    )));
+                }
+                (profiles.config.join(name), data)
+            }
+            None => (inner.config_dir().to_owned(), inner.data_dir().to_owned()),
+        };
+
+        let this = Self {
+            config,
+            data,
+            profiles,
+            ephemeral: None,
+        };
 
         this.create_dir_if_not_existent(this.config())?;
         this.create_dir_if_not_existent(this.data())?;
@@ -25,21 +64,112 @@ impl Dirs {
         Ok(this)
     }
 
+    /// The names of every profile besides the default one, sorted
+    pub fn profiles(&self) -> Result<Vec<String>> {
+        if !self.profiles.data.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.profiles.data)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        names.sort();
+
+        Ok(names)
+    }
+
+    /// Creates the folders of the profile `name`, starting its config.toml as a copy of
+    /// `config`, if that exists, and returns its data folder
+    pub fn create_profile(&self, name: &str, config: &Path) -> Result<PathBuf> {
+        validate_profile_name(name)?;
+        let data = self.profiles.data.join(name);
+        if data.exists() {
+            return Err(Error::Profile(format!("{name} already exists")));
+        }
+
+        let config_dir = self.profiles.config.join(name);
+        self.create_dir_if_not_existent(&config_dir)?;
+        self.create_dir_if_not_existent(&data)?;
+        if config.exists() {
+            fs::copy(config, config_dir.join("config.toml"))?;
+        }
+
+        Ok(data)
+    }
+
+    /// Swaps the data folder for a temporary copy of it, which is deleted on drop
+    pub fn make_ephemeral(&mut self) -> Result<()> {
+        let temp = TempDir::new()?;
+        copy_dir_recursively(&self.data, temp.path())?;
+        crate::info!(
+            "info: running against a throwaway copy of the data folder at {:?}",
+            temp.path()
+        );
+
+        self.data = temp.path().to_owned();
+        self.ephemeral = Some(temp);
+
+        Ok(())
+    }
+
     fn create_dir_if_not_existent(&self, path: &Path) -> Result<()> {
         if path.exists().not() {
             fs::create_dir_all(path)
                 .map_err(|_| Error::CouldNotCreateFolder(PathBuf::from(path)))?;
-            println!("info: created folder {:?}", path);
+            crate::info!("info: created folder {:?}", path);
         }
 
         Ok(())
     }
 
     pub fn config(&self) -> &Path {
-        self.inner.config_dir()
+        &self.config
     }
 
     pub fn data(&self) -> &Path {
-        self.inner.data_dir()
+        &self.data
+    }
+}
+
+/// `path` with `-suffix` added to its last component, e.g. `porquinho-profiles`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(format!("-{suffix}"));
+
+    path.with_file_name(name)
+}
+
+/// Profile names become folder names, so only letters, digits, `-` and `_` are allowed
+fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name != "default"
+        && name
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || matches!(ch, '-' | '_'));
+    match valid {
+        true => Ok(()),
+        false => Err(Error::Profile(format!(
+            "'{name}' can't name a profile, use letters, digits, - and _"
+        ))),
+    }
+}
+
+fn copy_dir_recursively(from: &Path, to: &Path) -> Result<()> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            fs::create_dir_all(&target)?;
+            copy_dir_recursively(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
     }
+
+    Ok(())
 }
@@ -0,0 +1,289 @@
+use std::{path::Path, str};
+
+use chrono::NaiveDate;
+use fs_err as fs;
+
+use crate::{
+    archive::archive_year,
+    balances::BALANCES_FILE,
+    bills::BILLS_FILE,
+    card::CARDS_FOLDER,
+    checklist::CHECKLIST_FILE,
+    config::Config,
+    crypt::{self, KEY_FILE},
+    diagnostics::Code,
+    file::BookkeepingFile,
+    goals::GOALS_FILE,
+    i18n::tf,
+    index::INDEX_FILE,
+    migrations::VERSION_FILE,
+    parser::Entry,
+    report::REPORTS_FOLDER,
+    sqlite::DATABASE_FILE,
+    writer::Writer,
+    Error, Result,
+};
+
+/// Everything in the data folder that isn't a bookkeeping file, but belongs there
+const KNOWN_FILES: &[&str] = &[
+    "backups",
+    ".git",
+    ".gitignore",
+    KEY_FILE,
+    VERSION_FILE,
+    CHECKLIST_FILE,
+    BALANCES_FILE,
+    GOALS_FILE,
+    CARDS_FOLDER,
+    BILLS_FILE,
+    REPORTS_FOLDER,
+    DATABASE_FILE,
+    INDEX_FILE,
+];
+
+/// Something wrong found in the data folder
+pub struct Problem {
+    /// The file, and line if it's about an entry
+    pub location: String,
+    pub code: Code,
+    pub message: String,
+    /// Whether `--fix` took care of it
+    pub fixed: bool,
+}
+
+/// Checks every bookkeeping file in `dir`, fixing what can be fixed safely if `fix` is set
+pub fn examine(dir: &Path, config: &Config, fix: bool) -> Result<Vec<Problem>> {
+    let mut problems = Vec::new();
+
+    let mut names: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    names.sort();
+
+    for name in names {
+        if KNOWN_FILES.contains(&name.as_str()) || archive_year(&name).is_some() {
+            continue;
+        }
+
+        let file = match BookkeepingFile::from_file_name(&name) {
+            Some(file) => file,
+            None => {
+                problems.push(examine_name(dir, &name, config, fix)?);
+                continue;
+            }
+        };
+
+        problems.extend(examine_file(dir, &file, config, fix)?);
+    }
+
+    Ok(problems)
+}
+
+/// A file whose name isn't `MM-YYYY`, renamed if it looks like a month written differently
+fn examine_name(dir: &Path, name: &str, config: &Config, fix: bool) -> Result<Problem> {
+    let renamed = guess_month(name).filter(|file| !dir.join(file.as_path()).exists());
+
+    let message = match &renamed {
+        Some(file) => format!("not named MM-YYYY, should probably be {}", file.as_str()),
+        None => "not named MM-YYYY, so it's ignored".to_owned(),
+    };
+
+    let fixed = match renamed {
+        Some(file) if fix && config.dry_run => {
+            println!("Would rename {} to {}", name, file.as_str());
+            true
+        }
+        Some(file) if fix => {
+            fs::rename(dir.join(name), dir.join(file.as_path()))?;
+            true
+        }
+        _ => false,
+    };
+
+    Ok(Problem {
+        location: name.to_owned(),
+        code: Code::MisnamedFile,
+        message,
+        fixed,
+    })
+}
+
+/// Reads names such as `1-2024`, `01_2024` or `01-2024.txt` as a month
+fn guess_month(name: &str) -> Option<BookkeepingFile> {
+    let stem = name.split_once('.').map_or(name, |(stem, _)| stem);
+    let (month, year) = stem.split_once(['-', '_', ' '])?;
+
+    let month: u32 = month
+        .parse()
+        .ok()
+        .filter(|month| (1..=12).contains(month))?;
+    let year: i32 = year
+        .parse()
+        .ok()
+        .filter(|year| (1000..=9999).contains(year))?;
+
+    Some(BookkeepingFile::new(month, year))
+}
+
+fn examine_file(
+    dir: &Path,
+    file: &BookkeepingFile,
+    config: &Config,
+    fix: bool,
+) -> Result<Vec<Problem>> {
+    let path = dir.join(file.as_path());
+    let mut problems = Vec::new();
+    let mut problem = |line: Option<usize>, code, message: String, fixable| {
+        let location = match line {
+            Some(line) => format!("{}:{}", file.as_str(), line),
+            None => file.as_str().to_owned(),
+        };
+
+        problems.push(Problem {
+            location,
+            code,
+            message,
+            fixed: fixable && fix,
+        });
+    };
+
+    let contents = crypt::read_file(&path, config.cipher.as_ref())?;
+    let contents = match str::from_utf8(&contents) {
+        Ok(contents) => contents,
+        Err(err) => {
+            problem(None, Code::InvalidUtf8, err.to_string(), false);
+            return Ok(problems);
+        }
+    };
+
+    let mut tidied = String::with_capacity(contents.len());
+    for (idx, line) in contents.lines().enumerate() {
+        let number = Some(idx + 1);
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            problem(number, Code::Untidy, "blank line".into(), true);
+            continue;
+        }
+        if trimmed != line {
+            problem(
+                number,
+                Code::Untidy,
+                "whitespace around the entry".into(),
+                true,
+            );
+        }
+        tidied.push_str(trimmed);
+        tidied.push('\n');
+
+        match Entry::from_str(trimmed, config.locale) {
+            Ok(entry) => {
+                let date = NaiveDate::from_ymd_opt(file.year(), file.month(), entry.day.into());
+                if date.is_none() {
+                    let message = tf(
+                        "day {} doesn't exist in {}",
+                        &[&entry.day, &file.month_name()],
+                    );
+                    problem(number, Code::ImpossibleDay, message, false);
+                }
+            }
+            Err(err) => problem(number, err.code(), err.to_string(), false),
+        }
+    }
+
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        // The next entry would be glued to the last one
+        problem(
+            None,
+            Code::Untidy,
+            "doesn't end with a line break".into(),
+            true,
+        );
+    }
+
+    if fix && tidied != contents {
+        Writer::replace_contents(&path, tidied.into_bytes(), config)?;
+    }
+
+    Ok(problems)
+}
+
+/// Prints `problems`, failing if any of them is still there
+pub fn report(problems: &[Problem]) -> Result<()> {
+    for problem in problems {
+        let severity = problem.code.severity().as_str();
+        let fixed = if problem.fixed { " (fixed)" } else { "" };
+
+        println!(
+            "{}[{}]: {}: {}{}",
+            severity,
+            problem.code.as_str(),
+            problem.location,
+            problem.message,
+            fixed
+        );
+    }
+
+    let remaining = problems.iter().filter(|problem| !problem.fixed).count();
+    match remaining {
+        0 if problems.is_empty() => println!("Everything looks fine"),
+        0 => println!("Fixed every problem"),
+        _ => return Err(Error::ProblemsFound(remaining)),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::examine;
+    use crate::{config::Config, diagnostics::Code};
+
+    #[test]
+    fn finds_and_fixes_problems() {
+        let dir = TempDir::new().unwrap();
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        fs::write(
+            dir.path().join("02-2023"),
+            "1 + 10 Gift  \n\n30 - 5 Coffee\n2 - x Lunch",
+        )
+        .unwrap();
+        fs::write(dir.path().join("3-2023.txt"), "").unwrap();
+
+        let codes = |fix| -> Vec<_> {
+            examine(dir.path(), &config, fix)
+                .unwrap()
+                .into_iter()
+                .map(|problem| (problem.code, problem.fixed))
+                .collect()
+        };
+
+        assert_eq!(
+            codes(true),
+            [
+                (Code::Untidy, true),
+                (Code::Untidy, true),
+                (Code::ImpossibleDay, false),
+                (Code::InvalidDecimal, false),
+                (Code::Untidy, true),
+                (Code::MisnamedFile, true),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("02-2023")).unwrap(),
+            "1 + 10 Gift\n30 - 5 Coffee\n2 - x Lunch\n"
+        );
+        assert!(dir.path().join("03-2023").exists());
+
+        assert_eq!(
+            codes(false),
+            [(Code::ImpossibleDay, false), (Code::InvalidDecimal, false)]
+        );
+    }
+}
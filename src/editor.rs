@@ -0,0 +1,105 @@
+use std::{
+    env,
+    io::{self, BufRead, Write},
+    path::Path,
+    process::Command,
+    str,
+};
+
+use fs_err as fs;
+
+use crate::{
+    config::Config, crypt, i18n::t, locale::Locale, parser::Entry, writer::Writer, Error, Result,
+};
+
+/// The command files are edited with, from `$VISUAL` or `$EDITOR`
+fn editor() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_owned())
+}
+
+/// Lines of `contents` that aren't entries, numbered from 1, along with what's wrong with them
+pub fn broken_lines(contents: &str, locale: Locale) -> Vec<(usize, String)> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let err = Entry::from_str(line, locale).err()?;
+            Some((idx + 1, err.to_string()))
+        })
+        .collect()
+}
+
+/// Opens the bookkeeping file at `path` in the user's editor, keeping the changes only if
+/// every line is still a valid entry
+///
+/// The file is edited as a decrypted copy, so encrypted books can be edited too. The copy is
+/// kept in a private temporary folder, removed along with any swap or backup files the editor
+/// left next to it once editing ends, however it ends.
+pub fn edit_in_editor(path: &Path, config: &Config, mut input: impl BufRead) -> Result<()> {
+    let original = crypt::read_file(path, config.cipher.as_ref())?;
+
+    let folder = tempfile::Builder::new().prefix("porquinho-").tempdir()?;
+    let copy = folder.path().join(path.file_name().unwrap_or_default());
+    fs::write(&copy, &original)?;
+
+    let editor = editor();
+    let mut words = editor.split_whitespace();
+    // Safety: `editor` isn't blank
+    let program = words.next().unwrap();
+    let args: Vec<_> = words.collect();
+
+    loop {
+        let status = Command::new(program).args(&args).arg(&copy).status()?;
+        if !status.success() {
+            return Err(Error::Editor(format!("{} exited with {}", editor, status)));
+        }
+
+        let edited = fs::read(&copy)?;
+        let broken = broken_lines(str::from_utf8(&edited)?, config.locale);
+
+        if broken.is_empty() {
+            if edited == original {
+                crate::info!("Nothing changed");
+            } else {
+                Writer::replace_contents(path, edited, config)?;
+                if !config.dry_run {
+                    crate::info!("Updated {}", path.display());
+                }
+            }
+            return Ok(());
+        }
+
+        for (line, err) in &broken {
+            println!("line {}: {}", line, err);
+        }
+        print!("{} ", t("Edit again? [Y/n]"));
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        if matches!(answer.trim(), "n" | "N") {
+            return Err(Error::BrokenLines(broken.len()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::broken_lines;
+    use crate::locale::Locale;
+
+    #[test]
+    fn finds_broken_lines() {
+        let contents = "05 - 12 Coffee\n\n06 * 3 Bus\n07 + 10\n";
+
+        let lines: Vec<_> = broken_lines(contents, Locale::default())
+            .into_iter()
+            .map(|(line, _)| line)
+            .collect();
+        assert_eq!(lines, [2, 3, 4]);
+    }
+}
@@ -13,6 +13,107 @@ pub enum Error {
     FileSystem(#[from] io::Error),
     #[error("Parsing error: {0}")]
     Parse(#[from] crate::parser::ParseError),
+    #[error("'{0}' is not a valid amount")]
+    InvalidAmount(String),
+    #[error("'{0}' is not a valid currency code, expected something like 'USD'")]
+    InvalidCurrency(String),
+    #[error("No exchange rate for {0}, add it to the [rates] table of config.toml")]
+    NoExchangeRate(String),
+    #[error("There's no entry #{0} in this month's file")]
+    NoSuchEntry(usize),
+    #[error("Amounts must be above zero, but this one would be {0}")]
+    NegativeAmount(bigdecimal::BigDecimal),
+    #[error("Didn't record {0}, as it wasn't confirmed")]
+    NotConfirmed(String),
+    #[error("Line {0} of the batch: {1}, nothing was recorded")]
+    InvalidBatchLine(usize, String),
+    #[error("'{0}' is not a year")]
+    InvalidYear(String),
+    #[error("'{0}' is not a date up to today, expected YYYY-MM-DD, today, yesterday or a weekday")]
+    InvalidDate(String),
+    #[error("{0} is not a day of this month up to today")]
+    DayNotInMonth(u8),
+    #[error("{0} was already archived")]
+    AlreadyArchived(i32),
+    #[error("There are no bookkeeping files from {0} to archive")]
+    NothingToArchive(i32),
+    #[error("Can't archive {0}, as it's not over yet")]
+    YearNotOver(i32),
+    #[error("Can't record the interest {0} earned, as it's not over yet")]
+    MonthNotOver(String),
+    #[error("'{0}' is not a month in the MM-YYYY format")]
+    InvalidMonth(String),
+    #[error("'{0}' is not a quarter, expected YYYY-QN, such as 2024-Q3")]
+    InvalidQuarter(String),
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("Failed to export or import: {0}")]
+    Interchange(String),
+    #[error("{0} round-trip changed the book, its exports can't be trusted as backups")]
+    SelftestFailed(String),
+    #[error("This month needs {0} bytes, which is more than a QR code can hold")]
+    TooLargeForQr(usize),
+    #[error("There's no bookkeeping file for {0}")]
+    NoSuchMonth(String),
+    #[error("git: {0}")]
+    Git(String),
+    #[error("The book isn't tracked with git, set `git = true` in config.toml first")]
+    GitDisabled,
+    #[error("Invalid config file {0}: {1}")]
+    Config(PathBuf, toml::de::Error),
+    #[error("'{0}' is not a diagnostic code, see `porquinho explain`")]
+    UnknownCode(String),
+    #[error("{1} (denied with --deny)")]
+    Denied(crate::diagnostics::Code, String),
+    #[error("Encryption: {0}")]
+    Encryption(String),
+    #[error("{0} problem(s) remain")]
+    ProblemsFound(usize),
+    #[error("The data folder is in format {0}, which this version of porquinho doesn't know")]
+    UnknownFormat(String),
+    #[error("There's no step #{0} in the checklist")]
+    NoSuchStep(usize),
+    #[error("Invalid checklist progress file: {0}")]
+    Checklist(String),
+    #[error("Server: {0}")]
+    Server(String),
+    #[error("Editor: {0}")]
+    Editor(String),
+    #[error("Chart: {0}")]
+    Chart(String),
+    #[error("Invalid opening balances file: {0}")]
+    Balances(String),
+    #[error("Invalid goals file: {0}")]
+    Goals(String),
+    #[error("There's no single goal called '{0}'")]
+    UnknownGoal(String),
+    #[error("Debts: {0}")]
+    Debts(String),
+    #[error("Card: {0}")]
+    Card(String),
+    #[error("Bills: {0}")]
+    Bills(String),
+    #[error("Profiles: {0}")]
+    Profile(String),
+    #[error("Couldn't pick a description, as {0}")]
+    Pick(String),
+    #[error("{0:?} can't be a description, as it's empty or spans several lines")]
+    InvalidDescription(String),
+    #[error("Database: {0}")]
+    Database(String),
+    #[error("SQL: {0}")]
+    Sql(String),
+    #[error("Couldn't watch the data folder: {0}")]
+    Watch(String),
+    #[error("The hook `{0}` failed, after the change was made: {1}")]
+    Hook(String, String),
+    #[error("The entry was recorded, but the webhook failed: {0}")]
+    Webhook(String),
+    /// Ends `check` with exit code 1, without anything printed
+    #[error("The check failed")]
+    CheckFailed,
+    #[error("The changes were discarded, as {0} line(s) weren't valid entries")]
+    BrokenLines(usize),
     #[error("Invalid UTF-8: {0}")]
     Utf8(#[from] std::str::Utf8Error),
 }
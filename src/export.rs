@@ -0,0 +1,166 @@
+use std::io::{Read, Write};
+
+use bigdecimal::BigDecimal;
+use clap::ArgEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    file::StoredFile,
+    parser::{Entry, EntryType, Operation},
+    reader::Reader,
+    Error, Result,
+};
+
+/// Formats the whole book can be exported to and imported from
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Json,
+    Csv,
+    /// Only for exporting: bills and recurring entries as monthly calendar events
+    Ical,
+    /// Only for exporting: a spreadsheet with a sheet per month, and a summary of them
+    Xlsx,
+}
+
+/// A single operation, detached from the file it's recorded in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// The bookkeeping file, in the `MM-YYYY` format
+    pub month: String,
+    pub day: u8,
+    pub kind: EntryType,
+    pub amount: BigDecimal,
+    pub currency: Option<String>,
+    pub description: String,
+}
+
+impl Record {
+    pub fn new(month: &str, operation: Operation) -> Self {
+        Self {
+            month: month.to_owned(),
+            day: operation.day,
+            kind: operation.typ,
+            amount: operation.amount,
+            currency: operation.currency,
+            description: operation.description,
+        }
+    }
+
+    pub fn from_entry(month: &str, entry: &Entry) -> Self {
+        Self {
+            month: month.to_owned(),
+            day: entry.day,
+            kind: entry.typ,
+            amount: entry.amount.clone(),
+            currency: entry.currency.map(ToOwned::to_owned),
+            description: entry.description.to_owned(),
+        }
+    }
+}
+
+/// Reads every operation of `files`, in order
+pub fn collect_records(files: &[StoredFile], config: &Config) -> Result<Vec<Record>> {
+    let mut reader = Reader::new(config);
+    let mut records = Vec::new();
+
+    for stored in files {
+        let month = stored.file.as_str();
+        let mut id = 0;
+
+        reader.for_each_entry_in(stored.open()?, |entry| {
+            id += 1;
+            records.push(Record::new(month, Operation::from_entry(id, entry)));
+            Ok(())
+        })?;
+    }
+
+    Ok(records)
+}
+
+pub fn write_records(records: &[Record], format: Format, output: impl Write) -> Result<()> {
+    match format {
+        Format::Json => serde_json::to_writer_pretty(output, records)?,
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(output);
+            for record in records {
+                writer.serialize(record)?;
+            }
+            writer.flush()?;
+        }
+        Format::Ical | Format::Xlsx => return Err(export_only(format)),
+    }
+
+    Ok(())
+}
+
+pub fn read_records(format: Format, input: impl Read) -> Result<Vec<Record>> {
+    let records = match format {
+        Format::Json => serde_json::from_reader(input)?,
+        Format::Csv => csv::Reader::from_reader(input)
+            .deserialize()
+            .collect::<std::result::Result<_, _>>()?,
+        Format::Ical | Format::Xlsx => return Err(export_only(format)),
+    };
+
+    Ok(records)
+}
+
+/// Formats porquinho writes, but can't read back
+fn export_only(format: Format) -> Error {
+    let message = match format {
+        Format::Ical => "iCal holds bills and recurring entries, not every operation",
+        _ => "spreadsheets can be exported, but not imported",
+    };
+    Error::Interchange(message.to_owned())
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Interchange(err.to_string())
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(err: csv::Error) -> Self {
+        Self::Interchange(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
+    use super::{read_records, write_records, Format, Record};
+    use crate::parser::EntryType;
+
+    #[test]
+    fn round_trips_records() {
+        let records = vec![
+            Record {
+                month: "10-2024".into(),
+                day: 3,
+                kind: EntryType::Debit,
+                amount: BigDecimal::from_str("12.50").unwrap(),
+                currency: None,
+                description: "Coffee, and a \"croissant\"".into(),
+            },
+            Record {
+                month: "10-2024".into(),
+                day: 5,
+                kind: EntryType::Credit,
+                amount: BigDecimal::from_str("3000").unwrap(),
+                currency: Some("USD".into()),
+                description: "Salary".into(),
+            },
+        ];
+
+        for format in [Format::Json, Format::Csv] {
+            let mut buf = Vec::new();
+            write_records(&records, format, &mut buf).unwrap();
+            assert_eq!(read_records(format, buf.as_slice()).unwrap(), records);
+        }
+    }
+}
@@ -1,9 +1,20 @@
-use std::{io::Write, path::Path, str};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    str,
+};
 
-use chrono::{Datelike, Local};
+use chrono::{Datelike, NaiveDate};
 use fs_err as fs;
 
+use crate::{
+    dates,
+    i18n::{language, tf, Language},
+    Result,
+};
+
 /// Represents the filename of a Porquinho bookkeeping file
+#[derive(Clone)]
 pub struct BookkeepingFile {
     name: [u8; 7],
 }
@@ -12,12 +23,14 @@ impl BookkeepingFile {
     /// The bookkeeping file for this month
     /// E.g. if we're in October of 2024, the relevant file in which
     /// we'll record income and expenses is `10-2024`
+    ///
+    /// With months starting on a custom day, it's the budget month today falls in.
     pub fn current_file() -> Self {
-        let mut buf = [0; 7];
+        dates::file_and_day(dates::today()).0
+    }
 
-        let today = Local::today();
-        let month = today.month();
-        let year = today.year();
+    pub fn new(month: u32, year: i32) -> Self {
+        let mut buf = [0; 7];
 
         // Safety: should not fail until after the year 9999
         write!(&mut buf[..], "{:02}-{year}", month).unwrap();
@@ -25,12 +38,191 @@ impl BookkeepingFile {
         Self { name: buf }
     }
 
+    /// Parses a file name in the `MM-YYYY` format, returning `None` if
+    /// it's not the name of a bookkeeping file
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let (month, year) = name.split_once('-')?;
+
+        if month.len() != 2 || year.len() != 4 {
+            return None;
+        }
+
+        let month: u32 = month
+            .parse()
+            .ok()
+            .filter(|month| (1..=12).contains(month))?;
+        let year: i32 = year.parse().ok()?;
+
+        Some(Self::new(month, year))
+    }
+
+    /// Parses a month given in the command line: `MM-YYYY`, `YYYY-MM`, or `last` for the one
+    /// before `current`
+    pub fn parse(month: &str, current: &Self) -> Option<Self> {
+        if month == "last" {
+            return Some(current.previous());
+        }
+
+        match month.split_once('-') {
+            Some((year, month)) if year.len() == 4 && month.len() == 2 => {
+                Self::from_file_name(&format!("{}-{}", month, year))
+            }
+            _ => Self::from_file_name(month),
+        }
+    }
+
+    pub fn month(&self) -> u32 {
+        // Safety: `self.name` always starts with two ASCII digits
+        self.as_str()[..2].parse().unwrap()
+    }
+
+    pub fn year(&self) -> i32 {
+        // Safety: `self.name` always ends with four ASCII digits
+        self.as_str()[3..].parse().unwrap()
+    }
+
+    /// Months since January of year 0, handy for month arithmetic
+    pub fn ordinal(&self) -> i32 {
+        self.year() * 12 + self.month() as i32 - 1
+    }
+
+    /// The file of the month before this one
+    pub fn previous(&self) -> Self {
+        match self.month() {
+            1 => Self::new(12, self.year() - 1),
+            month => Self::new(month - 1, self.year()),
+        }
+    }
+
+    /// The file of the month after this one
+    pub fn next(&self) -> Self {
+        match self.month() {
+            12 => Self::new(1, self.year() + 1),
+            month => Self::new(month + 1, self.year()),
+        }
+    }
+
+    /// How many days this file's month has
+    pub fn days(&self) -> u8 {
+        let next = self.next();
+        // Safety: the first day of a month is always a valid date
+        let next = NaiveDate::from_ymd_opt(next.year(), next.month(), 1).unwrap();
+
+        next.pred().day() as u8
+    }
+
+    /// The name of this file's month, e.g. `October`, in the language messages are shown in
+    pub fn month_name(&self) -> &'static str {
+        const ENGLISH: [&str; 12] = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        const PORTUGUESE: [&str; 12] = [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ];
+
+        let names = match language() {
+            Language::English => ENGLISH,
+            Language::Portuguese => PORTUGUESE,
+        };
+        names[self.month() as usize - 1]
+    }
+
+    /// This file's month as shown in tables and charts, e.g. `Oct 2024`, in the language
+    /// messages are shown in
+    ///
+    /// Labels don't sort chronologically, so months must be sorted before being labeled.
+    pub fn label(&self) -> String {
+        self.label_in(language())
+    }
+
+    /// Like `label`, in `language`, e.g. `out 2024` in Portuguese
+    pub fn label_in(&self, language: Language) -> String {
+        const ENGLISH: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        const PORTUGUESE: [&str; 12] = [
+            "jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez",
+        ];
+
+        let names = match language {
+            Language::English => ENGLISH,
+            Language::Portuguese => PORTUGUESE,
+        };
+        format!("{} {}", names[self.month() as usize - 1], self.year())
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Safety: `new` must never make `self.name` be invalid UTF-8
+        unsafe { str::from_utf8_unchecked(&self.name) }
+    }
+
     pub fn as_path(&self) -> &Path {
-        // Safety: `current_file` must never make `self.name` be invalid UTF-8
-        let filename = unsafe { str::from_utf8_unchecked(&self.name) };
+        Path::new(self.as_str())
+    }
+}
+
+/// Where the contents of a bookkeeping file are kept
+pub enum Location {
+    /// A plain file in the data folder
+    Data(PathBuf),
+    /// A file extracted from a yearly archive
+    Archived(Vec<u8>),
+}
 
-        Path::new(filename)
+/// A bookkeeping file, along with where to read it from
+pub struct StoredFile {
+    pub file: BookkeepingFile,
+    pub location: Location,
+}
+
+impl StoredFile {
+    /// A plain file in the data folder `dir`
+    pub fn in_data(dir: &Path, file: BookkeepingFile) -> Self {
+        let location = Location::Data(dir.join(file.as_path()));
+
+        Self { file, location }
     }
+
+    pub fn open(&self) -> Result<Box<dyn Read + '_>> {
+        Ok(match &self.location {
+            Location::Data(path) => Box::new(fs::File::open(path)?),
+            Location::Archived(contents) => Box::new(contents.as_slice()),
+        })
+    }
+}
+
+/// Lists every bookkeeping file in `dir`, sorted chronologically
+pub fn list_bookkeeping_files(dir: &Path) -> Result<Vec<BookkeepingFile>> {
+    let mut files: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| BookkeepingFile::from_file_name(entry.file_name().to_str()?))
+        .collect();
+
+    files.sort_by_key(|file| (file.year(), file.month()));
+
+    Ok(files)
 }
 
 pub fn create_file_if_not_existent(path: &Path) {
@@ -40,6 +232,60 @@ pub fn create_file_if_not_existent(path: &Path) {
         .open(path)
         .is_ok()
     {
-        println!("Created {}", path.display());
+        crate::info!("{}", tf("Created {}", &[&path.display()]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BookkeepingFile;
+    use crate::i18n::Language;
+
+    #[test]
+    fn parses_bookkeeping_file_names() {
+        let file = BookkeepingFile::from_file_name("10-2024").unwrap();
+        assert_eq!((file.month(), file.year()), (10, 2024));
+
+        assert!(BookkeepingFile::from_file_name("13-2024").is_none());
+        assert!(BookkeepingFile::from_file_name("1-2024").is_none());
+        assert!(BookkeepingFile::from_file_name("notes.txt").is_none());
+    }
+
+    #[test]
+    fn parses_months_from_the_command_line() {
+        let current = BookkeepingFile::new(1, 2025);
+        let parse =
+            |month| BookkeepingFile::parse(month, &current).map(|file| file.as_str().to_owned());
+
+        assert_eq!(parse("10-2024").as_deref(), Some("10-2024"));
+        assert_eq!(parse("2024-10").as_deref(), Some("10-2024"));
+        assert_eq!(parse("last").as_deref(), Some("12-2024"));
+        assert_eq!(parse("2024-13"), None);
+        assert_eq!(parse("2024"), None);
+    }
+
+    #[test]
+    fn finds_the_previous_month() {
+        let january = BookkeepingFile::new(1, 2024);
+        assert_eq!(january.previous().as_str(), "12-2023");
+        assert_eq!(january.previous().month_name(), "December");
+        assert_eq!(january.previous().next().as_str(), "01-2024");
+        assert_eq!(
+            BookkeepingFile::new(10, 2024).previous().as_str(),
+            "09-2024"
+        );
+    }
+    #[test]
+    fn labels_months() {
+        let october = BookkeepingFile::new(10, 2024);
+        assert_eq!(october.label_in(Language::English), "Oct 2024");
+        assert_eq!(october.label_in(Language::Portuguese), "out 2024");
+    }
+
+    #[test]
+    fn counts_days_of_the_month() {
+        assert_eq!(BookkeepingFile::new(2, 2024).days(), 29);
+        assert_eq!(BookkeepingFile::new(2, 2023).days(), 28);
+        assert_eq!(BookkeepingFile::new(12, 2023).days(), 31);
     }
 }
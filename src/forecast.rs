@@ -0,0 +1,248 @@
+use std::{collections::HashSet, path::Path};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::Datelike;
+
+use crate::{
+    config::Config,
+    currency::Converter,
+    dates::{cycle_day, last_date, month_start},
+    file::BookkeepingFile,
+    i18n::{t, tf},
+    parser::{EntryType, Operation},
+    reader::Reader,
+    Result, Total,
+};
+
+/// Entries of `file` that were also in the month before it, once each
+///
+/// The same notion of recurring `Forecast` uses, for a month that's expected to be over.
+pub fn recurring(dir: &Path, file: &BookkeepingFile, config: &Config) -> Result<Vec<Operation>> {
+    let mut reader = Reader::new(config);
+    let mut read = |file: BookkeepingFile| {
+        let path = dir.join(file.as_path());
+        if path.exists() {
+            reader.operations(path)
+        } else {
+            Ok(Vec::new())
+        }
+    };
+
+    let previous: HashSet<_> = read(file.previous())?
+        .iter()
+        .map(|operation| config.matching.normalize(&operation.description))
+        .collect();
+
+    let mut seen = HashSet::new();
+    Ok(read(file.clone())?
+        .into_iter()
+        .filter(|operation| {
+            let description = config.matching.normalize(&operation.description);
+            previous.contains(&description) && seen.insert(description)
+        })
+        .collect())
+}
+
+/// A projection of how a month will end, from how it's going so far
+pub struct Forecast {
+    pub file: BookkeepingFile,
+    /// What was recorded up to now
+    pub total: Total,
+    /// Days of the month still ahead, not counting today
+    pub remaining_days: u8,
+    /// Spending per day so far, leaving out recurring entries
+    pub daily_spending: BigDecimal,
+    /// Entries of the previous month later than today that weren't recorded again yet
+    pub expected: Vec<Operation>,
+    /// The sum of `expected`, converted into the default currency
+    pub expected_total: Total,
+}
+
+impl Forecast {
+    /// Projects the end of `file` from the entries recorded until `day`
+    ///
+    /// Entries that also happened in the previous month are considered recurring: the ones
+    /// still missing are expected to happen again, and the others don't count towards the
+    /// daily spending, so that paying the rent early doesn't make every day look expensive.
+    pub fn compute(dir: &Path, file: BookkeepingFile, day: u8, config: &Config) -> Result<Self> {
+        let converter = Converter::new(config);
+        let mut reader = Reader::new(config);
+        let normalize = |operation: &Operation| config.matching.normalize(&operation.description);
+
+        let operations = reader.operations(dir.join(file.as_path()))?;
+        let previous_path = dir.join(file.previous().as_path());
+        let previous = if previous_path.exists() {
+            reader.operations(previous_path)?
+        } else {
+            Vec::new()
+        };
+
+        let recorded: HashSet<_> = operations.iter().map(normalize).collect();
+        let recurring: HashSet<_> = previous.iter().map(normalize).collect();
+
+        let mut total = Total::zero();
+        let mut variable_spending = BigDecimal::zero();
+        for operation in &operations {
+            let amount = convert(&converter, operation)?;
+            match operation.typ {
+                EntryType::Debit => {
+                    if !recurring.contains(&normalize(operation)) {
+                        variable_spending += &amount;
+                    }
+                    total.outgoing += amount;
+                }
+                EntryType::Credit => total.incoming += amount,
+                EntryType::Refund => {
+                    if !recurring.contains(&normalize(operation)) {
+                        variable_spending -= &amount;
+                    }
+                    total.outgoing -= amount;
+                }
+            }
+        }
+
+        let mut expected_total = Total::zero();
+        let expected: Vec<_> = previous
+            .into_iter()
+            .filter(|operation| {
+                cycle_day(&file.previous(), operation.day) > cycle_day(&file, day)
+                    && !recorded.contains(&normalize(operation))
+            })
+            .collect();
+        for operation in &expected {
+            let amount = convert(&converter, operation)?;
+            match operation.typ {
+                EntryType::Debit => expected_total.outgoing += amount,
+                EntryType::Credit => expected_total.incoming += amount,
+                EntryType::Refund => expected_total.outgoing -= amount,
+            }
+        }
+
+        let elapsed_days = cycle_day(&file, day).clamp(1, file.days());
+
+        Ok(Self {
+            remaining_days: file.days() - elapsed_days,
+            daily_spending: (variable_spending / BigDecimal::from(elapsed_days)).with_scale(2),
+            file,
+            total,
+            expected,
+            expected_total,
+        })
+    }
+
+    /// The balance the month is on track to end with
+    pub fn projected_balance(&self) -> BigDecimal {
+        self.total.balance() + self.expected_total.balance()
+            - &self.daily_spending * BigDecimal::from(self.remaining_days)
+    }
+
+    /// When the budget month ends, as in `the end of October`, or `day 4 of October` when
+    /// months start on another day
+    pub fn end(&self) -> String {
+        if month_start() == 1 {
+            return tf("the end of {}", &[&self.file.month_name()]);
+        }
+
+        let last = last_date(&self.file);
+        let month = BookkeepingFile::new(last.month(), last.year());
+        tf("day {} of {}", &[&last.day(), &month.month_name()])
+    }
+
+    pub fn print(&self, config: &Config) {
+        let month = self.file.month_name();
+        let end = self.end();
+
+        println!("{}", tf("Forecast for {}", &[&self.file.label()]));
+        println!(
+            "\t{}: {}",
+            t("Balance so far"),
+            config.format_amount(&self.total.balance())
+        );
+
+        if !self.expected.is_empty() {
+            println!(
+                "\t{}",
+                tf(
+                    "Still expected, as in {}:",
+                    &[&self.file.previous().month_name()]
+                )
+            );
+            for operation in &self.expected {
+                let sign = operation.typ.sign();
+                let currency = operation.currency.as_deref().unwrap_or(&config.currency);
+
+                println!(
+                    "\t\t{:02} {} {} {}",
+                    operation.day,
+                    sign,
+                    config.format_amount_in(&operation.amount, currency),
+                    operation.description
+                );
+            }
+        }
+
+        println!(
+            "\t{}",
+            tf(
+                "Spending {} a day for {} more day(s)",
+                &[
+                    &config.format_amount(&self.daily_spending),
+                    &self.remaining_days
+                ]
+            )
+        );
+
+        let projected = self.projected_balance();
+        println!(
+            "\t{}",
+            tf(
+                "Projected balance by {}: {}",
+                &[&end, &config.format_amount(&projected)]
+            )
+        );
+        if projected < BigDecimal::zero() {
+            println!("{}", tf("At this pace, {} ends in the red", &[&month]));
+        }
+    }
+}
+
+fn convert(converter: &Converter, operation: &Operation) -> Result<BigDecimal> {
+    converter.convert(operation.amount.clone(), operation.currency.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::Forecast;
+    use crate::{config::Config, file::BookkeepingFile};
+
+    #[test]
+    fn projects_the_end_of_the_month() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("03-2024"),
+            "05 + 3000 Salary\n10 - 1000 Rent\n20 - 100 Internet\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("04-2024"),
+            "05 + 3000 Salary\n06 - 1000 Rent\n08 - 50 Groceries\n09 - 40 Lunch\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let april = BookkeepingFile::new(4, 2024);
+        let forecast = Forecast::compute(dir.path(), april, 10, &config).unwrap();
+
+        // Only the internet bill is still to come, and the rent isn't part of the daily spending
+        assert_eq!(forecast.expected.len(), 1);
+        assert_eq!(forecast.expected_total.outgoing, BigDecimal::from(100));
+        assert_eq!(forecast.daily_spending, BigDecimal::from(9));
+        assert_eq!(forecast.remaining_days, 20);
+        // 1910 so far, minus the internet bill and 20 days at 9 a day
+        assert_eq!(forecast.projected_balance(), BigDecimal::from(1630));
+    }
+}
@@ -0,0 +1,151 @@
+use bigdecimal::{BigDecimal, Signed};
+use serde::Deserialize;
+
+use crate::locale::{format_digits, Locale, Rounding};
+
+/// Renders amounts of money for display
+///
+/// Every amount porquinho prints goes through one, see `Config::formatter`.
+pub trait Formatter {
+    /// Formats `amount` in `currency`, which is a symbol such as `R$` or a code such as `USD`
+    fn format(&self, amount: &BigDecimal, currency: &str) -> String;
+}
+
+/// Which side of the number the currency goes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SymbolPosition {
+    /// `R$ 5,00`
+    #[default]
+    Before,
+    /// `5,00 R$`
+    After,
+}
+
+/// How negative amounts are told apart
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NegativeStyle {
+    /// `R$ -5,00`
+    #[default]
+    Minus,
+    /// `-R$ 5,00`
+    LeadingMinus,
+    /// `(R$ 5,00)`, as accountants write them
+    Parentheses,
+}
+
+/// How amounts are printed, the `[amounts]` table of config.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AmountStyle {
+    pub symbol_position: SymbolPosition,
+    pub negative: NegativeStyle,
+    /// Whether there's a space between the currency and the number
+    pub spaced: bool,
+    /// Replaces the locale's decimal separator
+    pub decimal_separator: Option<char>,
+    /// Replaces the locale's thousands separator
+    pub thousands_separator: Option<char>,
+    /// How many decimal places amounts are printed with, e.g. `0` for currencies without
+    /// cents
+    pub decimals: u8,
+    /// How amounts are rounded to `decimals`
+    pub rounding: Rounding,
+}
+
+impl Default for AmountStyle {
+    fn default() -> Self {
+        Self {
+            symbol_position: SymbolPosition::default(),
+            negative: NegativeStyle::default(),
+            spaced: true,
+            decimal_separator: None,
+            thousands_separator: None,
+            decimals: 2,
+            rounding: Rounding::default(),
+        }
+    }
+}
+
+/// The `Formatter` configured in config.toml: the locale's separators, styled by `AmountStyle`
+pub struct StyledFormatter<'a> {
+    pub locale: Locale,
+    pub style: &'a AmountStyle,
+}
+
+impl Formatter for StyledFormatter<'_> {
+    fn format(&self, amount: &BigDecimal, currency: &str) -> String {
+        let style = self.style;
+        let digits = format_digits(
+            amount,
+            style.decimals,
+            style.rounding,
+            style
+                .decimal_separator
+                .unwrap_or_else(|| self.locale.decimal_separator()),
+            style
+                .thousands_separator
+                .unwrap_or_else(|| self.locale.thousands_separator()),
+        );
+
+        let negative = amount.is_negative();
+        let number = match style.negative {
+            NegativeStyle::Minus if negative => format!("-{}", digits),
+            _ => digits,
+        };
+
+        let space = if style.spaced { " " } else { "" };
+        let money = match style.symbol_position {
+            SymbolPosition::Before => format!("{}{}{}", currency, space, number),
+            SymbolPosition::After => format!("{}{}{}", number, space, currency),
+        };
+
+        match style.negative {
+            NegativeStyle::LeadingMinus if negative => format!("-{}", money),
+            NegativeStyle::Parentheses if negative => format!("({})", money),
+            _ => money,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
+    use super::{AmountStyle, Formatter, NegativeStyle, StyledFormatter, SymbolPosition};
+    use crate::locale::Locale;
+
+    #[test]
+    fn formats_amounts_following_the_style() {
+        let format = |style: &AmountStyle, amount: &str| {
+            let formatter = StyledFormatter {
+                locale: Locale::BrazilianPortuguese,
+                style,
+            };
+            formatter.format(&BigDecimal::from_str(amount).unwrap(), "R$")
+        };
+
+        let default = AmountStyle::default();
+        assert_eq!(format(&default, "1234.5"), "R$ 1.234,50");
+        assert_eq!(format(&default, "-5"), "R$ -5,00");
+
+        let european = AmountStyle {
+            symbol_position: SymbolPosition::After,
+            negative: NegativeStyle::LeadingMinus,
+            thousands_separator: Some(' '),
+            ..AmountStyle::default()
+        };
+        assert_eq!(format(&european, "-1234.5"), "-1 234,50 R$");
+
+        let accounting = AmountStyle {
+            negative: NegativeStyle::Parentheses,
+            spaced: false,
+            ..AmountStyle::default()
+        };
+        assert_eq!(format(&accounting, "-5"), "(R$5,00)");
+        assert_eq!(format(&accounting, "5"), "R$5,00");
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use fs_err as fs;
+
+use crate::{index::INDEX_FILE, Error, Result};
+
+/// Identity used for commits when the user hasn't configured one for git
+const FALLBACK_NAME: &str = "porquinho";
+const FALLBACK_EMAIL: &str = "porquinho@localhost";
+
+/// The data folder, tracked as a git repository
+pub struct Git {
+    dir: PathBuf,
+}
+
+impl Git {
+    /// Opens the repository in `dir`, turning `dir` into one if it isn't yet, unless `dry_run`
+    pub fn open(dir: &Path, dry_run: bool) -> Result<Self> {
+        let this = Self {
+            dir: dir.to_owned(),
+        };
+
+        if !dir.join(".git").exists() && dry_run {
+            println!("Would start tracking {:?} with git", dir);
+        } else if !dir.join(".git").exists() {
+            this.run(&["init", "--quiet"])?;
+            // Backups are already versions of the book, and the index is rebuilt from it, so
+            // there's no point in tracking them
+            fs::write(
+                dir.join(".gitignore"),
+                format!("backups/\n{}\n", INDEX_FILE),
+            )?;
+            this.commit("Start tracking the book")?;
+            println!("info: tracking {:?} with git", dir);
+        }
+
+        Ok(this)
+    }
+
+    /// Commits every change in the data folder, if there are any
+    pub fn commit(&self, message: &str) -> Result<()> {
+        self.run(&["add", "--all"])?;
+
+        let nothing_staged = self
+            .command(&["diff", "--cached", "--quiet"])
+            .status()?
+            .success();
+        if nothing_staged {
+            return Ok(());
+        }
+
+        let mut args = self.identity_args();
+        args.extend(["commit", "--quiet", "--message", message].map(String::from));
+
+        self.run(&args)?;
+
+        Ok(())
+    }
+
+    /// Prints the history of the book
+    pub fn log(&self) -> Result<()> {
+        // git reports its own errors, and fails when piped into e.g. `head` anyway
+        self.command(&["log", "--format=%h %ad %s", "--date=short"])
+            .status()?;
+
+        Ok(())
+    }
+
+    /// Undoes the changes made by `commit`, recording that as a new commit
+    pub fn revert(&self, commit: &str) -> Result<()> {
+        let status = self.run(&["status", "--porcelain"])?;
+        if !status.stdout.is_empty() {
+            return Err(Error::Git(
+                "the data folder has uncommitted changes, commit or discard them first".into(),
+            ));
+        }
+
+        let mut args = self.identity_args();
+        args.extend(["revert", "--no-edit", commit].map(String::from));
+
+        if let Err(err) = self.run(&args) {
+            // A conflicting revert leaves markers in the book, which would break parsing.
+            // The tree was clean before, so this only throws away the failed revert
+            let _ = self.run(&["reset", "--quiet", "--hard", "HEAD"]);
+            return Err(err);
+        }
+
+        println!("Reverted {}", commit);
+
+        Ok(())
+    }
+
+    /// Shows the changes `revert` would make, which are the commit's changes in reverse
+    pub fn preview_revert(&self, commit: &str) -> Result<()> {
+        let output = self.run(&[
+            "show",
+            "--reverse",
+            "-R",
+            "--format=Would revert %h %s",
+            commit,
+        ])?;
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+
+        Ok(())
+    }
+
+    /// Arguments giving git an identity to commit with, if the user hasn't configured one
+    fn identity_args(&self) -> Vec<String> {
+        let has_identity = self
+            .command(&["config", "user.email"])
+            .output()
+            .is_ok_and(|output| output.status.success());
+
+        if has_identity {
+            Vec::new()
+        } else {
+            vec![
+                "-c".to_owned(),
+                format!("user.name={FALLBACK_NAME}"),
+                "-c".to_owned(),
+                format!("user.email={FALLBACK_EMAIL}"),
+            ]
+        }
+    }
+
+    fn command(&self, args: &[impl AsRef<str>]) -> Command {
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args.iter().map(AsRef::as_ref));
+
+        command
+    }
+
+    fn run(&self, args: &[impl AsRef<str>]) -> Result<Output> {
+        let output = self.command(args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::Git(stderr.trim().to_owned()));
+        }
+
+        Ok(output)
+    }
+}
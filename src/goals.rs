@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+
+use bigdecimal::{BigDecimal, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    file::BookkeepingFile,
+    i18n::{t, tf},
+    rules::Normalization,
+    sidecar, Error, Result,
+};
+
+/// Savings goals and how much was put into them, kept in the data folder
+pub const GOALS_FILE: &str = ".porquinho-goals.toml";
+
+/// Something being saved for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub name: String,
+    /// How much it takes
+    pub target: BigDecimal,
+    /// The month it should be reached by, in the `MM-YYYY` format
+    pub by: String,
+    /// How much was put into it so far
+    pub saved: BigDecimal,
+}
+
+impl Goal {
+    /// How much of the target was saved, in percent
+    pub fn progress(&self) -> BigDecimal {
+        if self.target.is_zero() {
+            return BigDecimal::from(100);
+        }
+
+        (&self.saved * BigDecimal::from(100) / &self.target).with_scale(1)
+    }
+
+    /// How much has to be put in every month from `current` on to reach the target in time
+    ///
+    /// Returns `None` if the deadline has passed without reaching it.
+    pub fn monthly(&self, current: &BookkeepingFile) -> Option<BigDecimal> {
+        let missing = &self.target - &self.saved;
+        if missing <= BigDecimal::zero() {
+            return Some(BigDecimal::zero());
+        }
+
+        let by = BookkeepingFile::from_file_name(&self.by)?;
+        // The deadline's month counts, as there's still time to put money in it
+        let months = by.ordinal() - current.ordinal() + 1;
+        if months <= 0 {
+            return None;
+        }
+
+        Some((missing / BigDecimal::from(months)).with_scale(2))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Goals {
+    #[serde(default, rename = "goals")]
+    pub list: Vec<Goal>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Goals {
+    pub fn load(dir: &Path, config: &Config) -> Result<Self> {
+        let path = dir.join(GOALS_FILE);
+
+        let mut goals: Self = sidecar::load(&path, config, Error::Goals)?;
+        goals.path = path;
+
+        Ok(goals)
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        sidecar::save(&self.path, self, config, Error::Goals)
+    }
+
+    /// The goal called `name`, or else the only one whose name contains it, so "trip" finds
+    /// "Trip to Chile"
+    pub fn find(&mut self, name: &str, normalization: &Normalization) -> Result<&mut Goal> {
+        let wanted = normalization.normalize(name);
+        let normalized: Vec<_> = self
+            .list
+            .iter()
+            .map(|goal| normalization.normalize(&goal.name))
+            .collect();
+
+        let idx = match normalized.iter().position(|goal| *goal == wanted) {
+            Some(idx) => idx,
+            None => {
+                let mut matches = normalized
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, goal)| goal.contains(&wanted))
+                    .map(|(idx, _)| idx);
+
+                match (matches.next(), matches.next()) {
+                    (Some(idx), None) => idx,
+                    _ => return Err(Error::UnknownGoal(name.to_owned())),
+                }
+            }
+        };
+
+        Ok(&mut self.list[idx])
+    }
+
+    /// Prints every goal with its progress, and how much it needs from `current` on
+    pub fn print(&self, current: &BookkeepingFile, config: &Config) {
+        if self.list.is_empty() {
+            println!("{}", t("No goals yet, add one with `porquinho goal add`"));
+            return;
+        }
+
+        println!("{}", t("Goals"));
+        for goal in &self.list {
+            let pace = match goal.monthly(current) {
+                Some(monthly) if monthly.is_zero() => t("reached").to_owned(),
+                Some(monthly) => tf(
+                    "{} a month to get there",
+                    &[&config.format_amount(&monthly)],
+                ),
+                None => t("overdue").to_owned(),
+            };
+
+            println!(
+                "\t{}: {}",
+                goal.name,
+                tf(
+                    "{} of {} ({}%) by {}, {}",
+                    &[
+                        &config.format_amount(&goal.saved),
+                        &config.format_amount(&goal.target),
+                        &goal.progress(),
+                        &goal.by,
+                        &pace
+                    ]
+                )
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use tempfile::TempDir;
+
+    use super::{Goal, Goals};
+    use crate::{config::Config, file::BookkeepingFile, rules::Normalization};
+
+    #[test]
+    fn tracks_progress_towards_goals() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::default();
+        let mut goals = Goals::load(dir.path(), &config).unwrap();
+        let goal = |name: &str| Goal {
+            name: name.to_owned(),
+            target: BigDecimal::from(5000),
+            by: "12-2025".to_owned(),
+            saved: BigDecimal::from(0),
+        };
+        goals.list.push(goal("Trip to Chile"));
+        goals.list.push(goal("Trip to Peru"));
+        goals.list.push(goal("Laptop"));
+        goals.save(&config).unwrap();
+
+        let mut goals = Goals::load(dir.path(), &config).unwrap();
+        let normalization = Normalization::default();
+        assert!(goals.find("trip", &normalization).is_err());
+        goals.find("chile", &normalization).unwrap().saved += BigDecimal::from(500);
+
+        let chile = &goals.list[0];
+        assert_eq!(chile.progress(), BigDecimal::from(10));
+        // 4500 left over the 10 months from March to December
+        let march = BookkeepingFile::new(3, 2025);
+        assert_eq!(chile.monthly(&march), Some(BigDecimal::from(450)));
+        assert_eq!(
+            chile.monthly(&BookkeepingFile::new(7, 2025)),
+            Some(BigDecimal::from(750))
+        );
+        assert_eq!(chile.monthly(&BookkeepingFile::new(1, 2026)), None);
+    }
+}
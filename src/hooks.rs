@@ -0,0 +1,105 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    export::Record,
+    i18n::{language, t},
+    webhook, Error, Result,
+};
+
+/// External commands run as the book changes, with what changed as JSON on their stdin
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hooks {
+    /// Run after `take`, `put` or `refund` records an entry
+    pub on_add: Option<String>,
+    /// Run after `close` carries a month's balance into the next one
+    pub on_close_month: Option<String>,
+}
+
+/// Tells the `on_add` hook and the `webhook` about entries just recorded
+///
+/// They were recorded already, so a hook or webhook failing is only warned about, and the
+/// change is still committed to git.
+pub fn recorded(records: &[Record], config: &Config) {
+    if config.dry_run {
+        return;
+    }
+
+    for record in records {
+        if let Some(hook) = &config.hooks.on_add {
+            warn_on_failure(run_hook("on_add", hook, record));
+        }
+        if let Some(url) = &config.webhook {
+            warn_on_failure(webhook::post(url, record));
+        }
+    }
+}
+
+/// Prints the error of a hook that ran after its change was made, as a warning
+pub fn warn_on_failure(result: Result<()>) {
+    if let Err(err) = result {
+        eprintln!(
+            "{}[{}]: {}",
+            t("warning"),
+            err.code().as_str(),
+            language().error(&err)
+        );
+    }
+}
+
+/// Runs the hook `name`, set to `command`, through `sh`, feeding it `payload` as JSON
+///
+/// Hooks run after the change was made, so a failing one doesn't undo it.
+pub fn run_hook(name: &str, command: &str, payload: &impl Serialize) -> Result<()> {
+    let failed = |reason: String| Error::Hook(name.to_owned(), reason);
+
+    if command.trim().is_empty() {
+        return Err(failed("its command is empty".into()));
+    }
+
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|err| failed(format!("`{}` couldn't run: {}", command, err)))?;
+
+    let json = serde_json::to_vec(payload)?;
+    // Safety: stdin was piped above
+    let mut stdin = child.stdin.take().unwrap();
+    // Hooks that don't read their input close it early, which is fine
+    let _ = stdin.write_all(&json);
+    drop(stdin);
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(failed(format!("`{}` exited with {}", command, status)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_hook;
+    use crate::Error;
+
+    #[test]
+    fn fails_with_the_hook() {
+        run_hook("on_add", "cat", &[1, 2, 3]).unwrap();
+        // Commands run through the shell, so quotes and pipes work
+        run_hook("on_add", "grep -q '\\[1, 2' || exit 1", &[1, 2, 3]).unwrap_err();
+        run_hook("on_add", "tr -d ' ' | grep -q '\\[1,2'", &[1, 2]).unwrap();
+        assert!(matches!(
+            run_hook("on_add", "false", &()),
+            Err(Error::Hook(name, _)) if name == "on_add"
+        ));
+        assert!(run_hook("on_add", " ", &()).is_err());
+    }
+}
@@ -0,0 +1,403 @@
+//! The language porquinho talks in, set once from config.toml or the `LANG` environment
+//! variable
+//!
+//! Messages are written in English, and looked up by that text in the table of the other
+//! languages, so that a message missing from a table is still shown, in English.
+
+use std::{
+    env,
+    fmt::Display,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use serde::Deserialize;
+
+use crate::{parser::ParseError, Error};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "pt-BR")]
+    Portuguese,
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        0 => Language::English,
+        _ => Language::Portuguese,
+    }
+}
+
+/// `english`, in the language set with `set_language`
+pub fn t(english: &'static str) -> &'static str {
+    language().text(english)
+}
+
+/// Like `t`, filling each `{}` of the message with `args`, in order
+pub fn tf(english: &'static str, args: &[&dyn Display]) -> String {
+    fill(t(english), args)
+}
+
+/// Whether `answer` to a yes or no question is a yes, in any language
+pub fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim(), "y" | "Y" | "s" | "S")
+}
+
+impl Language {
+    /// The language of the environment, following `LC_ALL`, `LC_MESSAGES` and `LANG`
+    pub fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+
+        if locale.starts_with("pt") {
+            Self::Portuguese
+        } else {
+            Self::English
+        }
+    }
+
+    /// `english` in this language, or as it is if it wasn't translated
+    pub fn text(self, english: &'static str) -> &'static str {
+        let table = match self {
+            Self::English => return english,
+            Self::Portuguese => PORTUGUESE,
+        };
+
+        table
+            .iter()
+            .find(|(original, _)| *original == english)
+            .map_or(english, |(_, translated)| translated)
+    }
+
+    /// What `err` says, in this language
+    ///
+    /// Details that come from outside porquinho, such as the ones of the operating system, are
+    /// kept as they were given.
+    pub fn error(self, err: &Error) -> String {
+        if self == Self::English {
+            return err.to_string();
+        }
+
+        match err {
+            Error::NoValidHomeDirFound => "Não foi possível encontrar a pasta pessoal".into(),
+            Error::CouldNotCreateFolder(path) => {
+                format!("Não foi possível criar a pasta {}", path.display())
+            }
+            Error::FileSystem(err) => format!("Erro no sistema de arquivos: {}", err),
+            Error::Parse(err) => format!("Erro de leitura: {}", self.parse_error(err)),
+            Error::InvalidAmount(amount) => format!("'{}' não é um valor válido", amount),
+            Error::InvalidCurrency(code) => format!(
+                "'{}' não é um código de moeda válido, como 'USD'",
+                code
+            ),
+            Error::NoExchangeRate(code) => format!(
+                "Não há cotação para {}, adicione-a à tabela [rates] do config.toml",
+                code
+            ),
+            Error::NoSuchEntry(id) => format!("Não há lançamento #{} no arquivo deste mês", id),
+            Error::NegativeAmount(amount) => format!(
+                "Valores devem ser maiores que zero, mas este seria {}",
+                amount
+            ),
+            Error::NotConfirmed(what) => format!("{} não foi registrado, pois não foi confirmado", what),
+            Error::InvalidBatchLine(line, err) => {
+                format!("Linha {} do lote: {}, nada foi registrado", line, err)
+            }
+            Error::InvalidYear(year) => format!("'{}' não é um ano", year),
+            Error::InvalidDate(date) => format!(
+                "'{}' não é uma data até hoje, use AAAA-MM-DD, today, yesterday ou um dia da semana",
+                date
+            ),
+            Error::DayNotInMonth(day) => format!("{} não é um dia deste mês até hoje", day),
+            Error::AlreadyArchived(year) => format!("{} já foi arquivado", year),
+            Error::NothingToArchive(year) => {
+                format!("Não há arquivos de {} para arquivar", year)
+            }
+            Error::YearNotOver(year) => {
+                format!("Não é possível arquivar {}, pois ele ainda não acabou", year)
+            }
+            Error::MonthNotOver(month) => format!(
+                "Não é possível registrar os juros de {}, pois ele ainda não acabou",
+                month
+            ),
+            Error::InvalidMonth(month) => format!("'{}' não é um mês no formato MM-AAAA", month),
+            Error::NoSuchMonth(month) => format!("Não há arquivo para {}", month),
+            Error::InvalidQuarter(quarter) => format!(
+                "'{}' não é um trimestre, use AAAA-QN, como 2024-Q3",
+                quarter
+            ),
+            Error::GitDisabled => {
+                "O livro não é versionado com git, defina `git = true` no config.toml antes".into()
+            }
+            Error::Config(path, err) => {
+                format!("Arquivo de configuração {} inválido: {}", path.display(), err)
+            }
+            Error::UnknownCode(code) => {
+                format!("'{}' não é um código de diagnóstico, veja `porquinho explain`", code)
+            }
+            Error::Denied(_, message) => format!("{} (negado com --deny)", message),
+            Error::ProblemsFound(count) => format!("Restam {} problema(s)", count),
+            Error::NoSuchStep(step) => format!("Não há passo #{} no checklist", step),
+            Error::UnknownGoal(name) => format!("Não há uma única meta chamada '{}'", name),
+            Error::InvalidDescription(description) => format!(
+                "{:?} não pode ser uma descrição, pois está vazia ou tem várias linhas",
+                description
+            ),
+            Error::BrokenLines(count) => format!(
+                "As mudanças foram descartadas, pois {} linha(s) não eram lançamentos válidos",
+                count
+            ),
+            _ => err.to_string(),
+        }
+    }
+
+    fn parse_error(self, err: &ParseError) -> String {
+        match err {
+            ParseError::InvalidEntryType(typ) => {
+                format!("'{}' não é um tipo de lançamento válido", typ)
+            }
+            ParseError::InvalidDay(day) => format!("'{}' não é um dia do mês válido", day),
+            ParseError::InvalidDecimal(decimal) => format!("'{}' não é um número", decimal),
+            ParseError::NoDescription(after) => format!("Falta a descrição depois de '{}'", after),
+            ParseError::Malformed(line) => format!("Lançamento malformado: '{}'", line),
+        }
+    }
+}
+
+/// Replaces each `{}` of `message` with one of `args`, in order
+fn fill(message: &str, args: &[&dyn Display]) -> String {
+    let mut filled = String::with_capacity(message.len());
+    let mut args = args.iter();
+    let mut pieces = message.split("{}");
+
+    if let Some(first) = pieces.next() {
+        filled.push_str(first);
+    }
+    for piece in pieces {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(piece);
+    }
+
+    filled
+}
+
+/// Messages in Brazilian Portuguese, by their English text
+#[rustfmt::skip]
+const PORTUGUESE: &[(&str, &str)] = &[
+    // Diagnostics
+    ("error", "erro"),
+    ("warning", "aviso"),
+
+    // Prompts
+    ("[y/N]", "[s/N]"),
+    ("[Y/n]", "[S/n]"),
+    ("Done? [y/N/q]", "Concluído? [s/N/q]"),
+    ("Keep only #{}? [y/N/q]", "Manter só o #{}? [s/N/q]"),
+    ("Edit again? [Y/n]", "Editar de novo? [S/n]"),
+    ("Add them to {}? [Y/n]", "Adicioná-las a {}? [S/n]"),
+    ("Updated {}", "{} atualizado"),
+    ("{} ended with {}, carried into {}", "{} terminou com {}, levado para {}"),
+
+    // Status
+    ("Status for {}", "Situação de {}"),
+    ("Opening balance", "Saldo inicial"),
+    ("Incoming", "Entradas"),
+    ("Outgoing", "Saídas"),
+    ("Cash on hand", "Saldo atual"),
+    ("Spent per day", "Gasto por dia"),
+    ("Received per day", "Recebido por dia"),
+    ("Saved", "Guardado"),
+    ("Saved {}% of the income", "Guardou {}% da renda"),
+    ("{}% of the income", "{}% da renda"),
+    ("Spending on par with {} at this point", "Gastando o mesmo que em {} até este ponto"),
+    ("Spending {}% above {} at this point", "Gastando {}% acima de {} até este ponto"),
+    ("Spending {}% below {} at this point", "Gastando {}% abaixo de {} até este ponto"),
+    ("Nothing had been spent by this point in {}", "Nada havia sido gasto até este ponto em {}"),
+    ("Spent {} more than received in {}", "Gastou {} a mais do que recebeu em {}"),
+    ("Spent {} more than by this point in {}", "Gastou {} a mais do que até este ponto em {}"),
+    ("Operations", "Lançamentos"),
+    ("Operations by {}", "Lançamentos por {}"),
+    ("By person", "Por pessoa"),
+    ("put in {}, spent {}", "colocou {}, gastou {}"),
+    ("(shared)", "(em comum)"),
+    ("Person", "Pessoa"),
+    ("Put in", "Colocou"),
+    ("Spent", "Gastou"),
+    ("Amount", "Valor"),
+    ("Count", "Quantidade"),
+    ("Day", "Dia"),
+    ("Balance", "Saldo"),
+    ("Description", "Descrição"),
+    ("description", "descrição"),
+    ("Category", "Categoria"),
+    ("category", "categoria"),
+
+    // Statistics
+    ("Statistics for {} month(s)", "Estatísticas de {} mês(es)"),
+    ("Average monthly savings", "Economia média por mês"),
+    ("Best month", "Melhor mês"),
+    ("Worst month", "Pior mês"),
+    ("Longest under-budget streak: {} month(s)", "Maior sequência dentro do orçamento: {} mês(es)"),
+    ("Entries: {} take(s), {} put(s), {} refund(s)", "Lançamentos: {} gasto(s), {} entrada(s), {} reembolso(s)"),
+    ("Amount of takes", "Valor dos gastos"),
+    ("Smallest", "Menor"),
+    ("Largest", "Maior"),
+    ("Mean", "Média"),
+    ("Median", "Mediana"),
+    ("Standard deviation", "Desvio padrão"),
+    ("Spending by category", "Gastos por categoria"),
+    (" of {} budgeted", " de {} orçados"),
+    ("Largest {} of {} take(s), {} spent in total", "Maiores {} de {} gasto(s), {} gastos no total"),
+    ("Outgoing by month", "Saídas por mês"),
+    (", grew the most", ", a que mais cresceu"),
+    ("Spending on {} over the last {} month(s)", "Gastos com {} nos últimos {} mês(es)"),
+    ("Spending over the last {} month(s)", "Gastos nos últimos {} mês(es)"),
+    ("Rising by {} a month", "Subindo {} por mês"),
+    ("Falling by {} a month", "Caindo {} por mês"),
+    ("Holding steady", "Estável"),
+    ("Not enough months to tell a trend", "Não há meses suficientes para dizer a tendência"),
+
+    // Bills and goals
+    ("No bills yet, add one with `porquinho bill add`", "Nenhuma conta ainda, adicione uma com `porquinho bill add`"),
+    ("Bills", "Contas"),
+    ("paid", "paga"),
+    ("upcoming", "a vencer"),
+    ("OVERDUE", "ATRASADA"),
+    ("No goals yet, add one with `porquinho goal add`", "Nenhuma meta ainda, adicione uma com `porquinho goal add`"),
+    ("Goals", "Metas"),
+    ("reached", "alcançada"),
+    ("{} a month to get there", "{} por mês para chegar lá"),
+    ("overdue", "atrasada"),
+    ("{} of {} ({}%) by {}, {}", "{} de {} ({}%) até {}, {}"),
+    ("Created {}", "{} criado"),
+
+    // Forecast
+    ("Balance so far", "Saldo até agora"),
+    ("Spending {} a day for {} more day(s)", "Gastando {} por dia por mais {} dia(s)"),
+    ("Forecast for {}", "Previsão de {}"),
+    ("Still expected, as in {}:", "Ainda esperado, como em {}:"),
+    ("Projected balance by {}: {}", "Saldo previsto para {}: {}"),
+    ("the end of {}", "o fim de {}"),
+    ("day {} of {}", "o dia {} de {}"),
+    ("At this pace, {} ends in the red", "Neste ritmo, {} termina no vermelho"),
+    ("the cash on hand is {}", "o saldo atual é {}"),
+    ("the cash on hand is on track to be {} by {}", "o saldo atual deve chegar a {} até {}"),
+    ("{}, below the low balance of {}", "{}, abaixo do saldo mínimo de {}"),
+
+    // Months
+    ("{} started with {}", "{} começou com {}"),
+    ("day {} doesn't exist in {}", "o dia {} não existe em {}"),
+    ("Report for {}", "Relatório de {}"),
+    ("Spending per day in {}", "Gastos por dia em {}"),
+    ("Comparing {} to {}", "Comparando {} com {}"),
+
+    // Help
+    ("Simplistic personal finances helper", "Ajudante simples de finanças pessoais"),
+    ("Run against a throwaway copy of the data folder, leaving the real one untouched", "Usa uma cópia descartável da pasta de dados, sem mexer na verdadeira"),
+    ("Show what a command would change in the book, without changing anything", "Mostra o que um comando mudaria no livro, sem mudar nada"),
+    ("Act as if today were this date, to backfill past months: YYYY-MM-DD, yesterday, or a weekday such as monday", "Age como se hoje fosse esta data, para preencher meses passados: AAAA-MM-DD, yesterday, ou um dia da semana como monday"),
+    ("Don't ask before recording amounts above `confirm_above`", "Não pergunta antes de registrar valores acima de `confirm_above`"),
+    ("Color the output: always, never, or only when printing to a terminal and `NO_COLOR` isn't set", "Colore a saída: always, never, ou só num terminal e sem `NO_COLOR` definida"),
+    ("Print nothing besides what was asked for, leaving out messages like \"Updated ...\"", "Não mostra nada além do que foi pedido, omitindo mensagens como \"... atualizado\""),
+    ("Also log which files are read and written, and how their lines are parsed", "Também registra quais arquivos são lidos e escritos, e como suas linhas são lidas"),
+    ("Silence a warning, by its code (e.g. P101)", "Silencia um aviso, pelo seu código (ex.: P101)"),
+    ("Turn a warning into an error, by its code, or every warning with `warnings`", "Transforma um aviso em erro, pelo seu código, ou todos com `warnings`"),
+    ("Compress every bookkeeping file of a past year into a single archive", "Compacta todos os arquivos de um ano passado num único arquivo"),
+    ("Record `take`, `put` and `refund` commands read from stdin, one per line, all at once", "Registra de uma vez comandos `take`, `put` e `refund` lidos da entrada, um por linha"),
+    ("Add or remove bills due every month, see `bills` for where they stand", "Adiciona ou remove contas mensais, veja `bills` para saber como estão"),
+    ("List the bills of the current month, as paid, upcoming or overdue", "Lista as contas do mês atual, como pagas, a vencer ou atrasadas"),
+    ("Show the open statement of every credit card in config.toml", "Mostra a fatura aberta de cada cartão de crédito do config.toml"),
+    ("Tag operations recorded before their rule existed, with the category of the rule", "Marca lançamentos registrados antes da sua regra existir com a categoria da regra"),
+    ("Draw a bar chart of how much was spent on each day of a month", "Desenha um gráfico de barras do quanto foi gasto em cada dia de um mês"),
+    ("Track the steps of closing a month, configured in config.toml", "Acompanha os passos do fechamento do mês, configurados no config.toml"),
+    ("Carry the balance a month ended with into the next one, as its opening balance", "Leva o saldo com que um mês terminou para o próximo, como seu saldo inicial"),
+    ("Compare two months, by their totals and by how much each category took", "Compara dois meses, pelos totais e pelo quanto cada categoria levou"),
+    ("Find identical entries in a month, choosing which ones to merge", "Encontra lançamentos idênticos num mês, escolhendo quais juntar"),
+    ("Check every bookkeeping file for problems, such as malformed entries or February 30", "Procura problemas nos arquivos do livro, como lançamentos malformados ou 30 de fevereiro"),
+    ("Change an entry of this month, by its number in `status --complete`", "Muda um lançamento deste mês, pelo seu número em `status --complete`"),
+    ("Encrypt every month written before `encryption` was set in config.toml", "Criptografa os meses escritos antes de `encryption` ser definida no config.toml"),
+    ("Print copy-pasteable workflows", "Mostra roteiros prontos para copiar e colar"),
+    ("Keep separate books, such as personal and business ones, each in its own folders", "Mantém livros separados, como o pessoal e o da empresa, cada um nas suas pastas"),
+    ("List every profile, marking the one in use, with the balance of each in its own currency", "Lista todos os perfis, marcando o que está em uso, com o saldo de cada um na sua própria moeda"),
+    ("Start a profile, with its own data and config folders", "Começa um perfil, com suas próprias pastas de dados e configuração"),
+    ("Use the separate book of this profile, created with `porquinho profile create`", "Usa o livro separado deste perfil, criado com `porquinho profile create`"),
+    ("Who it's from, in a shared book, added to the description as `@person`", "De quem é, num livro compartilhado, adicionado à descrição como `@pessoa`"),
+    ("Also show how much each person put in and spent, by the `@person` of each entry", "Também mostra quanto cada pessoa colocou e gastou, pela `@pessoa` de cada lançamento"),
+    ("Record an expense paid for a group, taking only my share and keeping track of what the others owe", "Registra uma despesa paga por um grupo, lançando só a minha parte e guardando o que os outros devem"),
+    ("Show what everyone owes from split expenses, or record someone paying you back", "Mostra o que cada um deve das despesas divididas, ou registra alguém pagando de volta"),
+    ("Explain a warning or error code, or list them all", "Explica um código de aviso ou erro, ou lista todos"),
+    ("Export every operation of the book", "Exporta todos os lançamentos do livro"),
+    ("Project the balance this month will end with, from the spending so far", "Projeta o saldo com que este mês vai terminar, pelos gastos até agora"),
+    ("Show savings goals, their progress and how much they need each month to stay on track", "Mostra as metas de economia, seu progresso e quanto precisam por mês para serem cumpridas"),
+    ("Show a month as a calendar, each day shaded by how much was spent on it", "Mostra um mês como calendário, cada dia sombreado pelo quanto foi gasto nele"),
+    ("Import operations previously exported with `export`", "Importa lançamentos exportados antes com `export`"),
+    ("Import the history of another budgeting app, its categories becoming #tags", "Importa o histórico de outro app de finanças, com suas categorias virando #tags"),
+    ("Set up starter categories and budgets in config.toml", "Configura categorias e orçamentos iniciais no config.toml"),
+    ("Record several operations at once, with undo and redo, writing them all on exit", "Registra vários lançamentos de uma vez, com desfazer e refazer, gravando todos ao sair"),
+    ("How much a small habit costs, and what it would be worth if saved instead", "Quanto custa um pequeno hábito, e quanto valeria se fosse guardado"),
+    ("Show the history of the book, when it's tracked with git", "Mostra o histórico do livro, quando versionado com git"),
+    ("Edit a month's file in $VISUAL or $EDITOR, checking every line afterwards", "Edita o arquivo de um mês no $VISUAL ou $EDITOR, conferindo cada linha depois"),
+    ("Set how much cash a month started with, for the first month `close` carries from", "Define com quanto dinheiro um mês começou, para o primeiro mês de onde `close` parte"),
+    ("Flag takes far above the typical amount of their category, or of their description", "Aponta gastos muito acima do comum para sua categoria, ou sua descrição"),
+    ("Record lines of a bookkeeping file read from stdin, as in `05 - 12.50 Coffee`, all at once", "Registra de uma vez linhas de arquivo do livro lidas da entrada, como `05 - 12.50 Café`"),
+    ("Record a new credit to your account", "Registra uma entrada na sua conta"),
+    ("Show a month as a QR code, to grab it from a phone or another machine", "Mostra um mês como QR code, para levá-lo a um celular ou outra máquina"),
+    ("Summarize a quarter of the fiscal year, for tax periods", "Resume um trimestre do ano fiscal, para períodos de impostos"),
+    ("Record money given back for something taken, which lowers the spending instead of counting as income", "Registra dinheiro devolvido por um gasto, o que diminui os gastos em vez de contar como renda"),
+    ("Change the description of every operation described as FROM, across the whole book", "Muda a descrição de todos os lançamentos descritos como FROM, no livro todo"),
+    ("Write a report of a month, with every entry and the spending of each category", "Escreve um relatório de um mês, com cada lançamento e os gastos de cada categoria"),
+    ("Undo the changes of a commit from `log`, when the book is tracked with git", "Desfaz as mudanças de um commit do `log`, quando o livro é versionado com git"),
+    ("Check that exporting and re-importing the book gives back the same book", "Confere se exportar e reimportar o livro devolve o mesmo livro"),
+    ("Serve a JSON API over the book on localhost, for building other interfaces on top", "Serve uma API JSON do livro no localhost, para construir outras interfaces"),
+    ("Statistics for the current month, or for the whole book", "Estatísticas do mês atual, ou do livro todo"),
+    ("Current status for your", "Situação atual das suas finanças"),
+    ("Record a debit transaction from your account", "Registra um gasto da sua conta"),
+    ("Print just the balance of this month, as a bare number, for status bars", "Imprime só o saldo deste mês, como um número puro, para barras de status"),
+    ("The balance of every month together, instead of this month's", "O saldo de todos os meses juntos, em vez do deste mês"),
+    ("Answer a question about every operation recorded, archived ones included", "Responde uma pergunta sobre todas as operações registradas, incluindo as arquivadas"),
+    ("Run an SQL statement over every operation recorded, archived ones included, loaded into an in-memory table called `ops`", "Executa um comando SQL sobre todas as operações registradas, incluindo as arquivadas, carregadas numa tabela em memória chamada `ops`"),
+    ("Check this month's finances, printing nothing and exiting with 1 if they're off, for shell prompts and cron jobs", "Verifica as finanças deste mês, sem imprimir nada e saindo com 1 se estiverem mal, para prompts do shell e tarefas do cron"),
+    ("Fail if the cash on hand isn't above this, counting the opening balance", "Falha se o dinheiro em caixa não estiver acima disto, contando o saldo inicial"),
+    ("Fail if a category went over its budget, or the month over the spending target", "Falha se uma categoria passou do orçamento, ou o mês passou da meta de gastos"),
+    ("Sum a year's deductible expenses and taxable income by category, listing their entries, as asked for when filing income tax", "Soma as despesas dedutíveis e a renda tributável de um ano por categoria, listando seus lançamentos, como pedido na declaração do imposto de renda"),
+    ("The largest takes, and their share of the spending", "Os maiores gastos, e sua parte do total gasto"),
+    ("Tell whether the spending is rising or falling, and by how much a month", "Diz se os gastos estão subindo ou caindo, e quanto por mês"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{fill, is_yes, Language};
+    use crate::Error;
+
+    #[test]
+    fn translates_messages() {
+        let portuguese = Language::Portuguese;
+        assert_eq!(portuguese.text("Incoming"), "Entradas");
+        assert_eq!(Language::English.text("Incoming"), "Incoming");
+        // Untranslated messages are kept in English
+        assert_eq!(portuguese.text("Something new"), "Something new");
+
+        assert_eq!(
+            fill(portuguese.text("Saved {}% of the income"), &[&12]),
+            "Guardou 12% da renda"
+        );
+        assert_eq!(
+            portuguese.error(&Error::InvalidAmount("abc".into())),
+            "'abc' não é um valor válido"
+        );
+        assert!(is_yes("s\n") && is_yes("Y") && !is_yes("n"));
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+
+use crate::{bills::Bill, config::Config, file::BookkeepingFile, parser::Operation, Result};
+
+/// Something that happens on the same day every month, as an event of the calendar
+pub struct Event {
+    /// Unique among the events of the calendar, so that importing it again updates them
+    pub uid: String,
+    pub summary: String,
+    pub day: u8,
+}
+
+impl Event {
+    pub fn from_bill(bill: &Bill, config: &Config) -> Self {
+        Self {
+            uid: format!("bill-{}", config.matching.normalize(&bill.name)),
+            summary: format!("{}: {}", bill.name, config.format_amount(&bill.amount)),
+            day: bill.due,
+        }
+    }
+
+    pub fn from_operation(operation: &Operation, config: &Config) -> Self {
+        let sign = operation.typ.sign();
+        let currency = operation.currency.as_deref().unwrap_or(&config.currency);
+
+        Self {
+            uid: format!(
+                "recurring-{}",
+                config.matching.normalize(&operation.description)
+            ),
+            summary: format!(
+                "{}: {}{}",
+                operation.description,
+                sign,
+                config.format_amount_in(&operation.amount, currency)
+            ),
+            day: operation.day,
+        }
+    }
+
+    /// The recurrence rule, falling back to the last day of the months that are too short
+    fn rule(&self) -> String {
+        if self.day > 28 {
+            let days: Vec<_> = (28..=self.day).map(|day| day.to_string()).collect();
+            format!("FREQ=MONTHLY;BYMONTHDAY={};BYSETPOS=-1", days.join(","))
+        } else {
+            format!("FREQ=MONTHLY;BYMONTHDAY={}", self.day)
+        }
+    }
+}
+
+/// Writes `events` as an iCalendar file, each repeating monthly from `from` on with a
+/// reminder on the day before
+pub fn write_calendar(
+    events: &[Event],
+    from: &BookkeepingFile,
+    stamp: DateTime<Utc>,
+    mut output: impl Write,
+) -> Result<()> {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//porquinho//porquinho//EN".to_owned(),
+    ];
+
+    for event in events {
+        let day = event.day.clamp(1, from.days());
+        lines.extend([
+            "BEGIN:VEVENT".to_owned(),
+            format!("UID:{}@porquinho", escape(&event.uid).replace(' ', "-")),
+            format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")),
+            format!(
+                "DTSTART;VALUE=DATE:{:04}{:02}{:02}",
+                from.year(),
+                from.month(),
+                day
+            ),
+            format!("RRULE:{}", event.rule()),
+            format!("SUMMARY:{}", escape(&event.summary)),
+            "BEGIN:VALARM".to_owned(),
+            "ACTION:DISPLAY".to_owned(),
+            format!("DESCRIPTION:{}", escape(&event.summary)),
+            "TRIGGER:-P1D".to_owned(),
+            "END:VALARM".to_owned(),
+            "END:VEVENT".to_owned(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_owned());
+
+    for line in lines {
+        // The format asks for CRLF line endings
+        write!(output, "{}\r\n", fold(&line))?;
+    }
+
+    Ok(())
+}
+
+/// Breaks `line` so that no line is longer than 75 octets, continuing each on the next after a
+/// space, without splitting a character in two
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    // The space that starts a continued line counts too
+    let mut room = 75;
+
+    for character in line.chars() {
+        if character.len_utf8() > room {
+            folded.push_str("\r\n ");
+            room = 74;
+        }
+        folded.push(character);
+        room -= character.len_utf8();
+    }
+
+    folded
+}
+
+/// Escapes the characters that are special in iCalendar text values
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::{TimeZone, Utc};
+
+    use super::{fold, write_calendar, Event};
+    use crate::{bills::Bill, config::Config, file::BookkeepingFile};
+
+    #[test]
+    fn writes_monthly_events() {
+        let config = Config::default();
+        let bill = Bill {
+            name: "Rent; Condo".to_owned(),
+            amount: BigDecimal::from(1500),
+            due: 31,
+        };
+        let events = [Event::from_bill(&bill, &config)];
+
+        let mut buf = Vec::new();
+        let stamp = Utc.ymd(2024, 2, 1).and_hms(12, 0, 0);
+        write_calendar(&events, &BookkeepingFile::new(2, 2024), stamp, &mut buf).unwrap();
+        let calendar = String::from_utf8(buf).unwrap();
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.contains("DTSTAMP:20240201T120000Z\r\n"));
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20240229\r\n"));
+        assert!(calendar.contains("RRULE:FREQ=MONTHLY;BYMONTHDAY=28,29,30,31;BYSETPOS=-1\r\n"));
+        assert!(calendar.contains("SUMMARY:Rent\\; Condo: R$ 1\\,500.00\r\n"));
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        assert_eq!(fold("SUMMARY:Rent"), "SUMMARY:Rent");
+
+        let line = format!("SUMMARY:{}", "ç".repeat(80));
+        let folded = fold(&line);
+        assert_eq!(folded.replace("\r\n ", ""), line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75, "{:?} is {} octets", part, part.len());
+        }
+        // The 'ç's are two octets each, and aren't split
+        assert_eq!(folded.split("\r\n").count(), 3);
+        assert!(folded
+            .split("\r\n")
+            .skip(1)
+            .all(|part| part.starts_with(" ç")));
+    }
+}
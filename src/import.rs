@@ -0,0 +1,41 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    config::Config, export::Record, file::BookkeepingFile, hooks, parser::Entry, writer::Writer,
+    Error, Result,
+};
+
+/// Appends every record to the bookkeeping file of its month, inside of `dir`
+///
+/// Returns how many months were touched.
+pub fn import_records(dir: &Path, records: &[Record], config: &Config) -> Result<usize> {
+    let mut months: BTreeMap<&str, Vec<Entry>> = BTreeMap::new();
+
+    for record in records {
+        if BookkeepingFile::from_file_name(&record.month).is_none() {
+            return Err(Error::InvalidMonth(record.month.clone()));
+        }
+
+        let entry = Entry {
+            currency: record.currency.as_deref(),
+            ..Entry::new(
+                record.day,
+                record.kind,
+                record.amount.clone(),
+                &record.description,
+            )
+        };
+        months.entry(&record.month).or_default().push(entry);
+    }
+
+    for (month, entries) in &months {
+        Writer::append_entries(&dir.join(month), entries, config)?;
+        let records: Vec<_> = entries
+            .iter()
+            .map(|entry| Record::from_entry(month, entry))
+            .collect();
+        hooks::recorded(&records, config);
+    }
+
+    Ok(months.len())
+}
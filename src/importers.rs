@@ -0,0 +1,543 @@
+use std::{collections::BTreeMap, io::Read};
+
+use bigdecimal::{BigDecimal, Signed, Zero};
+use chrono::{Datelike, NaiveDate};
+use clap::ArgEnum;
+use serde::Deserialize;
+
+use crate::{
+    config::Config, export::Record, file::BookkeepingFile, locale::Locale, parser::EntryType,
+    rules::Normalization, Error, Result,
+};
+
+/// Budgeting apps whose exports can be imported
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum App {
+    /// YNAB's register export, in CSV
+    Ynab,
+    /// Organizze's transactions export, in CSV
+    Organizze,
+}
+
+/// A transaction read from another app's export
+#[derive(Debug, PartialEq)]
+pub struct Transaction {
+    pub date: NaiveDate,
+    pub description: String,
+    /// The app's category, with its parents joined by `: `
+    pub category: Option<String>,
+    /// Negative for money spent
+    pub amount: BigDecimal,
+}
+
+impl App {
+    pub fn read(self, input: impl Read, config: &Config) -> Result<Vec<Transaction>> {
+        match self {
+            Self::Ynab => read_ynab(input, config.locale),
+            Self::Organizze => read_organizze(input),
+        }
+    }
+}
+
+/// Which sign a bank gives to money spent
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpendingSign {
+    /// `-12.50` was spent, as in bank account statements
+    #[default]
+    Negative,
+    /// `12.50` was spent, as in credit card statements
+    Positive,
+}
+
+/// How a bank lays out its CSV statements, the `[profiles.NAME]` tables of config.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// What each column holds, in order: `date`, `description`, `amount` or `category`,
+    /// anything else being ignored
+    pub columns: Vec<String>,
+    /// Format of the dates, e.g. `%d/%m/%Y`
+    pub date_format: String,
+    /// `.` or `,`, defaults to the configured locale's
+    pub decimal_separator: Option<char>,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// Lines to skip before the first transaction, such as headers
+    #[serde(default = "default_skip_lines")]
+    pub skip_lines: usize,
+    /// Skips every line up to the first one starting with this, the header, ignoring `skip_lines`
+    pub starts_after: Option<String>,
+    #[serde(default)]
+    pub spending_sign: SpendingSign,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_skip_lines() -> usize {
+    1
+}
+
+/// Names of the profiles porquinho knows without any config
+pub const PRESETS: &[&str] = &["nubank", "nubank-conta", "inter", "itau"];
+
+impl Profile {
+    /// Built-in profiles for the statements of popular Brazilian banks
+    pub fn preset(name: &str) -> Option<Self> {
+        let profile = |columns: &[&str], date_format: &str, decimal_separator, delimiter| Self {
+            columns: columns.iter().map(|&column| column.to_owned()).collect(),
+            date_format: date_format.to_owned(),
+            decimal_separator: Some(decimal_separator),
+            delimiter,
+            skip_lines: 1,
+            starts_after: None,
+            spending_sign: SpendingSign::Negative,
+        };
+
+        let preset = match name {
+            // Credit card bills: `date,title,amount`
+            "nubank" => Self {
+                spending_sign: SpendingSign::Positive,
+                ..profile(&["date", "description", "amount"], "%Y-%m-%d", '.', ',')
+            },
+            // Account statements: `Data,Valor,Identificador,Descrição`
+            "nubank-conta" => profile(
+                &["date", "amount", "id", "description"],
+                "%d/%m/%Y",
+                '.',
+                ',',
+            ),
+            // Starts with the account's details, before `Data Lançamento;Descrição;Valor;Saldo`
+            "inter" => Self {
+                starts_after: Some("Data Lançamento".to_owned()),
+                ..profile(
+                    &["date", "description", "amount", "balance"],
+                    "%d/%m/%Y",
+                    ',',
+                    ';',
+                )
+            },
+            // No header: `date;description;amount`
+            "itau" => Self {
+                skip_lines: 0,
+                ..profile(&["date", "description", "amount"], "%d/%m/%Y", ',', ';')
+            },
+            _ => return None,
+        };
+
+        Some(preset)
+    }
+
+    pub fn read(&self, input: impl Read, config: &Config) -> Result<Vec<Transaction>> {
+        let locale = match self.decimal_separator {
+            None => config.locale,
+            Some('.') => Locale::English,
+            Some(',') => Locale::BrazilianPortuguese,
+            Some(other) => {
+                return Err(Error::Interchange(format!(
+                    "{other:?} can't be a decimal separator, use '.' or ','"
+                )))
+            }
+        };
+        let column = |name: &str| self.columns.iter().position(|column| column == name);
+        let required = |name: &str| {
+            column(name)
+                .ok_or_else(|| Error::Interchange(format!("the profile has no {name:?} column")))
+        };
+        let (date, description, amount) = (
+            required("date")?,
+            required("description")?,
+            required("amount")?,
+        );
+        let category = column("category");
+
+        let mut delimiter = [0; 4];
+        let delimiter = self.delimiter.encode_utf8(&mut delimiter).as_bytes();
+        if delimiter.len() != 1 {
+            return Err(Error::Interchange(
+                "the delimiter must be an ASCII character".to_owned(),
+            ));
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter[0])
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(input);
+
+        let mut rows = reader.records();
+        match &self.starts_after {
+            Some(header) => {
+                // `any` stops right after the header, leaving only transactions in `rows`
+                let found = rows.any(|row| {
+                    row.ok().is_some_and(|row| {
+                        let first = row.get(0).unwrap_or_default();
+                        first
+                            .trim_start_matches('\u{feff}')
+                            .trim()
+                            .starts_with(header.as_str())
+                    })
+                });
+                if !found {
+                    return Err(Error::Interchange(format!(
+                        "no line starts with {header:?}"
+                    )));
+                }
+            }
+            None => for _ in rows.by_ref().take(self.skip_lines) {},
+        }
+
+        let mut transactions = Vec::new();
+        for row in rows {
+            let row = row?;
+            let cell = |position: usize| row.get(position).unwrap_or_default();
+
+            let mut amount = parse_money(cell(amount), locale)?;
+            if self.spending_sign == SpendingSign::Positive {
+                amount = -amount;
+            }
+            if amount.is_zero() {
+                continue;
+            }
+
+            transactions.push(Transaction {
+                date: parse_date(cell(date), &[&self.date_format])?,
+                description: cell(description).trim().to_owned(),
+                category: category.and_then(|position| non_empty(cell(position))),
+                amount,
+            });
+        }
+
+        Ok(transactions)
+    }
+}
+
+/// Maps the categories of another app into porquinho's
+pub struct CategoryMap {
+    overrides: BTreeMap<String, String>,
+}
+
+impl CategoryMap {
+    /// `mappings` are `FROM=TO` pairs, with FROM being the app's full category name
+    pub fn new(mappings: &[String]) -> Result<Self> {
+        let overrides = mappings
+            .iter()
+            .map(|mapping| {
+                let (from, to) = mapping.split_once('=').ok_or_else(|| {
+                    Error::Interchange(format!("expected FROM=TO, got {mapping:?}"))
+                })?;
+                Ok((from.trim().to_owned(), tag_name(to)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { overrides })
+    }
+
+    /// Explicit mappings win, otherwise the deepest category is used, as in `Food: Restaurants`
+    /// becoming `restaurants`
+    pub fn map(&self, category: &str) -> String {
+        match self.overrides.get(category) {
+            Some(mapped) => mapped.clone(),
+            // Safety: `rsplit` always yields at least one item
+            None => tag_name(category.rsplit(':').next().unwrap()),
+        }
+    }
+
+    /// Every category found in `transactions`, what it maps to and how many transactions it has
+    pub fn review<'t>(
+        &self,
+        transactions: &'t [Transaction],
+    ) -> BTreeMap<&'t str, (String, usize)> {
+        let mut review = BTreeMap::new();
+
+        for category in transactions.iter().filter_map(|tx| tx.category.as_deref()) {
+            review
+                .entry(category)
+                .or_insert_with(|| (self.map(category), 0))
+                .1 += 1;
+        }
+
+        review
+    }
+
+    /// Shows how `transactions` would be imported, before anything is written
+    pub fn print_review(&self, transactions: &[Transaction]) {
+        println!("Found {} transaction(s)", transactions.len());
+
+        let dates = transactions.iter().map(|tx| tx.date);
+        if let (Some(first), Some(last)) = (dates.clone().min(), dates.max()) {
+            println!("\tFrom {} to {}", first, last);
+        }
+
+        println!("Categories");
+        for (category, (mapped, count)) in self.review(transactions) {
+            println!("\t{} -> #{} ({} transaction(s))", category, mapped, count);
+        }
+    }
+
+    /// Turns `transactions` into records, tagging each description with its category
+    pub fn to_records(&self, transactions: &[Transaction]) -> Vec<Record> {
+        transactions
+            .iter()
+            .map(|tx| {
+                let month = BookkeepingFile::new(tx.date.month(), tx.date.year());
+                let kind = if tx.amount.is_negative() {
+                    EntryType::Debit
+                } else {
+                    EntryType::Credit
+                };
+                let description = match &tx.category {
+                    Some(category) => format!("{} #{}", tx.description, self.map(category)),
+                    None => tx.description.clone(),
+                };
+
+                Record {
+                    month: month.as_str().to_owned(),
+                    day: tx.date.day() as u8,
+                    kind,
+                    amount: tx.amount.abs(),
+                    currency: None,
+                    description,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A category name fit for a `#tag`: lowercase, without accents nor spaces
+fn tag_name(category: &str) -> String {
+    Normalization::default()
+        .normalize(category)
+        .replace(' ', "-")
+}
+
+/// YNAB exports spending and income in separate `Outflow` and `Inflow` columns
+fn read_ynab(input: impl Read, locale: Locale) -> Result<Vec<Transaction>> {
+    let mut reader = csv::Reader::from_reader(input);
+    let columns = Columns::find(
+        reader.headers()?,
+        &[
+            "date",
+            "payee",
+            "category group/category",
+            "memo",
+            "outflow",
+            "inflow",
+        ],
+    )?;
+    // YNAB writes dates following the user's region
+    let date_format = match locale {
+        Locale::English => "%m/%d/%Y",
+        Locale::BrazilianPortuguese => "%d/%m/%Y",
+    };
+
+    let mut transactions = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        let [date, payee, category, memo, outflow, inflow] = columns.get(&row);
+
+        let amount = parse_money(inflow, locale)? - parse_money(outflow, locale)?;
+        if amount.is_zero() {
+            continue;
+        }
+
+        let description = match (payee.trim(), memo.trim()) {
+            (payee, "") => payee.to_owned(),
+            ("", memo) => memo.to_owned(),
+            (payee, memo) => format!("{payee}: {memo}"),
+        };
+
+        transactions.push(Transaction {
+            date: parse_date(date, &["%Y-%m-%d", date_format])?,
+            description,
+            category: non_empty(category),
+            amount,
+        });
+    }
+
+    Ok(transactions)
+}
+
+/// Organizze is Brazilian, so its exports always use Brazilian conventions
+fn read_organizze(mut input: impl Read) -> Result<Vec<Transaction>> {
+    let mut contents = String::new();
+    input.read_to_string(&mut contents)?;
+
+    // Spreadsheets saved in Brazil separate columns with semicolons
+    let first_line = contents.lines().next().unwrap_or_default();
+    let delimiter = if first_line.contains(';') { b';' } else { b',' };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(contents.as_bytes());
+    let columns = Columns::find(
+        reader.headers()?,
+        &["data", "descricao", "categoria", "valor"],
+    )?;
+
+    let mut transactions = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        let [date, description, category, amount] = columns.get(&row);
+
+        transactions.push(Transaction {
+            date: parse_date(date, &["%d/%m/%Y"])?,
+            description: description.trim().to_owned(),
+            category: non_empty(category),
+            amount: parse_money(amount, Locale::BrazilianPortuguese)?,
+        });
+    }
+
+    Ok(transactions)
+}
+
+/// Positions of the columns we need, as apps may add, remove or reorder the others
+struct Columns<const N: usize>([usize; N]);
+
+impl<const N: usize> Columns<N> {
+    fn find(headers: &csv::StringRecord, names: &[&str; N]) -> Result<Self> {
+        // Headers may come in any case, with accents, or with a byte order mark
+        let normalize = |header: &str| {
+            Normalization::default().normalize(header.trim_start_matches('\u{feff}'))
+        };
+
+        let mut positions = [0; N];
+        for (position, name) in positions.iter_mut().zip(names) {
+            *position = headers
+                .iter()
+                .position(|header| normalize(header) == *name)
+                .ok_or_else(|| Error::Interchange(format!("missing column {name:?}")))?;
+        }
+
+        Ok(Self(positions))
+    }
+
+    fn get<'r>(&self, row: &'r csv::StringRecord) -> [&'r str; N] {
+        self.0.map(|position| row.get(position).unwrap_or_default())
+    }
+}
+
+fn parse_date(date: &str, formats: &[&str]) -> Result<NaiveDate> {
+    formats
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date.trim(), format).ok())
+        .ok_or_else(|| Error::Interchange(format!("{date:?} is not a date")))
+}
+
+/// Parses amounts such as `R$ 1.234,56`, `-$12.00` or an empty cell, which means zero
+fn parse_money(money: &str, locale: Locale) -> Result<BigDecimal> {
+    let digits: String = money
+        .chars()
+        .filter(|&ch| ch.is_ascii_digit() || ch == '-' || ch == locale.decimal_separator())
+        .collect();
+
+    if digits.is_empty() {
+        return Ok(BigDecimal::zero());
+    }
+
+    locale
+        .parse_decimal(&digits)
+        .ok_or_else(|| Error::Interchange(format!("{money:?} is not an amount")))
+}
+
+fn non_empty(cell: &str) -> Option<String> {
+    let cell = cell.trim();
+    (!cell.is_empty()).then(|| cell.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveDate;
+
+    use super::{read_organizze, read_ynab, CategoryMap, Profile};
+    use crate::{config::Config, locale::Locale, parser::EntryType};
+
+    #[test]
+    fn reads_ynab_and_organizze_exports() {
+        let ynab = "\u{feff}\"Account\",\"Flag\",\"Date\",\"Payee\",\"Category Group/Category\",\"Category Group\",\"Category\",\"Memo\",\"Outflow\",\"Inflow\",\"Cleared\"\n\
+            \"Checking\",\"\",\"10/03/2023\",\"Padaria\",\"Food: Bakery\",\"Food\",\"Bakery\",\"\",\"$1,012.50\",\"$0.00\",\"Cleared\"\n\
+            \"Checking\",\"\",\"10/05/2023\",\"ACME\",\"\",\"\",\"\",\"Salary\",\"$0.00\",\"$3,000.00\",\"Cleared\"\n";
+        let ynab = read_ynab(ynab.as_bytes(), Locale::English).unwrap();
+
+        assert_eq!(ynab[0].date, NaiveDate::from_ymd(2023, 10, 3));
+        assert_eq!(ynab[0].amount, BigDecimal::from_str("-1012.50").unwrap());
+        assert_eq!(ynab[1].description, "ACME: Salary");
+        assert_eq!(ynab[1].category, None);
+
+        let organizze = "Data;Descrição;Categoria;Valor;Situação\n\
+            03/10/2023;Açaí;Alimentação: Lanches;-1.012,50;Pago\n";
+        let organizze = read_organizze(organizze.as_bytes()).unwrap();
+        assert_eq!(organizze[0].amount, ynab[0].amount);
+        assert_eq!(organizze[0].date, ynab[0].date);
+
+        let map = CategoryMap::new(&["Food: Bakery=food".into()]).unwrap();
+        let records = map.to_records(&ynab);
+        assert_eq!(records[0].description, "Padaria #food");
+        assert_eq!(records[0].kind, EntryType::Debit);
+        assert_eq!(records[0].month, "10-2023");
+
+        let records = map.to_records(&organizze);
+        assert_eq!(records[0].description, "Açaí #lanches");
+    }
+
+    #[test]
+    fn reads_statements_following_a_profile() {
+        let profile: Profile = toml::from_str(
+            r#"
+            columns = ["date", "category", "description", "amount"]
+            date_format = "%Y-%m-%d"
+            decimal_separator = "."
+            spending_sign = "positive"
+            "#,
+        )
+        .unwrap();
+        let statement = "date,category,title,amount\n\
+            2023-10-03,restaurante,Padaria,12.50\n\
+            2023-10-05,,Pagamento recebido,-300\n";
+
+        let transactions = profile
+            .read(statement.as_bytes(), &Config::default())
+            .unwrap();
+        assert_eq!(transactions[0].date, NaiveDate::from_ymd(2023, 10, 3));
+        assert_eq!(
+            transactions[0].amount,
+            BigDecimal::from_str("-12.50").unwrap()
+        );
+        assert_eq!(transactions[0].category.as_deref(), Some("restaurante"));
+        assert_eq!(transactions[1].amount, BigDecimal::from(300));
+        assert_eq!(transactions[1].category, None);
+    }
+
+    #[test]
+    fn reads_statements_of_brazilian_banks() {
+        let read = |preset: &str, statement: &str| {
+            Profile::preset(preset)
+                .unwrap()
+                .read(statement.as_bytes(), &Config::default())
+                .unwrap()
+        };
+
+        let nubank = read("nubank", "date,title,amount\n2023-10-03,Padaria,12.50\n");
+        let inter = read(
+            "inter",
+            "Extrato Conta Corrente \nConta ;123456\n\nData Lançamento;Descrição;Valor;Saldo\n\
+             03/10/2023;Padaria;-12,50;987,50\n",
+        );
+        let itau = read("itau", "03/10/2023;PADARIA;-12,50\n");
+
+        for transactions in [nubank, inter, itau] {
+            assert_eq!(transactions.len(), 1);
+            assert_eq!(transactions[0].date, NaiveDate::from_ymd(2023, 10, 3));
+            assert_eq!(
+                transactions[0].amount,
+                BigDecimal::from_str("-12.50").unwrap()
+            );
+        }
+        assert!(Profile::preset("unknown").is_none());
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use bigdecimal::BigDecimal;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    archive::list_stored_files,
+    config::Config,
+    currency::Converter,
+    file::{BookkeepingFile, Location, StoredFile},
+    parser::EntryType,
+    reader::Reader,
+    sidecar,
+    stats::{MonthTotal, Stats},
+    Error, Result, Total,
+};
+
+/// The totals of every month, cached in the data folder
+pub const INDEX_FILE: &str = ".porquinho-index.toml";
+
+/// The totals of every month, keyed by `MM-YYYY`, so that summaries only parse the months
+/// that changed since they were last read
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Index {
+    #[serde(flatten)]
+    months: BTreeMap<String, Summary>,
+    #[serde(skip)]
+    path: PathBuf,
+    /// Whether a month was read since the index was loaded
+    #[serde(skip)]
+    changed: bool,
+}
+
+/// What's cached of a month, dropped when porquinho writes to it, and stale once its file's
+/// size or modification time changes, as they do after edits made elsewhere
+///
+/// Amounts are kept in the currency they were recorded in, `""` standing for the default one,
+/// so that changing the exchange rates doesn't make them stale.
+#[derive(Debug, Serialize, Deserialize)]
+struct Summary {
+    /// In nanoseconds since the epoch, `0` for archived months, which never change
+    mtime: u64,
+    size: u64,
+    /// How many entries the month has
+    count: usize,
+    incoming: BTreeMap<String, BigDecimal>,
+    outgoing: BTreeMap<String, BigDecimal>,
+}
+
+impl Index {
+    /// Loads the index of `dir`, starting over if it's missing or can't be read, as it can
+    /// always be rebuilt
+    pub fn load(dir: &Path, config: &Config) -> Result<Self> {
+        let path = dir.join(INDEX_FILE);
+
+        let mut index: Self = sidecar::load(&path, config, invalid).unwrap_or_else(|err| {
+            crate::debug!("rebuilding the index, as it couldn't be read: {}", err);
+            Self::default()
+        });
+        index.path = path;
+
+        Ok(index)
+    }
+
+    pub fn save(&self, config: &Config) -> Result<()> {
+        sidecar::save(&self.path, self, config, invalid)
+    }
+
+    /// The totals of every month in `files`, which are expected to be sorted chronologically,
+    /// only reading the ones whose summary is stale
+    pub fn totals(&mut self, files: &[StoredFile], config: &Config) -> Result<Vec<MonthTotal>> {
+        let converter = Converter::new(config);
+        let mut reader = Reader::new(config);
+        let mut totals = Vec::with_capacity(files.len());
+
+        // Months no longer in the book are forgotten
+        let before = self.months.len();
+        self.months
+            .retain(|month, _| files.iter().any(|stored| stored.file.as_str() == month));
+        self.changed |= self.months.len() != before;
+
+        for stored in files {
+            let (mtime, size) = match &stored.location {
+                Location::Data(path) => {
+                    let metadata = fs::metadata(path)?;
+                    let mtime = metadata.modified()?.duration_since(UNIX_EPOCH);
+                    let mtime = mtime.map_or(0, |mtime| mtime.as_nanos() as u64);
+                    (mtime, metadata.len())
+                }
+                Location::Archived(contents) => (0, contents.len() as u64),
+            };
+
+            let fresh = self
+                .months
+                .get(stored.file.as_str())
+                .is_some_and(|summary| summary.mtime == mtime && summary.size == size);
+            if !fresh {
+                crate::debug!("indexing {}", stored.file.as_str());
+                let summary = summarize(stored, mtime, size, &mut reader)?;
+                self.months.insert(stored.file.as_str().to_owned(), summary);
+                self.changed = true;
+            }
+            let summary = &self.months[stored.file.as_str()];
+
+            let mut total = Total::zero();
+            for (currency, amount) in &summary.incoming {
+                total.incoming += converter.convert(amount.clone(), currency_of(currency))?;
+            }
+            for (currency, amount) in &summary.outgoing {
+                total.outgoing += converter.convert(amount.clone(), currency_of(currency))?;
+            }
+            totals.push(MonthTotal {
+                file: stored.file.clone(),
+                total,
+            });
+        }
+
+        Ok(totals)
+    }
+}
+
+/// Drops what's cached of the month kept at `path`, as it's about to be written to
+///
+/// Its size and modification time may not change after an edit, as in `10` becoming `20` on
+/// a filesystem with coarse timestamps, so they can't be relied on to tell it apart.
+pub fn forget(path: &Path, config: &Config) -> Result<()> {
+    let name = path.file_name().and_then(OsStr::to_str);
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    if name.and_then(BookkeepingFile::from_file_name).is_none() || !dir.join(INDEX_FILE).exists() {
+        return Ok(());
+    }
+
+    let mut index = Index::load(dir, config)?;
+    if name.is_some_and(|name| index.months.remove(name).is_some()) {
+        index.save(config)?;
+    }
+
+    Ok(())
+}
+
+/// The totals of every month in `dir`, archived ones included, refreshing its index
+///
+/// Encrypted books aren't indexed, as the index would give their totals away.
+pub fn month_totals(dir: &Path, config: &Config) -> Result<Vec<MonthTotal>> {
+    let files = list_stored_files(dir)?;
+    if config.cipher.is_some() {
+        return Ok(Stats::from_files(files, config)?.months);
+    }
+
+    let mut index = Index::load(dir, config)?;
+    let totals = index.totals(&files, config)?;
+    if index.changed && !config.dry_run {
+        index.save(config)?;
+    }
+
+    Ok(totals)
+}
+
+fn summarize(stored: &StoredFile, mtime: u64, size: u64, reader: &mut Reader) -> Result<Summary> {
+    let mut summary = Summary {
+        mtime,
+        size,
+        count: 0,
+        incoming: BTreeMap::new(),
+        outgoing: BTreeMap::new(),
+    };
+
+    reader.for_each_entry_in(stored.open()?, |entry| {
+        summary.count += 1;
+        let currency = entry.currency.unwrap_or_default().to_owned();
+        match entry.typ {
+            EntryType::Debit => *summary.outgoing.entry(currency).or_default() += entry.amount,
+            EntryType::Credit => *summary.incoming.entry(currency).or_default() += entry.amount,
+            EntryType::Refund => *summary.outgoing.entry(currency).or_default() -= entry.amount,
+        }
+
+        Ok(())
+    })?;
+
+    Ok(summary)
+}
+
+fn invalid(err: String) -> Error {
+    io::Error::other(format!("invalid {}: {}", INDEX_FILE, err)).into()
+}
+
+fn currency_of(key: &str) -> Option<&str> {
+    Some(key).filter(|key| !key.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{month_totals, Index, INDEX_FILE};
+    use crate::{archive::list_stored_files, config::Config, writer::Writer};
+
+    #[test]
+    fn only_reads_stale_months() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("01-2024"), "05 + 100 Salary\n").unwrap();
+        fs::write(dir.path().join("02-2024"), "05 - USD 10 Book\n").unwrap();
+
+        let config: Config = toml::from_str("[rates]\nUSD = 5").unwrap();
+        let totals = month_totals(dir.path(), &config).unwrap();
+        assert_eq!(totals[0].total.incoming, BigDecimal::from(100));
+        assert_eq!(totals[1].total.outgoing, BigDecimal::from(50));
+        assert!(dir.path().join(INDEX_FILE).exists());
+
+        // Nothing changed, so nothing is read again
+        let files = list_stored_files(dir.path()).unwrap();
+        let mut index = Index::load(dir.path(), &config).unwrap();
+        index.totals(&files, &config).unwrap();
+        assert!(!index.changed);
+
+        fs::write(dir.path().join("02-2024"), "05 - USD 10 Book\n06 - 5 Pen\n").unwrap();
+        let files = list_stored_files(dir.path()).unwrap();
+        let totals = index.totals(&files, &config).unwrap();
+        assert!(index.changed);
+        assert_eq!(totals[1].total.outgoing, BigDecimal::from(55));
+    }
+
+    #[test]
+    fn writes_drop_the_months_they_change() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01-2024");
+        fs::write(&path, "05 - 10 Lunch\n").unwrap();
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        month_totals(dir.path(), &config).unwrap();
+
+        // Same size, which coarse timestamps wouldn't tell apart
+        Writer::edit_entry(&path, 1, &config, |operation| {
+            operation.amount = BigDecimal::from(20);
+            Ok(())
+        })
+        .unwrap();
+        let index = Index::load(dir.path(), &config).unwrap();
+        assert!(!index.months.contains_key("01-2024"));
+
+        let totals = month_totals(dir.path(), &config).unwrap();
+        assert_eq!(totals[0].total.outgoing, BigDecimal::from(20));
+    }
+}
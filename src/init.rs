@@ -0,0 +1,177 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+use fs_err as fs;
+
+use crate::{config::Config, i18n::tf, Result};
+
+/// Categories of a typical Brazilian household, with the share of the income usually set aside
+/// for each, appended to config.toml by `porquinho init`
+const STARTER: &str = r#"
+# Starter categories and budgets, added by `porquinho init`
+
+[budgets]
+moradia = "30"
+alimentacao = "15"
+transporte = "10"
+saude = "10"
+educacao = "5"
+lazer = "5"
+
+[[rules]]
+pattern = "aluguel"
+category = "moradia"
+[[rules]]
+pattern = "condominio"
+category = "moradia"
+[[rules]]
+pattern = "iptu"
+category = "moradia"
+[[rules]]
+pattern = "conta de luz"
+category = "moradia"
+[[rules]]
+pattern = "sabesp"
+category = "moradia"
+[[rules]]
+pattern = "internet"
+category = "moradia"
+[[rules]]
+pattern = "mercado"
+category = "alimentacao"
+[[rules]]
+pattern = "padaria"
+category = "alimentacao"
+[[rules]]
+pattern = "ifood"
+category = "alimentacao"
+[[rules]]
+pattern = "restaurante"
+category = "alimentacao"
+[[rules]]
+pattern = "acougue"
+category = "alimentacao"
+[[rules]]
+pattern = "uber"
+category = "transporte"
+[[rules]]
+pattern = "combustivel"
+category = "transporte"
+[[rules]]
+pattern = "gasolina"
+category = "transporte"
+[[rules]]
+pattern = "estacionamento"
+category = "transporte"
+[[rules]]
+pattern = "bilhete unico"
+category = "transporte"
+[[rules]]
+pattern = "farmacia"
+category = "saude"
+[[rules]]
+pattern = "drogaria"
+category = "saude"
+[[rules]]
+pattern = "plano de saude"
+category = "saude"
+[[rules]]
+pattern = "consulta"
+category = "saude"
+[[rules]]
+pattern = "escola"
+category = "educacao"
+[[rules]]
+pattern = "faculdade"
+category = "educacao"
+[[rules]]
+pattern = "curso"
+category = "educacao"
+[[rules]]
+pattern = "livro"
+category = "educacao"
+[[rules]]
+pattern = "cinema"
+category = "lazer"
+[[rules]]
+pattern = "netflix"
+category = "lazer"
+[[rules]]
+pattern = "spotify"
+category = "lazer"
+[[rules]]
+pattern = "show"
+category = "lazer"
+"#;
+
+/// Offers to add the starter categories and budgets to the config file at `path`
+pub fn init(path: &Path, config: &Config, mut input: impl BufRead) -> Result<()> {
+    if !config.rules.is_empty() || !config.budgets.is_empty() {
+        println!(
+            "{} already has rules or budgets, so it was left alone",
+            path.display()
+        );
+        return Ok(());
+    }
+
+    // Safety: checked by the `starter_config_is_valid` test
+    let starter: Config = toml::from_str(STARTER).unwrap();
+    let mut patterns: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for rule in &starter.rules {
+        patterns
+            .entry(&rule.category)
+            .or_default()
+            .push(&rule.pattern);
+    }
+
+    println!("Starter categories, with their budgets as a share of the month's income");
+    for (category, patterns) in &patterns {
+        let budget = starter
+            .budgets
+            .get(*category)
+            .map(|percent| format!(" ({}%)", percent))
+            .unwrap_or_default();
+        println!("\t{}{}: {}", category, budget, patterns.join(", "));
+    }
+
+    if config.dry_run {
+        println!("Would append them to {}", path.display());
+        return Ok(());
+    }
+
+    print!("{} ", tf("Add them to {}? [Y/n]", &[&path.display()]));
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    if matches!(answer.trim(), "n" | "N") {
+        return Ok(());
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)?;
+    file.write_all(STARTER.as_bytes())?;
+
+    println!("Added them, edit {} to adjust them", path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::STARTER;
+    use crate::config::Config;
+
+    #[test]
+    fn starter_config_is_valid() {
+        let starter: Config = toml::from_str(STARTER).unwrap();
+
+        for category in starter.budgets.keys() {
+            assert!(starter.rules.iter().any(|rule| rule.category == *category));
+        }
+    }
+}
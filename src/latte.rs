@@ -0,0 +1,159 @@
+use bigdecimal::{BigDecimal, One, Zero};
+
+use crate::{
+    config::Config,
+    currency::Converter,
+    file::{BookkeepingFile, StoredFile},
+    parser::EntryType,
+    reader::Reader,
+    rules::RuleSet,
+    Result,
+};
+
+/// How much has been spent on a small, recurring habit
+pub struct Latte {
+    /// Amount spent on the habit in each month that has a bookkeeping file
+    pub months: Vec<(BookkeepingFile, BigDecimal)>,
+}
+
+impl Latte {
+    /// Sums every take about `habit`, a category or part of a description
+    pub fn compute(files: Vec<StoredFile>, habit: &str, config: &Config) -> Result<Self> {
+        let rules = RuleSet::from_config(config);
+        let converter = Converter::new(config);
+        let mut reader = Reader::new(config);
+        let mut months = Vec::with_capacity(files.len());
+
+        for stored in files {
+            let mut spent = BigDecimal::zero();
+
+            reader.for_each_entry_in(stored.open()?, |entry| {
+                if entry.typ == EntryType::Debit && rules.is_about(entry.description, habit) {
+                    spent += converter.convert(entry.amount, entry.currency)?;
+                }
+
+                Ok(())
+            })?;
+
+            months.push((stored.file, spent));
+        }
+
+        Ok(Self { months })
+    }
+
+    /// Amount spent in the `months` months up to and including `current`
+    pub fn spent_over(&self, current: &BookkeepingFile, months: u32) -> BigDecimal {
+        let first = current.ordinal() - months as i32 + 1;
+
+        self.months
+            .iter()
+            .filter(|(file, _)| (first..=current.ordinal()).contains(&file.ordinal()))
+            .map(|(_, spent)| spent)
+            .sum()
+    }
+
+    /// Projects the spending of the next twelve months from the average of the last `months`
+    pub fn yearly_projection(&self, current: &BookkeepingFile, months: u32) -> BigDecimal {
+        // Multiplying first keeps whole results exact, e.g. 25 over 3 months is exactly 100
+        self.spent_over(current, months) * BigDecimal::from(12) / BigDecimal::from(months.max(1))
+    }
+}
+
+impl Latte {
+    pub fn print(
+        &self,
+        habit: &str,
+        current: &BookkeepingFile,
+        horizons: &[u32],
+        invested_at: Option<&BigDecimal>,
+        config: &Config,
+    ) {
+        println!("Spent on {:?}", habit);
+        for &months in horizons {
+            let spent = self.spent_over(current, months);
+            println!(
+                "\tLast {} month(s): {}",
+                months,
+                config.format_amount(&spent)
+            );
+        }
+
+        // The longest horizon gives the steadiest average
+        let longest = horizons.iter().copied().max().unwrap_or(12);
+        let projection = self.yearly_projection(current, longest);
+        println!(
+            "\tProjected for the next 12 months: {}",
+            config.format_amount(&projection)
+        );
+
+        if let Some(rate) = invested_at {
+            println!(
+                "\tIf invested at {}% a year instead: {}",
+                rate,
+                config.format_amount(&invested_for_a_year(&projection, rate))
+            );
+        }
+    }
+}
+
+/// What a year of equal monthly deposits adding up to `yearly` would be worth,
+/// if invested at `annual_rate` percent a year (compounded monthly)
+pub fn invested_for_a_year(yearly: &BigDecimal, annual_rate: &BigDecimal) -> BigDecimal {
+    let monthly_deposit = yearly / BigDecimal::from(12);
+    let monthly_rate = annual_rate / BigDecimal::from(1200);
+    let growth = BigDecimal::one() + monthly_rate;
+
+    let mut balance = BigDecimal::zero();
+    for _ in 0..12 {
+        balance = (balance + &monthly_deposit) * &growth;
+    }
+
+    balance
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{invested_for_a_year, Latte};
+    use crate::{archive::list_stored_files, config::Config, file::BookkeepingFile};
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn sums_spending_on_a_habit_over_horizons() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("08-2024"), "02 - 10.00 Café\n").unwrap();
+        fs::write(
+            dir.path().join("10-2024"),
+            "02 - 8.00 cafe da manha\n03 - 50.00 Lunch\n04 - 7.00 CAFÉ\n",
+        )
+        .unwrap();
+
+        let files = list_stored_files(dir.path()).unwrap();
+        let latte = Latte::compute(files, "cafe", &Config::default()).unwrap();
+        let current = BookkeepingFile::new(10, 2024);
+
+        assert_eq!(latte.spent_over(&current, 1), decimal("15"));
+        assert_eq!(latte.spent_over(&current, 3), decimal("25"));
+        assert_eq!(latte.yearly_projection(&current, 3), decimal("100"));
+    }
+
+    #[test]
+    fn compounds_monthly_deposits() {
+        assert_eq!(
+            invested_for_a_year(&decimal("1200"), &decimal("0")),
+            decimal("1200")
+        );
+        assert_eq!(
+            invested_for_a_year(&decimal("1200"), &decimal("12")).with_scale(2),
+            decimal("1280.93")
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Simplistic personal finances helper
+//!
+//! The book is a folder of plain text files, one per month (`MM-YYYY`), with one entry per line:
+//!
+//! ```text
+//! 05 + 3000 Salary
+//! 06 - 45.90 Groceries
+//! 09 ~ 12.00 Groceries refund
+//! ```
+//!
+//! `Bookkeeper` reads and writes them, and computes their `Status` and `Stats`. Every module is
+//! public, as the `porquinho` binary is built on top of this library.
+
+pub mod alerts;
+pub mod amount;
+pub mod archive;
+pub mod backup;
+pub mod balances;
+pub mod bills;
+pub mod bookkeeper;
+pub mod card;
+pub mod categorize;
+pub mod chart;
+pub mod checklist;
+pub mod color;
+pub mod compare;
+pub mod config;
+pub mod crypt;
+pub mod currency;
+pub mod dates;
+pub mod debts;
+pub mod dedupe;
+pub mod diagnostics;
+pub mod dirs;
+pub mod doctor;
+pub mod editor;
+pub mod error;
+pub mod export;
+pub mod file;
+pub mod forecast;
+pub mod formatter;
+pub mod git;
+pub mod goals;
+pub mod hooks;
+pub mod i18n;
+pub mod ical;
+pub mod import;
+pub mod importers;
+pub mod index;
+pub mod init;
+pub mod latte;
+pub mod locale;
+pub mod log;
+pub mod migrations;
+pub mod outliers;
+pub mod parser;
+pub mod pick;
+pub mod qr;
+pub mod quarter;
+pub mod query;
+pub mod reader;
+pub mod rename;
+pub mod report;
+pub mod rules;
+pub mod selftest;
+pub mod serve;
+pub mod session;
+pub mod sidecar;
+pub mod sql;
+pub mod sqlite;
+pub mod stats;
+pub mod status;
+pub mod storage;
+pub mod tax;
+pub mod top;
+pub mod trend;
+pub mod watch;
+pub mod webhook;
+pub mod writer;
+pub mod xlsx;
+
+use bigdecimal::{BigDecimal, Zero};
+
+pub use bookkeeper::Bookkeeper;
+pub use error::{Error, Result};
+
+#[derive(Debug)]
+pub struct Total {
+    /// Amount spended
+    pub outgoing: BigDecimal,
+    /// Amount received
+    pub incoming: BigDecimal,
+}
+
+impl Total {
+    pub fn zero() -> Self {
+        Self {
+            outgoing: BigDecimal::zero(),
+            incoming: BigDecimal::zero(),
+        }
+    }
+
+    /// Amount received minus amount spended
+    pub fn balance(&self) -> BigDecimal {
+        &self.incoming - &self.outgoing
+    }
+
+    /// The share of the amount received that wasn't spended, in percent
+    ///
+    /// Returns `None` if nothing was received
+    pub fn savings_rate(&self) -> Option<BigDecimal> {
+        if self.incoming.is_zero() {
+            return None;
+        }
+
+        Some((self.balance() * BigDecimal::from(100) / &self.incoming).with_scale(1))
+    }
+}
@@ -0,0 +1,182 @@
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, Signed};
+use serde::Deserialize;
+
+/// Conventions used when reading and printing amounts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Locale {
+    /// `1,234.56`
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    /// `1.234,56`
+    #[serde(rename = "pt-BR")]
+    BrazilianPortuguese,
+}
+
+impl Locale {
+    pub const fn decimal_separator(self) -> char {
+        match self {
+            Self::English => '.',
+            Self::BrazilianPortuguese => ',',
+        }
+    }
+
+    pub const fn thousands_separator(self) -> char {
+        match self {
+            Self::English => ',',
+            Self::BrazilianPortuguese => '.',
+        }
+    }
+
+    /// Parses a decimal written either with a dot or with this locale's decimal separator
+    ///
+    /// Thousands separators are never accepted, as they'd make `1.234` ambiguous
+    pub fn parse_decimal(self, input: &str) -> Option<BigDecimal> {
+        let separator = self.decimal_separator();
+
+        if separator != '.' && input.contains(separator) {
+            BigDecimal::from_str(&input.replacen(separator, ".", 1)).ok()
+        } else {
+            BigDecimal::from_str(input).ok()
+        }
+    }
+}
+
+/// How amounts are rounded to the decimal places they're printed with
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    /// Halves away from zero, so `0.125` is `0.13`
+    #[default]
+    HalfUp,
+    /// Halves to the even digit, so `0.125` is `0.12` and `0.135` is `0.14`, as banks often do
+    HalfEven,
+    /// Towards zero, dropping the extra digits
+    Down,
+}
+
+impl Rounding {
+    /// Rounds `amount` to `scale` decimal places
+    pub fn apply(self, amount: &BigDecimal, scale: i64) -> BigDecimal {
+        match self {
+            Self::HalfUp => round(amount, scale),
+            Self::Down => amount.with_scale(scale),
+            Self::HalfEven => {
+                let truncated = amount.with_scale(scale);
+                let rest = (amount - &truncated).abs();
+                let half = BigDecimal::new(5.into(), scale + 1);
+                let (digits, _) = truncated.as_bigint_and_exponent();
+                let odd = &digits % 2 != 0.into();
+
+                if rest > half || (rest == half && odd) {
+                    round(amount, scale)
+                } else {
+                    truncated
+                }
+            }
+        }
+    }
+}
+
+/// Formats the absolute value of `amount` with `decimals` decimal places and grouped thousands
+pub fn format_digits(
+    amount: &BigDecimal,
+    decimals: u8,
+    rounding: Rounding,
+    decimal_separator: char,
+    thousands_separator: char,
+) -> String {
+    let rounded = rounding.apply(amount, decimals.into()).abs().to_string();
+    let (integer, fraction) = rounded.split_once('.').unwrap_or((&rounded, ""));
+
+    let mut formatted = String::with_capacity(rounded.len() + integer.len() / 3);
+
+    for (idx, digit) in integer.chars().enumerate() {
+        if idx > 0 && (integer.len() - idx) % 3 == 0 {
+            formatted.push(thousands_separator);
+        }
+        formatted.push(digit);
+    }
+
+    if !fraction.is_empty() {
+        formatted.push(decimal_separator);
+        formatted.push_str(fraction);
+    }
+
+    formatted
+}
+
+/// Rounds half away from zero to `scale` decimal places
+pub fn round(amount: &BigDecimal, scale: i64) -> BigDecimal {
+    let half = BigDecimal::new(5.into(), scale + 1);
+
+    if amount.is_negative() {
+        (amount - half).with_scale(scale)
+    } else {
+        (amount + half).with_scale(scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
+    use super::{format_digits, Locale, Rounding};
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn parses_decimals_with_the_locale_separator() {
+        let pt_br = Locale::BrazilianPortuguese;
+        assert_eq!(pt_br.parse_decimal("25,50"), Some(decimal("25.50")));
+        assert_eq!(pt_br.parse_decimal("25.50"), Some(decimal("25.50")));
+        assert_eq!(pt_br.parse_decimal("1.234,50"), None);
+
+        let en = Locale::English;
+        assert_eq!(en.parse_decimal("25.50"), Some(decimal("25.50")));
+        assert_eq!(en.parse_decimal("25,50"), None);
+    }
+
+    #[test]
+    fn formats_amounts_with_thousands_separators() {
+        let amount = decimal("1234567.891");
+
+        let format = |locale: Locale, amount: &BigDecimal| {
+            format_digits(
+                amount,
+                2,
+                Rounding::HalfUp,
+                locale.decimal_separator(),
+                locale.thousands_separator(),
+            )
+        };
+
+        assert_eq!(format(Locale::English, &amount), "1,234,567.89");
+        assert_eq!(format(Locale::BrazilianPortuguese, &amount), "1.234.567,89");
+        assert_eq!(format(Locale::English, &decimal("-999.995")), "1,000.00");
+        assert_eq!(format(Locale::English, &decimal("5")), "5.00");
+        assert_eq!(format(Locale::English, &decimal("123")), "123.00");
+    }
+
+    #[test]
+    fn rounds_to_the_configured_precision() {
+        let format = |amount: &str, decimals, rounding| {
+            format_digits(&decimal(amount), decimals, rounding, '.', ',')
+        };
+
+        assert_eq!(format("0.125", 2, Rounding::HalfUp), "0.13");
+        assert_eq!(format("0.125", 2, Rounding::HalfEven), "0.12");
+        assert_eq!(format("0.135", 2, Rounding::HalfEven), "0.14");
+        assert_eq!(format("0.129", 2, Rounding::Down), "0.12");
+        // Currencies such as the yen have no cents, and others such as the dinar have three
+        assert_eq!(format("1234.5", 0, Rounding::HalfUp), "1,235");
+        assert_eq!(format("2.5", 0, Rounding::HalfEven), "2");
+        assert_eq!(format("1.2345", 3, Rounding::HalfUp), "1.235");
+    }
+}
@@ -0,0 +1,50 @@
+//! How much porquinho says besides what was asked for, set once by `--quiet` or `--verbose`
+//!
+//! `info!` is for chatter like "Updated ...", and `debug!` for details only worth seeing
+//! when something looks off, like which files were read. Both are no-ops when silenced.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only what was asked for, and errors
+    Quiet,
+    /// Also say what changed
+    Normal,
+    /// Also log which files are read and written, and how they're parsed
+    Verbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose,
+    }
+}
+
+/// Prints to stdout, unless `--quiet` was given
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::verbosity() >= $crate::log::Verbosity::Normal {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Prints to stderr, only if `--verbose` was given
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::verbosity() >= $crate::log::Verbosity::Verbose {
+            eprintln!("debug: {}", format_args!($($arg)*));
+        }
+    };
+}
@@ -1,38 +1,96 @@
 mod cli;
-mod dirs;
-mod error;
-mod file;
-mod parser;
-mod reader;
-mod writer;
-
-use std::path::PathBuf;
-
-use bigdecimal::BigDecimal;
-use chrono::{Datelike, Local};
-use clap::Parser;
-use dirs::Dirs;
-use error::{Error, Result};
-use parser::{Entry, EntryType};
-use reader::Reader;
-
-use crate::{
-    cli::{Opts, Subcommand},
-    file::{create_file_if_not_existent, BookkeepingFile},
+
+use std::{
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Datelike, Utc};
+use fs_err as fs;
+use porquinho::{
+    alerts::{self, breaches, low_balance},
+    amount,
+    archive::{archive_year_files, list_stored_files},
+    balances::{self, OpeningBalances},
+    bills::{Bill, BillState, Bills},
+    card::{card_dir, Statement},
+    categorize::categorize_file,
+    chart::{self, DailySpending},
+    checklist::{print_checklist, run_checklist, Progress},
+    compare::Comparison,
+    config::Config,
+    currency::{is_currency_code, Converter},
+    dates::{
+        current_day, cycle_day, file_and_day, last_day, parse_date, set_month_start, set_today,
+        today,
+    },
+    debts::{people, split_evenly, Debts, Movement},
+    debug,
+    dedupe::{dedupe, duplicate_of},
+    diagnostics::{Code, Diagnostics},
+    dirs::Dirs,
+    doctor::{examine, report},
+    editor::edit_in_editor,
+    error::{Error, Result},
+    export::{collect_records, read_records, write_records, Format, Record},
+    file::{create_file_if_not_existent, list_bookkeeping_files, BookkeepingFile, StoredFile},
+    forecast::{recurring, Forecast},
+    git::Git,
+    goals::{Goal, Goals},
+    hooks::{self, run_hook, warn_on_failure},
+    i18n::{is_yes, language, set_language, t, tf, Language},
+    ical::{write_calendar, Event},
+    import::import_records,
+    importers::{CategoryMap, PRESETS},
+    index::INDEX_FILE,
+    info,
+    init::init,
+    latte::Latte,
+    log::{self, Verbosity},
+    migrations::FORMAT_VERSION,
+    outliers::{find_outliers, print_outliers},
+    parser::{Entry, EntryType},
+    pick::{pick_description, PICK},
+    qr::render_month,
+    quarter::Quarter,
+    query::Query,
+    rename::{known_descriptions, may_be_misspelled, rename_in_file, suggest},
+    report::{write_report, REPORTS_FOLDER},
+    rules::{attributed, auto_tag, untagged, RuleSet},
+    selftest::selftest,
+    serve::serve,
+    session::{run_batch, run_paste, run_session},
+    sidecar, sql,
+    sqlite::Backend,
+    stats::Stats,
+    status::{self, Column, GroupBy, Status},
+    tax::TaxReport,
+    top::Top,
+    trend::Trend,
+    watch::watch as watch_dir,
     writer::Writer,
+    xlsx::write_workbook,
+    Bookkeeper,
 };
 
-#[derive(Debug)]
-pub struct Total {
-    /// Amount spended
-    pub outgoing: BigDecimal,
-    /// Amount received
-    pub incoming: BigDecimal,
-}
+use crate::cli::{
+    BillAction, CardAction, ChecklistAction, GoalAction, Opts, ProfileAction, Subcommand, WORKFLOWS,
+};
 
 fn main() {
     if let Err(err) = exec() {
-        eprintln!("Error: {}", err);
+        // `check` is silent, only its exit code tells if it passed
+        if let Error::CheckFailed = err {
+            std::process::exit(1);
+        }
+
+        eprintln!(
+            "{}[{}]: {}",
+            t("error"),
+            err.code().as_str(),
+            language().error(&err)
+        );
         std::process::exit(127);
     }
 }
@@ -40,61 +98,1408 @@ fn main() {
 struct GlobalState {
     opts: Opts,
     dirs: Dirs,
+    book: Bookkeeper,
+    /// The data folder's repository, if `config.git` is set
+    git: Option<Git>,
+    /// What to do with warnings, following `--allow` and `--deny`
+    diagnostics: Diagnostics,
     // Bookkeeping path
     bk_path: PathBuf,
 }
 
 impl GlobalState {
     pub fn new() -> Result<Self> {
-        let opts = Opts::parse();
-        let dirs = Dirs::init()?;
+        set_language(Language::from_env());
+        let opts = Opts::parse_in(language());
+        if let Some(date) = &opts.today {
+            let date = parse_date(date, today()).ok_or_else(|| Error::InvalidDate(date.clone()))?;
+            set_today(date);
+        }
+        // Reports written by cron, checks and balances for status bars must be silent, as
+        // whatever's printed gets mailed or shown
+        let auto_report = matches!(
+            opts.cmd,
+            Subcommand::Report { auto: true, .. }
+                | Subcommand::Check { .. }
+                | Subcommand::Balance { .. }
+        );
+        log::set_verbosity(if opts.quiet || auto_report {
+            Verbosity::Quiet
+        } else if opts.verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        });
+        // `default` names the book kept without profiles
+        let profile = opts.profile.as_deref().filter(|name| *name != "default");
+        let mut dirs = Dirs::init(profile)?;
+        if opts.ephemeral {
+            dirs.make_ephemeral()?;
+        }
+        debug!(
+            "config folder {}, data folder {}",
+            dirs.config().display(),
+            dirs.data().display()
+        );
+        let diagnostics = Diagnostics::new(&opts.allow, &opts.deny)?;
+        let mut config = Config::load(&dirs.config().join("config.toml"))?;
+        config.dry_run = opts.dry_run;
+        config.color = opts.color.enabled();
+        if opts.cmd.reads_book() {
+            config.unlock(dirs.data())?;
+        }
+        if let Some(language) = config.language {
+            set_language(language);
+        }
+        set_month_start(config.month_starts_on);
+
+        let book = Bookkeeper::open(dirs.data(), config)?;
+        if let Some(from) = book.upgraded_from() {
+            info!(
+                "Upgraded the data folder from format {} to {}, a copy of the old one is in backups/format-{}",
+                from, FORMAT_VERSION, from
+            );
+        }
+        let git = book
+            .config()
+            .git
+            .then(|| Git::open(dirs.data(), book.config().dry_run))
+            .transpose()?;
 
         let bk_path = dirs.data().join(BookkeepingFile::current_file().as_path());
-        create_file_if_not_existent(&bk_path);
+        if book.config().storage == Backend::Files && !book.config().dry_run {
+            create_file_if_not_existent(&bk_path);
+        }
 
         Ok(Self {
             opts,
             dirs,
+            book,
+            git,
+            diagnostics,
             bk_path,
         })
     }
 
     pub fn run_command(self) -> Result<()> {
-        let day = Local::today().day() as u8;
+        let day = current_day();
+        let options = self.opts.global_options();
         let Self {
             ref bk_path,
-            opts: Opts { cmd },
+            ref dirs,
+            ref book,
+            ref git,
+            ref diagnostics,
+            opts: Opts {
+                cmd, yes, profile, ..
+            },
             ..
         } = self;
 
+        let config = book.config();
+        let mut cmd = cmd;
+        pick_in_command(&mut cmd, book.dir(), config)?;
+        attribute_in_command(&mut cmd);
+        let commit_message = cmd.commit_message();
+        let reads_book = cmd.reads_book();
+        if config.storage == Backend::Sqlite && !cmd.works_on_database() {
+            return Err(Error::Database(
+                "this command only works with books kept in files, see `storage` in config.toml"
+                    .into(),
+            ));
+        }
+
         match cmd {
             Subcommand::Take {
-                amount,
+                ref amount,
                 ref description,
+                ref currency,
+                ref date,
+                day: entry_day,
+                ..
             } => {
-                let entry = Entry::new(day, EntryType::Debit, amount, description);
-                Writer::write_entry(bk_path, entry)?;
+                let (file, day) = entry_date(date.as_deref(), entry_day)?;
+                let path = book.location(&file);
+                let amount = parse_amount(amount, config)?;
+                let tagged = auto_tag(config, description);
+                let entry = Entry {
+                    currency: parse_currency(currency.as_deref())?,
+                    ..Entry::new(
+                        day,
+                        EntryType::Debit,
+                        amount,
+                        tagged.as_deref().unwrap_or(description),
+                    )
+                };
+                confirm_entry(book, &file, &entry, diagnostics, yes)?;
+                let month_stats = || book.month_stats(&file);
+                // Only worth reading the month if there's a limit to go over
+                let has_limits = !config.budgets.is_empty() || config.spending_target.is_some();
+                let before = has_limits.then(month_stats).transpose()?;
+
+                book.record(&file, entry)?;
+                print_updated(&path, config);
+                if let Some(before) = before {
+                    let breaches = breaches(&before, &month_stats()?, config);
+                    for breach in &breaches {
+                        diagnostics.warn(Code::OverLimit, breach.overshoot(config))?;
+                    }
+                    if config.notify {
+                        alerts::notify(&breaches, config);
+                    }
+                }
+                // Bills are only tracked against bookkeeping files
+                if config.storage == Backend::Files {
+                    print_paid_bills(dirs.data(), &path, day, description, config)?;
+                }
             }
             Subcommand::Put {
-                amount,
+                ref amount,
+                ref description,
+                ref currency,
+                ref date,
+                day: entry_day,
+                ..
+            } => {
+                let (file, day) = entry_date(date.as_deref(), entry_day)?;
+                let path = book.location(&file);
+                let amount = parse_amount(amount, config)?;
+                let tagged = auto_tag(config, description);
+                let entry = Entry {
+                    currency: parse_currency(currency.as_deref())?,
+                    ..Entry::new(
+                        day,
+                        EntryType::Credit,
+                        amount,
+                        tagged.as_deref().unwrap_or(description),
+                    )
+                };
+                confirm_entry(book, &file, &entry, diagnostics, yes)?;
+                book.record(&file, entry)?;
+                print_updated(&path, config);
+            }
+            Subcommand::Refund {
+                ref amount,
+                ref description,
+                ref currency,
+                ref date,
+                day: entry_day,
+                ..
+            } => {
+                let (file, day) = entry_date(date.as_deref(), entry_day)?;
+                let path = book.location(&file);
+                let amount = parse_amount(amount, config)?;
+                let tagged = auto_tag(config, description);
+                let entry = Entry {
+                    currency: parse_currency(currency.as_deref())?,
+                    ..Entry::new(
+                        day,
+                        EntryType::Refund,
+                        amount,
+                        tagged.as_deref().unwrap_or(description),
+                    )
+                };
+                confirm_entry(book, &file, &entry, diagnostics, yes)?;
+                book.record(&file, entry)?;
+                print_updated(&path, config);
+            }
+            Subcommand::Status {
+                complete,
+                ref columns,
+                ref convert,
+                sort,
+                desc,
+                group_by,
+                by_person,
+                format,
+                ref month,
+                watch,
+            } => {
+                let converter = match parse_currency(convert.as_deref())? {
+                    Some(target) => Converter::to(config, target),
+                    None => Converter::new(config),
+                };
+                let file = parse_month(month.as_deref())?;
+                // Other months are shown whole, compared with the whole month before them
+                let day = if file.as_str() == BookkeepingFile::current_file().as_str() {
+                    day
+                } else {
+                    u8::MAX
+                };
+                let columns = if !columns.is_empty() {
+                    &columns[..]
+                } else if (complete && group_by.is_none()) || sort.is_some() {
+                    &Column::ALL[..]
+                } else {
+                    &[]
+                };
+
+                let show = || -> Result<()> {
+                    let mut status = book.status_in(file.clone(), day, &converter)?;
+                    if let Some(key) = sort {
+                        status.sort(key, desc);
+                    }
+                    // Plain output has a single kind of line, to be cut and grepped
+                    if format != status::Format::Plain || (group_by.is_none() && !by_person) {
+                        status.print(config, columns, format);
+                    }
+                    if let Some(by) = group_by {
+                        let groups = status.groups(by, config, &converter)?;
+                        status.print_groups(&groups, by, config, format);
+                    }
+                    if by_person {
+                        let shares = status.shares(config, &converter)?;
+                        status.print_shares(&shares, config, format);
+                    }
+
+                    for (code, message) in status.warnings(config) {
+                        diagnostics.warn(code, message)?;
+                    }
+
+                    Ok(())
+                };
+                if watch {
+                    watch_dir(book.dir(), show)?;
+                } else {
+                    show()?;
+                }
+            }
+            Subcommand::Report {
+                ref month,
+                ref output,
+                format,
+                auto,
+            } => {
+                let file = if auto {
+                    BookkeepingFile::current_file().previous()
+                } else {
+                    parse_month(month.as_deref())?
+                };
+                let output = if auto {
+                    let folder = match &config.reports_folder {
+                        Some(folder) => folder.clone(),
+                        None => dirs.data().join(REPORTS_FOLDER),
+                    };
+                    Some(folder.join(format!("{}.{}", file.as_str(), format.extension())))
+                } else {
+                    output.clone()
+                };
+
+                let exists = book.dir().join(file.as_path()).exists();
+                let reported = output.as_deref().is_some_and(Path::exists);
+                if auto && (!exists || reported) {
+                    debug!("nothing to report for {}", file.as_str());
+                } else if !exists {
+                    return Err(Error::NoSuchMonth(file.as_str().to_owned()));
+                } else {
+                    let converter = Converter::new(config);
+                    let day = file.days();
+                    let status = Status::compute(book.dir(), file, day, config, &converter)?;
+                    let groups = status.groups(GroupBy::Category, config, &converter)?;
+
+                    match output {
+                        Some(output) if config.dry_run => {
+                            println!("Would write the report into {}", output.display());
+                        }
+                        Some(output) => {
+                            if let Some(folder) = output.parent() {
+                                fs::create_dir_all(folder)?;
+                            }
+                            let mut report = Vec::new();
+                            write_report(&status, &groups, config, format, &mut report)?;
+                            // Those written by --auto are kept along with the book, encrypted
+                            // like it
+                            if auto {
+                                Writer::replace_contents(&output, report, config)?;
+                            } else {
+                                Writer::write_plain(&output, &report, config)?;
+                            }
+                            info!("Wrote the report into {}", output.display());
+                        }
+                        None => write_report(&status, &groups, config, format, &mut io::stdout())?,
+                    }
+                }
+            }
+            Subcommand::Forecast => {
+                let file = BookkeepingFile::current_file();
+                Forecast::compute(book.dir(), file, day, config)?.print(config);
+            }
+            Subcommand::Balance { all } => {
+                let total = if all {
+                    book.lifetime_total()?
+                } else {
+                    book.month_stats(&BookkeepingFile::current_file())?
+                        .lifetime_total()
+                };
+                let style = &config.amounts;
+                println!(
+                    "{}",
+                    style
+                        .rounding
+                        .apply(&total.balance(), style.decimals.into())
+                );
+            }
+            Subcommand::Edit {
+                id,
+                ref amount,
                 ref description,
             } => {
-                let entry = Entry::new(day, EntryType::Credit, amount, description);
-                Writer::write_entry(bk_path, entry)?;
+                let file = BookkeepingFile::current_file();
+                book.edit(&file, id, |operation| {
+                    if let Some(amount) = amount {
+                        operation.amount = adjust_amount(&operation.amount, amount, config)?;
+                    }
+                    if let Some(description) = description {
+                        operation.description = description.clone();
+                    }
+
+                    Ok(())
+                })?;
+                print_updated(&book.location(&file), config);
+            }
+            Subcommand::Init => init(
+                &dirs.config().join("config.toml"),
+                config,
+                io::stdin().lock(),
+            )?,
+            Subcommand::Batch => {
+                let recorded = run_batch(io::stdin().lock(), bk_path, day, config)?;
+                info!("Recorded {} operation(s)", recorded);
+            }
+            Subcommand::Paste => {
+                let recorded = run_paste(io::stdin().lock(), bk_path, config)?;
+                info!("Recorded {} operation(s)", recorded);
+            }
+            Subcommand::Interactive => run_session(io::stdin().lock(), bk_path, day, config)?,
+            Subcommand::Stats {
+                all_time,
+                ref month,
+            } => {
+                let stats = if all_time {
+                    book.stats()?
+                } else {
+                    book.month_stats(&parse_month(month.as_deref())?)?
+                };
+
+                stats.print(config);
+            }
+            Subcommand::Compare {
+                ref before,
+                ref after,
+                year,
+            } => {
+                // Each period's stats, and how it's named
+                let stats = |period: &str| -> Result<(Stats, String)> {
+                    if !year {
+                        let stored = find_month(dirs.data(), Some(period))?;
+                        let label = stored.file.label();
+                        return Ok((Stats::from_files(vec![stored], config)?, label));
+                    }
+
+                    let year: i32 = period
+                        .parse()
+                        .map_err(|_| Error::InvalidYear(period.to_owned()))?;
+                    let files: Vec<_> = list_stored_files(dirs.data())?
+                        .into_iter()
+                        .filter(|stored| stored.file.year() == year)
+                        .collect();
+                    if files.is_empty() {
+                        return Err(Error::NoSuchMonth(period.to_owned()));
+                    }
+                    Ok((Stats::from_files(files, config)?, period.to_owned()))
+                };
+                let (before, before_label) = stats(before)?;
+                let (after, after_label) = stats(after)?;
+                let comparison = Comparison { before, after };
+
+                comparison.print(&before_label, &after_label, config);
+                if year {
+                    comparison.print_by_month(config);
+                }
+            }
+            Subcommand::Quarter { ref quarter } => {
+                let first_month = config.fiscal_year_starts_in;
+                let quarter = match quarter {
+                    Some(quarter) => Quarter::parse(quarter)
+                        .ok_or_else(|| Error::InvalidQuarter(quarter.to_owned()))?,
+                    None => Quarter::of(&BookkeepingFile::current_file(), first_month),
+                };
+                let months = quarter.months(first_month);
+                let stats = book.period_stats(&months)?;
+                if stats.months.is_empty() {
+                    return Err(Error::NoSuchMonth(quarter.to_string()));
+                }
+
+                println!(
+                    "{} ({} – {})",
+                    quarter,
+                    months[0].label(),
+                    months[months.len() - 1].label()
+                );
+                stats.print(config);
+            }
+            Subcommand::TaxReport { ref year } => {
+                let year = year
+                    .parse()
+                    .map_err(|_| Error::InvalidYear(year.to_owned()))?;
+                TaxReport::from_files(year, list_stored_files(dirs.data())?, config)?.print(config);
+            }
+            Subcommand::Query { ref query } => {
+                let query = Query::parse(query, config)?;
+                query
+                    .answer(list_stored_files(dirs.data())?, config)?
+                    .print(config);
+            }
+            Subcommand::Sql { ref statement } => sql::query(book, statement)?.print(),
+            Subcommand::Check {
+                ref balance_above,
+                under_budget,
+            } => {
+                let file = BookkeepingFile::current_file();
+                let stats = book.month_stats(&file)?;
+                let mut passed = true;
+
+                if let Some(threshold) = balance_above {
+                    let threshold = parse_signed_amount(threshold, config)?;
+                    let opening = OpeningBalances::load(dirs.data(), config)?
+                        .get(&file)
+                        .cloned()
+                        .unwrap_or_default();
+                    passed &= opening + stats.lifetime_total().balance() > threshold;
+                }
+                if under_budget {
+                    passed &= alerts::over_limits(&stats, config).is_empty();
+                }
+
+                if !passed {
+                    return Err(Error::CheckFailed);
+                }
+            }
+            Subcommand::Outliers { ref factor, all } => {
+                let current = BookkeepingFile::current_file();
+                let mut outliers = find_outliers(list_stored_files(dirs.data())?, factor, config)?;
+                // Past months are still read, for what's typical
+                if !all {
+                    outliers.retain(|outlier| outlier.file.as_str() == current.as_str());
+                }
+
+                print_outliers(&outliers, config);
+            }
+            Subcommand::Trend {
+                ref category,
+                months,
+            } => {
+                let current = BookkeepingFile::current_file();
+                let trend = match category {
+                    Some(category) => {
+                        let mut files = list_stored_files(dirs.data())?;
+                        files.retain(|stored| stored.file.ordinal() < current.ordinal());
+                        let files = files.split_off(files.len().saturating_sub(months));
+                        Trend::from_files(files, Some(category), config)?
+                    }
+                    // The whole spending is cached, so there's nothing to read
+                    None => {
+                        let mut totals = book.month_totals()?;
+                        totals.retain(|month| month.file.ordinal() < current.ordinal());
+                        Trend::from_totals(totals.split_off(totals.len().saturating_sub(months)))
+                    }
+                };
+
+                trend.print(config);
+            }
+            Subcommand::Top {
+                count, year, all, ..
+            } => {
+                let current = BookkeepingFile::current_file();
+                let files = if all {
+                    list_stored_files(dirs.data())?
+                } else if year {
+                    let mut files = list_stored_files(dirs.data())?;
+                    files.retain(|stored| stored.file.year() == current.year());
+                    files
+                } else {
+                    vec![StoredFile::in_data(dirs.data(), current)]
+                };
+
+                Top::from_files(files, config)?.print(count, config);
+            }
+            Subcommand::Chart {
+                ref month,
+                ref output,
+            } => {
+                let stored = find_month(dirs.data(), month.as_deref())?;
+
+                if let Some(path) = output {
+                    let month = Stats::from_files(vec![stored], config)?;
+                    chart::draw(path, &month, &book.month_totals()?, config)?;
+                    println!("Drew {}", path.display());
+                } else {
+                    DailySpending::compute(&stored, config)?.print(&stored, config);
+                }
+            }
+            Subcommand::Heatmap { ref month } => {
+                let stored = find_month(dirs.data(), month.as_deref())?;
+                DailySpending::compute(&stored, config)?.print_heatmap(&stored, config);
+            }
+            Subcommand::Latte {
+                ref habit,
+                ref horizons,
+                ref invested_at,
+            } => {
+                let invested_at = invested_at
+                    .as_deref()
+                    .map(|rate| parse_signed_amount(rate, config))
+                    .transpose()?;
+                let current = BookkeepingFile::current_file();
+                let latte = Latte::compute(list_stored_files(dirs.data())?, habit, config)?;
+
+                latte.print(habit, &current, horizons, invested_at.as_ref(), config);
+            }
+            Subcommand::Archive { year } => {
+                if year >= today().year() {
+                    return Err(Error::YearNotOver(year));
+                }
+
+                let archive = archive_year_files(dirs.data(), year, config.dry_run)?;
+                if !config.dry_run {
+                    info!("Archived {} into {}", year, archive.display());
+                }
+            }
+            Subcommand::Export {
+                format: Format::Ical,
+                ref output,
+            } => {
+                let current = BookkeepingFile::current_file();
+                let bills = Bills::load(dirs.data(), config)?;
+                let names: Vec<_> = bills
+                    .list
+                    .iter()
+                    .map(|bill| config.matching.normalize(&bill.name))
+                    .collect();
+
+                let mut events: Vec<_> = bills
+                    .list
+                    .iter()
+                    .map(|bill| Event::from_bill(bill, config))
+                    .collect();
+                for operation in recurring(dirs.data(), &current.previous(), config)? {
+                    // Paying a bill every month makes it recurring, but it's already there
+                    let description = config.matching.normalize(&operation.description);
+                    if !names.iter().any(|name| description.contains(name)) {
+                        events.push(Event::from_operation(&operation, config));
+                    }
+                }
+
+                let stamp = Utc::now();
+                match output {
+                    Some(path) => {
+                        write_calendar(&events, &current, stamp, fs::File::create(path)?)?
+                    }
+                    None => write_calendar(&events, &current, stamp, io::stdout().lock())?,
+                }
+            }
+            Subcommand::Export {
+                format: Format::Xlsx,
+                ref output,
+            } => {
+                let records = collect_records(&list_stored_files(dirs.data())?, config)?;
+
+                match output {
+                    Some(path) => write_workbook(&records, config, fs::File::create(path)?)?,
+                    None => write_workbook(&records, config, io::stdout().lock())?,
+                }
+            }
+            Subcommand::Export { format, ref output } => {
+                let records = collect_records(&list_stored_files(dirs.data())?, config)?;
+
+                match output {
+                    Some(path) => write_records(&records, format, fs::File::create(path)?)?,
+                    None => write_records(&records, format, io::stdout().lock())?,
+                }
+            }
+            Subcommand::Import {
+                format,
+                ref path,
+                ref profile,
+            } => {
+                let records = match profile {
+                    Some(name) if format == Format::Csv => {
+                        let profile = config.profile(name).ok_or_else(|| {
+                            Error::Interchange(format!(
+                                "there's no [profiles.{name}] in config.toml, nor a preset named so ({})",
+                                PRESETS.join(", ")
+                            ))
+                        })?;
+                        let transactions = profile.read(fs::File::open(path)?, config)?;
+                        tag_records(CategoryMap::new(&[])?.to_records(&transactions), config)
+                    }
+                    Some(_) => {
+                        return Err(Error::Interchange(
+                            "profiles only describe CSV files".to_owned(),
+                        ))
+                    }
+                    None => read_records(format, fs::File::open(path)?)?,
+                };
+                let months = import_records(dirs.data(), &records, config)?;
+                info!(
+                    "Imported {} operations into {} month(s)",
+                    records.len(),
+                    months
+                );
+            }
+            Subcommand::ImportFrom {
+                app,
+                ref path,
+                ref mappings,
+                write,
+            } => {
+                let transactions = app.read(fs::File::open(path)?, config)?;
+                let map = CategoryMap::new(mappings)?;
+                map.print_review(&transactions);
+
+                if write {
+                    let records = tag_records(map.to_records(&transactions), config);
+                    let months = import_records(dirs.data(), &records, config)?;
+                    info!("Imported into {} month(s)", months);
+                } else {
+                    println!("Nothing was written yet, adjust categories with --map and run again with --write");
+                }
+            }
+            Subcommand::Qr { ref month } => {
+                let stored = find_month(dirs.data(), month.as_deref())?;
+                println!("{}", render_month(stored, config)?);
+            }
+            Subcommand::Log => git.as_ref().ok_or(Error::GitDisabled)?.log()?,
+            Subcommand::Revert { ref commit } => {
+                let git = git.as_ref().ok_or(Error::GitDisabled)?;
+                if config.dry_run {
+                    git.preview_revert(commit)?;
+                } else {
+                    git.revert(commit)?;
+                }
+            }
+            Subcommand::Encrypt => {
+                if config.cipher.is_none() {
+                    return Err(Error::Encryption(
+                        "set `encryption` in config.toml first".into(),
+                    ));
+                }
+
+                let mut encrypted = 0;
+                for file in list_bookkeeping_files(dirs.data())? {
+                    if Writer::encrypt_file(&dirs.data().join(file.as_path()), config)? {
+                        encrypted += 1;
+                    }
+                }
+
+                for name in sidecar::FILES {
+                    let path = dirs.data().join(name);
+                    if path.exists() {
+                        Writer::encrypt_file(&path, config)?;
+                    }
+                }
+                // The index has every month's totals, and encrypted books go without one
+                let index = dirs.data().join(INDEX_FILE);
+                if index.exists() && !config.dry_run {
+                    fs::remove_file(index)?;
+                }
+
+                info!("Encrypted {} month(s)", encrypted);
+                if dirs.data().join("backups").exists() {
+                    println!(
+                        "Backups made before that are still in plaintext, in the backups folder"
+                    );
+                }
+            }
+            Subcommand::Checklist {
+                ref action,
+                ref month,
+            } => {
+                let month = parse_month(month.as_deref())?;
+                let steps = &config.checklist;
+                let mut progress = Progress::load(dirs.data(), config)?;
+
+                match *action {
+                    None => print_checklist(steps, &progress, &month),
+                    Some(ChecklistAction::Run) => {
+                        let input = io::stdin().lock();
+                        run_checklist(steps, &mut progress, &month, input, &options, config)?
+                    }
+                    Some(ChecklistAction::Done { step } | ChecklistAction::Undo { step }) => {
+                        let done = matches!(action, Some(ChecklistAction::Done { .. }));
+                        let step = step
+                            .checked_sub(1)
+                            .and_then(|idx| steps.get(idx))
+                            .ok_or(Error::NoSuchStep(step))?;
+
+                        progress.set_done(&month, step, done);
+                        if !config.dry_run {
+                            progress.save(config)?;
+                        }
+                        print_checklist(steps, &progress, &month);
+                    }
+                }
+            }
+            Subcommand::Bill { ref action } => {
+                let mut bills = Bills::load(dirs.data(), config)?;
+
+                match action {
+                    BillAction::Add { name, amount, due } => {
+                        if !(1..=31).contains(due) {
+                            return Err(Error::Bills(format!("{} is not a day of the month", due)));
+                        }
+                        bills.list.push(Bill {
+                            name: name.clone(),
+                            amount: parse_amount(amount, config)?,
+                            due: *due,
+                        });
+                    }
+                    BillAction::Remove { name } => {
+                        let wanted = config.matching.normalize(name);
+                        let before = bills.list.len();
+                        bills
+                            .list
+                            .retain(|bill| config.matching.normalize(&bill.name) != wanted);
+                        if bills.list.len() == before {
+                            return Err(Error::Bills(format!("there's no bill called {:?}", name)));
+                        }
+                    }
+                }
+
+                if !config.dry_run {
+                    bills.save(config)?;
+                }
+                if log::verbosity() > Verbosity::Quiet {
+                    bills.print(bk_path, day, config)?;
+                }
+            }
+            Subcommand::Bills => Bills::load(dirs.data(), config)?.print(bk_path, day, config)?,
+            Subcommand::Card { ref action } => match action {
+                None => {
+                    let current = BookkeepingFile::current_file();
+                    for (name, card) in &config.cards {
+                        let closing = card.statement_of(&current, day);
+                        let dir = card_dir(dirs.data(), name);
+                        Statement::read(&dir, card, closing, config)?.print(name, config);
+                    }
+                }
+                Some(
+                    CardAction::Take {
+                        card: name,
+                        amount,
+                        description,
+                        currency,
+                    }
+                    | CardAction::Put {
+                        card: name,
+                        amount,
+                        description,
+                        currency,
+                    },
+                ) => {
+                    config.card(name)?;
+                    let typ = match action {
+                        Some(CardAction::Take { .. }) => EntryType::Debit,
+                        _ => EntryType::Credit,
+                    };
+                    let amount = parse_amount(amount, config)?;
+                    let tagged = auto_tag(config, description);
+                    let entry = Entry {
+                        currency: parse_currency(currency.as_deref())?,
+                        ..Entry::new(day, typ, amount, tagged.as_deref().unwrap_or(description))
+                    };
+
+                    let dir = card_dir(dirs.data(), name);
+                    if !config.dry_run {
+                        fs::create_dir_all(&dir)?;
+                    }
+                    let month = BookkeepingFile::current_file();
+                    let path = dir.join(month.as_path());
+                    let record = Record::from_entry(month.as_str(), &entry);
+                    Writer::write_entry(&path, entry, config)?;
+                    print_updated(&path, config);
+                    hooks::recorded(&[record], config);
+                }
+                Some(CardAction::Close { card: name, month }) => {
+                    let card = config.card(name)?;
+                    let closing = parse_month(month.as_deref())?;
+                    let statement =
+                        Statement::read(&card_dir(dirs.data(), name), card, closing, config)?;
+                    statement.print(name, config);
+
+                    let due = statement.close(dirs.data(), name, card, config)?;
+                    print_updated(&dirs.data().join(due.as_path()), config);
+                }
+            },
+            Subcommand::Split {
+                ref amount,
+                ref description,
+                ref with,
+                ref date,
+                day: entry_day,
+            } => {
+                let (file, day) = entry_date(date.as_deref(), entry_day)?;
+                let path = book.location(&file);
+                let amount = parse_amount(amount, config)?;
+                let with = people(with, &config.matching)?;
+                let (mine, theirs) = split_evenly(&amount, with.len());
+                let tagged = auto_tag(config, description);
+                let entry = Entry::new(
+                    day,
+                    EntryType::Debit,
+                    mine,
+                    tagged.as_deref().unwrap_or(description),
+                );
+                confirm_entry(book, &file, &entry, diagnostics, yes)?;
+
+                book.record(&file, entry)?;
+                print_updated(&path, config);
+
+                let mut debts = Debts::load(dirs.data(), config)?;
+                for person in with {
+                    info!("{} owes you {}", person, config.format_amount(&theirs));
+                    debts.list.push(Movement {
+                        person,
+                        amount: theirs.clone(),
+                        description: description.clone(),
+                        month: file.as_str().to_owned(),
+                    });
+                }
+                if !config.dry_run {
+                    debts.save(config)?;
+                }
             }
-            Subcommand::Status => {
-                let total = Reader::new().total_from_file(bk_path)?;
-                // Safeyu: Always has file name because it's in format "MM-YYYY"
-                println!("Status for {:?}", bk_path.file_name().unwrap());
-                println!("\tIncoming: R$ {}", total.incoming);
-                println!("\tOutgoing: R$ {}", total.outgoing);
+            Subcommand::Settle { person: None, .. } => {
+                Debts::load(dirs.data(), config)?.print(config)
             }
+            Subcommand::Settle {
+                person: Some(ref person),
+                ref amount,
+            } => {
+                let mut debts = Debts::load(dirs.data(), config)?;
+                let (name, owed) = debts.owed_by(person, &config.matching)?;
+                let paid = match amount {
+                    Some(amount) => parse_amount(amount, config)?,
+                    None => owed.clone(),
+                };
+                if paid > owed {
+                    return Err(Error::Debts(format!(
+                        "{} only owes you {}",
+                        name,
+                        config.format_amount(&owed)
+                    )));
+                }
+                debts.list.push(Movement {
+                    person: name.clone(),
+                    amount: -paid.clone(),
+                    description: "Repayment".to_owned(),
+                    month: BookkeepingFile::current_file().as_str().to_owned(),
+                });
+                if !config.dry_run {
+                    debts.save(config)?;
+                }
+
+                let left = &owed - &paid;
+                if left > BigDecimal::zero() {
+                    info!(
+                        "{} paid back {}, and still owes you {}",
+                        name,
+                        config.format_amount(&paid),
+                        config.format_amount(&left)
+                    );
+                } else {
+                    info!(
+                        "{} paid back {}, and is settled",
+                        name,
+                        config.format_amount(&paid)
+                    );
+                }
+            }
+            Subcommand::Goal { ref action } => {
+                let mut goals = Goals::load(dirs.data(), config)?;
+
+                match action {
+                    None => {}
+                    Some(GoalAction::Add { name, target, by }) => {
+                        // Validated here, as goals keep it as written
+                        parse_month(Some(by))?;
+                        goals.list.push(Goal {
+                            name: name.clone(),
+                            target: parse_amount(target, config)?,
+                            by: by.clone(),
+                            saved: BigDecimal::zero(),
+                        });
+                    }
+                    Some(GoalAction::Put { name, amount }) => {
+                        let amount = parse_amount(amount, config)?;
+                        goals.find(name, &config.matching)?.saved += amount;
+                    }
+                }
+
+                if action.is_some() && !config.dry_run {
+                    goals.save(config)?;
+                }
+                // Shown after a change only as a confirmation, which --quiet leaves out
+                if action.is_none() || log::verbosity() > Verbosity::Quiet {
+                    goals.print(&BookkeepingFile::current_file(), config);
+                }
+            }
+            Subcommand::Profile { ref action } => match action {
+                None | Some(ProfileAction::List) => {
+                    let current = profile.as_deref().unwrap_or("default");
+                    let names = dirs.profiles()?;
+                    for name in ["default"]
+                        .into_iter()
+                        .chain(names.iter().map(String::as_str))
+                    {
+                        // Each in its own currency and format, the open one already unlocked
+                        let balance = if name == current {
+                            Some(config.format_amount(&book.lifetime_total()?.balance()))
+                        } else {
+                            profile_balance(Some(name).filter(|name| *name != "default"))?
+                        };
+                        let mark = if name == current { "*" } else { " " };
+                        match balance {
+                            Some(balance) => println!("{} {}\t{}", mark, name, balance),
+                            None => println!("{} {}", mark, name),
+                        }
+                    }
+                }
+                Some(ProfileAction::Create { name }) if config.dry_run => {
+                    println!("Would create the profile {}", name);
+                }
+                Some(ProfileAction::Create { name }) => {
+                    let data = dirs.create_profile(name, &dirs.config().join("config.toml"))?;
+                    info!(
+                        "Created the profile {}, kept in {}, use it with `porquinho --profile {} ...`",
+                        name,
+                        data.display(),
+                        name
+                    );
+                }
+            },
+            Subcommand::Close { ref month } => {
+                let file = parse_month(month.as_deref())?;
+                let path = month_path(dirs.data(), month.as_deref())?;
+
+                if let Some(earned) = balances::interest(dirs.data(), &file, config)? {
+                    let entry = Entry::new(
+                        last_day(&file),
+                        EntryType::Credit,
+                        earned,
+                        balances::INTEREST,
+                    );
+                    book.record(&file, entry)?;
+                    print_updated(&path, config);
+                }
+                let closing = balances::close(dirs.data(), &file, config)?;
+                info!(
+                    "{}",
+                    tf(
+                        "{} ended with {}, carried into {}",
+                        &[
+                            &file.month_name(),
+                            &config.format_amount(&closing.closing),
+                            &file.next().as_str()
+                        ]
+                    )
+                );
+                if let (Some(hook), false) = (&config.hooks.on_close_month, config.dry_run) {
+                    warn_on_failure(run_hook("on_close_month", hook, &closing));
+                }
+            }
+            Subcommand::Opening {
+                ref amount,
+                ref month,
+            } => {
+                let file = parse_month(month.as_deref())?;
+                // Overdrawn accounts start out negative
+                let amount = match amount.strip_prefix('-') {
+                    Some(overdrawn) => -parse_signed_amount(overdrawn, config)?,
+                    None => parse_signed_amount(amount, config)?,
+                };
+                info!(
+                    "{}",
+                    tf(
+                        "{} started with {}",
+                        &[&file.month_name(), &config.format_amount(&amount)]
+                    )
+                );
+
+                let mut balances = OpeningBalances::load(dirs.data(), config)?;
+                balances.set(&file, amount);
+                if !config.dry_run {
+                    balances.save(config)?;
+                }
+            }
+            Subcommand::Open { ref month } => {
+                let path = month_path(dirs.data(), month.as_deref())?;
+                edit_in_editor(&path, config, io::stdin().lock())?;
+            }
+            Subcommand::Serve { port } => serve(book, git.as_ref(), port)?,
+            Subcommand::Categorize { all, apply } => {
+                let files = if all {
+                    list_bookkeeping_files(dirs.data())?
+                } else {
+                    vec![BookkeepingFile::current_file()]
+                };
+                let rules = RuleSet::from_config(config);
+
+                let (mut tagged, mut uncategorized) = (0, 0);
+                for file in &files {
+                    let found = categorize_file(dirs.data(), file, &rules, config, apply)?;
+                    tagged += found.tagged;
+                    uncategorized += found.uncategorized;
+                }
+
+                if apply {
+                    info!("Tagged {} operation(s)", tagged);
+                    info!("{} operation(s) match no rule", uncategorized);
+                } else {
+                    println!(
+                        "{} operation(s) would be tagged, run again with --apply",
+                        tagged
+                    );
+                    println!("{} operation(s) match no rule", uncategorized);
+                }
+            }
+            Subcommand::Rename { ref from, ref to } => {
+                let mut renamed = 0;
+                for file in list_bookkeeping_files(dirs.data())? {
+                    renamed += rename_in_file(dirs.data(), &file, from, to, config)?;
+                }
+
+                if renamed == 0 {
+                    println!("No operation is described as {:?}", from);
+                } else if config.dry_run {
+                    println!("Would rename {} operation(s)", renamed);
+                } else {
+                    info!("Renamed {} operation(s)", renamed);
+                }
+            }
+            Subcommand::Dedupe { ref month } => {
+                let path = month_path(dirs.data(), month.as_deref())?;
+                let removed = dedupe(&path, config, io::stdin().lock())?;
+                info!("Removed {} duplicate(s)", removed);
+            }
+            Subcommand::Doctor { fix } => report(&examine(dirs.data(), config, fix)?)?,
+            Subcommand::Selftest => selftest(dirs.data(), config)?,
+            Subcommand::Explain { ref code } => match code {
+                Some(code) => {
+                    let code: Code = code.parse()?;
+                    print_explanation(code);
+                }
+                None => {
+                    for code in Code::all() {
+                        print_explanation(code);
+                    }
+                }
+            },
+            Subcommand::Examples => println!("{}", WORKFLOWS),
         };
 
+        if let (Some(git), Some(message), false) = (git, commit_message, config.dry_run) {
+            git.commit(&message)?;
+        }
+
+        // Unasked for, so left out by --quiet, and by commands that don't look at the book
+        if reads_book && log::verbosity() > Verbosity::Quiet && config.storage == Backend::Files {
+            let current = BookkeepingFile::current_file();
+            if let Some(message) = low_balance(book.dir(), current, day, config)? {
+                diagnostics.warn(Code::LowBalance, message)?;
+            }
+        }
+
         Ok(())
     }
 }
 
+/// The balance of every month of `profile`, formatted as its own config.toml says, or `None`
+/// if it's encrypted, as listing profiles shouldn't ask for every passphrase
+fn profile_balance(profile: Option<&str>) -> Result<Option<String>> {
+    let dirs = Dirs::init(profile)?;
+    let config = Config::load(&dirs.config().join("config.toml"))?;
+    if config.encryption.is_some() {
+        return Ok(None);
+    }
+
+    let book = Bookkeeper::open(dirs.data(), config)?;
+    let balance = book.lifetime_total()?.balance();
+
+    Ok(Some(book.config().format_amount(&balance)))
+}
+
+/// Parses an amount or arithmetic expression given in the command line, following the
+/// configured locale
+///
+/// Expressions such as `5-10` are refused when they come out as zero or less, as a take of
+/// `-5` would be recorded as income.
+fn parse_amount(amount: &str, config: &Config) -> Result<BigDecimal> {
+    let amount = parse_signed_amount(amount, config)?;
+    if amount <= BigDecimal::zero() {
+        return Err(Error::NegativeAmount(amount));
+    }
+
+    Ok(amount)
+}
+
+/// Like `parse_amount`, for thresholds and rates that may be zero or negative
+fn parse_signed_amount(amount: &str, config: &Config) -> Result<BigDecimal> {
+    amount::parse_amount(amount, config.locale)
+        .ok_or_else(|| Error::InvalidAmount(amount.to_owned()))
+}
+
+/// `records` read from a bank or another app, tagged by the config's rules
+fn tag_records(mut records: Vec<Record>, config: &Config) -> Vec<Record> {
+    for record in &mut records {
+        if let Some(description) = auto_tag(config, &record.description) {
+            record.description = description;
+        }
+    }
+
+    records
+}
+
+/// Points out the bills a take described as `description` paid
+fn print_paid_bills(
+    data: &Path,
+    path: &Path,
+    day: u8,
+    description: &str,
+    config: &Config,
+) -> Result<()> {
+    let bills = Bills::load(data, config)?;
+    let description = config.matching.normalize(description);
+
+    for (bill, state) in bills.states(path, day, config)? {
+        if state == BillState::Paid && description.contains(&config.matching.normalize(&bill.name))
+        {
+            info!("Paid the {} bill", bill.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Tells which file a command changed
+fn print_updated(path: &Path, config: &Config) {
+    if !config.dry_run {
+        info!("{}", tf("Updated {}", &[&path.display()]));
+    }
+}
+
+/// Asks before recording an entry whose amount is above `confirm_above`, or which is
+/// already in `file`, unless `yes` is set
+fn confirm_entry(
+    book: &Bookkeeper,
+    file: &BookkeepingFile,
+    entry: &Entry,
+    diagnostics: &Diagnostics,
+    yes: bool,
+) -> Result<()> {
+    let config = book.config();
+    let amount = config.format_amount_in(&entry.amount, entry.currency.unwrap_or(&config.currency));
+    let mut stdin = io::stdin().lock();
+
+    if let Some(threshold) = &config.confirm_above {
+        let converted = Converter::new(config).convert(entry.amount.clone(), entry.currency)?;
+        if converted > *threshold
+            && !yes
+            && !ask(&format!("Really record {}?", amount), &mut stdin)?
+        {
+            return Err(Error::NotConfirmed(amount));
+        }
+    }
+
+    let operations = book.operations(file)?;
+    let description = config.matching.normalize(&untagged(entry.description));
+    // Descriptions already used this month are never suggested another
+    let needs_suggestion = may_be_misspelled(entry.description, config.matching)
+        && !diagnostics.is_allowed(Code::Misspelled)
+        && !operations.iter().any(|operation| {
+            config.matching.normalize(&untagged(&operation.description)) == description
+        });
+
+    if let Some(duplicate) = duplicate_of(operations, entry) {
+        let message = format!("the same entry was already recorded, as #{}", duplicate.id);
+        if diagnostics.warn(Code::Duplicate, message)?
+            && !yes
+            && !ask("Record it again?", &mut stdin)?
+        {
+            return Err(Error::NotConfirmed(format!(
+                "{} {}",
+                amount, entry.description
+            )));
+        }
+    }
+
+    if !needs_suggestion {
+        return Ok(());
+    }
+
+    let known = known_descriptions(book.dir(), config)?;
+    if let Some((suggestion, count)) = suggest(entry.description, &known, config.matching) {
+        let message = format!(
+            "{:?} looks like {:?}, used {} time(s) before, see `porquinho rename`",
+            entry.description, suggestion, count
+        );
+        diagnostics.warn(Code::Misspelled, message)?;
+    }
+
+    Ok(())
+}
+
+/// Replaces a description given as `@` with one picked from the ones used before
+fn pick_in_command(cmd: &mut Subcommand, data: &Path, config: &Config) -> Result<()> {
+    let (description, dir) = match cmd {
+        Subcommand::Take { description, .. }
+        | Subcommand::Put { description, .. }
+        | Subcommand::Refund { description, .. } => (description, data.to_owned()),
+        Subcommand::Card {
+            action:
+                Some(
+                    CardAction::Take {
+                        card, description, ..
+                    }
+                    | CardAction::Put {
+                        card, description, ..
+                    },
+                ),
+        } => (description, card_dir(data, card)),
+        _ => return Ok(()),
+    };
+
+    if description == PICK {
+        *description = pick_description(&dir, config)?;
+    }
+
+    Ok(())
+}
+
+/// Adds the person given with `--by` to the description of the entry being recorded
+fn attribute_in_command(cmd: &mut Subcommand) {
+    if let Subcommand::Take {
+        description,
+        by: Some(person),
+        ..
+    }
+    | Subcommand::Put {
+        description,
+        by: Some(person),
+        ..
+    }
+    | Subcommand::Refund {
+        description,
+        by: Some(person),
+        ..
+    } = cmd
+    {
+        *description = attributed(description, person);
+    }
+}
+
+/// Asks a yes or no `question`, defaulting to no
+fn ask(question: &str, input: &mut impl BufRead) -> Result<bool> {
+    print!("{} {} ", question, t("[y/N]"));
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+
+    Ok(is_yes(&answer))
+}
+
+/// Applies an amount given to `edit`: `+5` and `-3` adjust `current`, anything else replaces it
+fn adjust_amount(current: &BigDecimal, change: &str, config: &Config) -> Result<BigDecimal> {
+    let adjusted = if let Some(increase) = change.strip_prefix('+') {
+        current + parse_amount(increase, config)?
+    } else if let Some(decrease) = change.strip_prefix('-') {
+        current - parse_amount(decrease, config)?
+    } else {
+        parse_amount(change, config)?
+    };
+
+    if adjusted < BigDecimal::zero() {
+        return Err(Error::NegativeAmount(adjusted));
+    }
+
+    Ok(adjusted)
+}
+
+/// Parses a month given in the command line (`MM-YYYY`), or the current one if `None`
+fn parse_month(month: Option<&str>) -> Result<BookkeepingFile> {
+    match month {
+        Some(month) => BookkeepingFile::parse(month, &BookkeepingFile::current_file())
+            .ok_or_else(|| Error::InvalidMonth(month.to_owned())),
+        None => Ok(BookkeepingFile::current_file()),
+    }
+}
+
+/// The path of the bookkeeping file for `month` in `dir`, which must exist and not be archived
+fn month_path(dir: &Path, month: Option<&str>) -> Result<PathBuf> {
+    let file = parse_month(month)?;
+    let path = dir.join(file.as_path());
+
+    if path.exists() {
+        Ok(path)
+    } else {
+        Err(Error::NoSuchMonth(file.as_str().to_owned()))
+    }
+}
+
+/// The bookkeeping file and day an entry made on `date`, or on `day` of the current month,
+/// goes into, today if neither is given
+fn entry_date(date: Option<&str>, day: Option<u8>) -> Result<(BookkeepingFile, u8)> {
+    let today = today();
+
+    match (date, day) {
+        (Some(date), _) => parse_date(date, today)
+            .map(file_and_day)
+            .ok_or_else(|| Error::InvalidDate(date.to_owned())),
+        // Days of the current budget month up to today, which may have started last month
+        (None, Some(day))
+            if (1..=31).contains(&day) && {
+                let current = BookkeepingFile::current_file();
+                day <= current.days()
+                    && cycle_day(&current, day) <= cycle_day(&current, today.day() as u8)
+            } =>
+        {
+            Ok((BookkeepingFile::current_file(), day))
+        }
+        (None, Some(day)) => Err(Error::DayNotInMonth(day)),
+        (None, None) => Ok(file_and_day(today)),
+    }
+}
+
+/// Finds the month named `month` (`MM-YYYY`), or the current one if `None`,
+/// whether it's archived or not
+fn find_month(dir: &Path, month: Option<&str>) -> Result<StoredFile> {
+    let file = parse_month(month)?;
+
+    list_stored_files(dir)?
+        .into_iter()
+        .find(|stored| stored.file.as_str() == file.as_str())
+        .ok_or_else(|| Error::NoSuchMonth(file.as_str().to_owned()))
+}
+
+/// Prints a code as listed by `explain`
+fn print_explanation(code: Code) {
+    println!(
+        "{} ({}): {}",
+        code.as_str(),
+        code.severity().as_str(),
+        code.explanation()
+    );
+}
+
+/// Validates a currency code given in the command line
+fn parse_currency(currency: Option<&str>) -> Result<Option<&str>> {
+    match currency {
+        Some(code) if !is_currency_code(code) => Err(Error::InvalidCurrency(code.to_owned())),
+        currency => Ok(currency),
+    }
+}
+
 fn exec() -> Result<()> {
     GlobalState::new()?.run_command()
 }
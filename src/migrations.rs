@@ -0,0 +1,185 @@
+use std::path::Path;
+
+use fs_err as fs;
+
+use crate::{
+    archive::archive_year,
+    config::Config,
+    crypt,
+    file::{list_bookkeeping_files, BookkeepingFile},
+    writer::Writer,
+    Error, Result,
+};
+
+/// Version of the data folder's layout and of the bookkeeping files' format
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Holds the `FORMAT_VERSION` the data folder is in
+pub const VERSION_FILE: &str = ".porquinho-format";
+
+/// Upgrades a data folder by one version
+type Migration = fn(&Path, &Config) -> Result<()>;
+
+/// `MIGRATIONS[n]` upgrades a data folder from version `n` to `n + 1`
+const MIGRATIONS: [Migration; FORMAT_VERSION as usize] = [end_files_with_line_breaks];
+
+/// Brings the data folder in `dir` up to `FORMAT_VERSION`, backing it up first into
+/// `backups/format-{version}`
+///
+/// Returns the version it was in, if it was upgraded.
+pub fn migrate(dir: &Path, config: &Config) -> Result<Option<u32>> {
+    migrate_with(dir, config, &MIGRATIONS)
+}
+
+/// Runs the `migrations` the data folder needs, returning the version it was in if any ran
+fn migrate_with(dir: &Path, config: &Config, migrations: &[Migration]) -> Result<Option<u32>> {
+    let latest = migrations.len() as u32;
+    let version_path = dir.join(VERSION_FILE);
+
+    let current = if version_path.exists() {
+        let version = fs::read_to_string(&version_path)?;
+        version
+            .trim()
+            .parse()
+            .map_err(|_| Error::UnknownFormat(version.trim().to_owned()))?
+    } else if has_no_book(dir)? {
+        // A brand new data folder, already in the latest format
+        latest
+    } else {
+        // Folders from before versioning existed
+        0
+    };
+
+    if current > latest {
+        return Err(Error::UnknownFormat(current.to_string()));
+    }
+
+    let needs_migrating = current < latest;
+    if config.dry_run {
+        // Commands still work on old folders, as long as they don't write
+        if needs_migrating {
+            println!(
+                "Would upgrade the data folder from format {} to {}",
+                current, latest
+            );
+        }
+        return Ok(None);
+    }
+
+    if needs_migrating {
+        back_up_folder(dir, current)?;
+
+        for migration in &migrations[current as usize..] {
+            migration(dir, config)?;
+        }
+    }
+
+    if needs_migrating || !version_path.exists() {
+        fs::write(&version_path, format!("{}\n", latest))?;
+    }
+
+    Ok(needs_migrating.then_some(current))
+}
+
+/// Whether `dir` has neither bookkeeping files nor archives
+fn has_no_book(dir: &Path) -> Result<bool> {
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        let name = name.to_string_lossy();
+
+        if BookkeepingFile::from_file_name(&name).is_some() || archive_year(&name).is_some() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Copies every file of `dir` into `backups/format-{version}`
+fn back_up_folder(dir: &Path, version: u32) -> Result<()> {
+    let backup = dir.join("backups").join(format!("format-{}", version));
+    fs::create_dir_all(&backup)?;
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            fs::copy(entry.path(), backup.join(entry.file_name()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Version 1: every bookkeeping file ends with a line break, so appending to
+/// a file edited by hand doesn't glue the new entry to its last line
+fn end_files_with_line_breaks(dir: &Path, config: &Config) -> Result<()> {
+    for file in list_bookkeeping_files(dir)? {
+        let path = dir.join(file.as_path());
+        let mut contents = crypt::read_file(&path, config.cipher.as_ref())?;
+
+        if contents.last().is_some_and(|&byte| byte != b'\n') {
+            contents.push(b'\n');
+            Writer::replace_contents(&path, contents, config)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{migrate_with, Migration, VERSION_FILE};
+    use crate::{config::Config, Result};
+
+    fn shout(dir: &Path, _: &Config) -> Result<()> {
+        let path = dir.join("01-2023");
+        let contents = fs::read_to_string(&path)?;
+        fs::write(path, contents.to_uppercase())?;
+        Ok(())
+    }
+
+    #[test]
+    fn migrates_old_folders_once() {
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        let migrations: [Migration; 2] = [super::end_files_with_line_breaks, shout];
+
+        let fresh = TempDir::new().unwrap();
+        assert_eq!(
+            migrate_with(fresh.path(), &config, &migrations).unwrap(),
+            None
+        );
+        let version = fs::read_to_string(fresh.path().join(VERSION_FILE)).unwrap();
+        assert_eq!(version, "2\n");
+
+        let old = TempDir::new().unwrap();
+        let path = old.path().join("01-2023");
+        fs::write(&path, "1 - 5 coffee").unwrap();
+
+        assert_eq!(
+            migrate_with(old.path(), &config, &migrations).unwrap(),
+            Some(0)
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 - 5 COFFEE\n");
+
+        let backup = old.path().join("backups/format-0/01-2023");
+        assert_eq!(fs::read_to_string(backup).unwrap(), "1 - 5 coffee");
+
+        // Already up to date, so nothing runs again
+        assert_eq!(
+            migrate_with(old.path(), &config, &migrations).unwrap(),
+            None
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 - 5 COFFEE\n");
+
+        fs::write(old.path().join(VERSION_FILE), "3").unwrap();
+        assert!(migrate_with(old.path(), &config, &migrations).is_err());
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::{
+    config::Config,
+    currency::Converter,
+    file::{BookkeepingFile, StoredFile},
+    parser::EntryType,
+    reader::Reader,
+    rules::RuleSet,
+    Error, Result,
+};
+
+/// Groups with fewer takes than this have no typical amount to compare with
+const MIN_TAKES: usize = 3;
+
+/// A take far above the typical amount of the others like it
+pub struct Outlier {
+    pub file: BookkeepingFile,
+    /// 1-based line number of the entry
+    pub id: usize,
+    pub day: u8,
+    /// In the default currency
+    pub amount: BigDecimal,
+    pub description: String,
+    /// The category of the take, or its description if it has none
+    pub group: String,
+    /// The median amount of the takes in `group`
+    pub median: BigDecimal,
+}
+
+impl Outlier {
+    /// How many times the median the take was
+    pub fn ratio(&self) -> BigDecimal {
+        (&self.amount / &self.median).with_scale(1)
+    }
+}
+
+/// A take, as read from the book
+struct Take {
+    file: BookkeepingFile,
+    id: usize,
+    day: u8,
+    amount: BigDecimal,
+    description: String,
+}
+
+/// Every take in `files` that's at least `factor` times the median of its category, or of the
+/// takes with the same description if it has no category, in chronological order
+///
+/// Fails if `factor` isn't above zero, as every take would stand out.
+pub fn find_outliers(
+    files: Vec<StoredFile>,
+    factor: &BigDecimal,
+    config: &Config,
+) -> Result<Vec<Outlier>> {
+    if *factor <= BigDecimal::zero() {
+        return Err(Error::NegativeAmount(factor.clone()));
+    }
+
+    let rules = RuleSet::from_config(config);
+    let converter = Converter::new(config);
+    let mut reader = Reader::new(config);
+    // The takes of each group, by the normalized name of the group
+    let mut groups: HashMap<String, (String, Vec<Take>)> = HashMap::new();
+
+    for stored in files {
+        let mut id = 0;
+        reader.for_each_entry_in(stored.open()?, |entry| {
+            id += 1;
+            if entry.typ != EntryType::Debit {
+                return Ok(());
+            }
+
+            let group = rules
+                .categorize(entry.description)
+                .unwrap_or(entry.description);
+            groups
+                .entry(config.matching.normalize(group))
+                .or_insert_with(|| (group.to_owned(), Vec::new()))
+                .1
+                .push(Take {
+                    file: stored.file.clone(),
+                    id,
+                    day: entry.day,
+                    amount: converter.convert(entry.amount, entry.currency)?,
+                    description: entry.description.to_owned(),
+                });
+
+            Ok(())
+        })?;
+    }
+
+    let mut outliers = Vec::new();
+    for (group, takes) in groups.into_values() {
+        if takes.len() < MIN_TAKES {
+            continue;
+        }
+
+        let median = median(takes.iter().map(|take| take.amount.clone()).collect());
+        if median <= BigDecimal::zero() {
+            continue;
+        }
+
+        let threshold = &median * factor;
+        outliers.extend(
+            takes
+                .into_iter()
+                .filter(|take| take.amount >= threshold)
+                .map(|take| Outlier {
+                    file: take.file,
+                    id: take.id,
+                    day: take.day,
+                    amount: take.amount,
+                    description: take.description,
+                    group: group.clone(),
+                    median: median.clone(),
+                }),
+        );
+    }
+
+    outliers.sort_by_key(|outlier| (outlier.file.ordinal(), outlier.day, outlier.id));
+    Ok(outliers)
+}
+
+/// The middle value of `amounts`, which mustn't be empty
+fn median(mut amounts: Vec<BigDecimal>) -> BigDecimal {
+    amounts.sort();
+    let middle = amounts.len() / 2;
+
+    if amounts.len().is_multiple_of(2) {
+        ((&amounts[middle - 1] + &amounts[middle]) / BigDecimal::from(2)).with_scale(2)
+    } else {
+        amounts.swap_remove(middle)
+    }
+}
+
+pub fn print_outliers(outliers: &[Outlier], config: &Config) {
+    if outliers.is_empty() {
+        println!("No take stands out");
+        return;
+    }
+
+    for outlier in outliers {
+        println!(
+            "{} #{} {:02}: {} {}, {}x the median of {} for {:?}",
+            outlier.file.label(),
+            outlier.id,
+            outlier.day,
+            config.format_amount(&outlier.amount),
+            outlier.description,
+            outlier.ratio(),
+            config.format_amount(&outlier.median),
+            outlier.group
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::find_outliers;
+    use crate::{archive::list_stored_files, config::Config};
+
+    #[test]
+    fn flags_takes_far_above_the_median() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "02 - 100 Market\n09 - 120 market\n16 - 90 Bakery #groceries\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("02-2024"),
+            "03 - 110 Market\n10 - 1100 Market\n12 - 900 Laptop\n",
+        )
+        .unwrap();
+
+        let config: Config = toml::from_str(
+            r#"
+            [[rules]]
+            pattern = "market"
+            category = "groceries"
+            "#,
+        )
+        .unwrap();
+        let files = list_stored_files(dir.path()).unwrap();
+        let factor = BigDecimal::from(3);
+        let outliers = find_outliers(files, &factor, &config).unwrap();
+
+        // The laptop is alone in its group, so it has nothing to stand out from
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers[0].file.as_str(), "02-2024");
+        assert_eq!(outliers[0].id, 2);
+        assert_eq!(outliers[0].group, "groceries");
+        assert_eq!(outliers[0].median, BigDecimal::from(110));
+        assert_eq!(outliers[0].ratio(), BigDecimal::from_str("10.0").unwrap());
+
+        for factor in ["0", "-2"] {
+            let factor = BigDecimal::from_str(factor).unwrap();
+            let files = list_stored_files(dir.path()).unwrap();
+            assert!(find_outliers(files, &factor, &config).is_err());
+        }
+    }
+}
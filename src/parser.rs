@@ -1,14 +1,41 @@
-use std::{ops::Not, str::FromStr};
+use std::ops::Not;
 
 use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
-#[cfg_attr(test, derive(PartialEq))]
+use crate::{currency::is_currency_code, locale::Locale};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EntryType {
     /// Entry is an expenditure
+    #[serde(rename = "take")]
     Debit,
     /// Entry
+    #[serde(rename = "put")]
     Credit,
+    /// Money given back for an expenditure, which offsets it instead of counting as income
+    #[serde(rename = "refund")]
+    Refund,
+}
+
+impl EntryType {
+    /// The character that stands for it in bookkeeping files
+    pub fn sign(self) -> char {
+        match self {
+            Self::Debit => '-',
+            Self::Credit => '+',
+            Self::Refund => '~',
+        }
+    }
+
+    /// How it's named in the command line, exports and plain output
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Debit => "take",
+            Self::Credit => "put",
+            Self::Refund => "refund",
+        }
+    }
 }
 
 pub type ParseResult<T> = std::result::Result<T, ParseError>;
@@ -34,6 +61,8 @@ pub struct Entry<'a> {
     pub day: u8,
     pub typ: EntryType,
     pub amount: BigDecimal,
+    /// ISO 4217 code of the amount's currency, if it's not the book's default one
+    pub currency: Option<&'a str>,
     // TODO: rename to account?
     // TODO: make it optional?
     pub description: &'a str,
@@ -45,16 +74,19 @@ impl<'a> Entry<'a> {
             day,
             typ,
             amount,
+            currency: None,
             description,
         }
     }
 
-    pub fn from_str(input: &'a str) -> ParseResult<Self> {
+    pub fn from_str(input: &'a str, locale: Locale) -> ParseResult<Self> {
         let (day, rest) = parse_day(input)?;
 
         let (typ, rest) = parse_entry_type(rest)?;
 
-        let (amount, rest) = parse_decimal(rest)?;
+        let (currency, rest) = parse_currency(rest);
+
+        let (amount, rest) = parse_decimal(rest, locale)?;
 
         let description = parse_description(rest);
 
@@ -62,11 +94,49 @@ impl<'a> Entry<'a> {
             day,
             typ,
             amount,
+            currency,
             description,
         })
     }
 }
 
+/// An owned `Entry`, along with its position in the bookkeeping file
+#[derive(Debug, Clone, Serialize)]
+pub struct Operation {
+    /// 1-based line number of the entry
+    pub id: usize,
+    pub day: u8,
+    /// Named like in exports
+    #[serde(rename = "kind")]
+    pub typ: EntryType,
+    pub amount: BigDecimal,
+    pub currency: Option<String>,
+    pub description: String,
+}
+
+impl Operation {
+    pub fn from_entry(id: usize, entry: Entry) -> Self {
+        Self {
+            id,
+            day: entry.day,
+            typ: entry.typ,
+            amount: entry.amount,
+            currency: entry.currency.map(ToOwned::to_owned),
+            description: entry.description.to_owned(),
+        }
+    }
+
+    pub fn as_entry(&self) -> Entry<'_> {
+        Entry {
+            day: self.day,
+            typ: self.typ,
+            amount: self.amount.clone(),
+            currency: self.currency.as_deref(),
+            description: &self.description,
+        }
+    }
+}
+
 fn parse_day(input: &str) -> ParseResult<(u8, &str)> {
     let (first, rest) = input
         .trim()
@@ -92,11 +162,22 @@ fn parse_entry_type(input: &str) -> ParseResult<(EntryType, &str)> {
     match first {
         "+" => Ok((EntryType::Credit, rest)),
         "-" => Ok((EntryType::Debit, rest)),
+        "~" => Ok((EntryType::Refund, rest)),
         _ => Err(ParseError::InvalidEntryType(first.to_owned())),
     }
 }
 
-fn parse_decimal(input: &str) -> ParseResult<(BigDecimal, &str)> {
+/// Parses the optional currency code written between the entry type and the amount
+fn parse_currency(input: &str) -> (Option<&str>, &str) {
+    let input = input.trim_start();
+
+    match input.split_once(' ') {
+        Some((code, rest)) if is_currency_code(code) => (Some(code), rest),
+        _ => (None, input),
+    }
+}
+
+fn parse_decimal(input: &str, locale: Locale) -> ParseResult<(BigDecimal, &str)> {
     let input = input.trim_start();
 
     fn parse_decimal(input: &str) -> Option<(&str, &str)> {
@@ -111,7 +192,7 @@ fn parse_decimal(input: &str) -> ParseResult<(BigDecimal, &str)> {
     let (decimal, rest) =
         parse_decimal(input).ok_or_else(|| ParseError::NoDescription(input.to_owned()))?;
 
-    match BigDecimal::from_str(decimal).ok() {
+    match locale.parse_decimal(decimal) {
         Some(decimal) => Ok((decimal, rest)),
         None => Err(ParseError::InvalidDecimal(decimal.to_owned())),
     }
@@ -128,7 +209,10 @@ mod entry_parsing {
 
     use bigdecimal::BigDecimal;
 
-    use crate::parser::{parse_decimal, parse_description, EntryType, ParseError};
+    use crate::{
+        locale::Locale,
+        parser::{parse_currency, parse_decimal, parse_description, EntryType, ParseError},
+    };
 
     use super::Entry;
 
@@ -138,24 +222,61 @@ mod entry_parsing {
         let six = BigDecimal::from_str("6.00").unwrap();
 
         assert_eq!(
-            Entry::from_str("22 + 5.00 Salary").unwrap(),
+            Entry::from_str("22 + 5.00 Salary", Locale::English).unwrap(),
             Entry {
                 day: 22,
                 typ: EntryType::Credit,
                 amount: five,
+                currency: None,
                 description: "Salary"
             }
         );
 
         assert_eq!(
-            Entry::from_str("12 - 6.000 Rent\n").unwrap(),
+            Entry::from_str("12 - 6.000 Rent\n", Locale::English).unwrap(),
             Entry {
                 day: 12,
                 typ: EntryType::Debit,
                 amount: six,
+                currency: None,
                 description: "Rent"
             }
         );
+
+        assert_eq!(
+            Entry::from_str("14 ~ 6 Returned shoes", Locale::English).unwrap(),
+            Entry {
+                day: 14,
+                typ: EntryType::Refund,
+                amount: BigDecimal::from(6),
+                currency: None,
+                description: "Returned shoes"
+            }
+        );
+
+        assert_eq!(
+            Entry::from_str("03 - USD 20.00 Hotel", Locale::English).unwrap(),
+            Entry {
+                day: 3,
+                typ: EntryType::Debit,
+                amount: BigDecimal::from_str("20").unwrap(),
+                currency: Some("USD"),
+                description: "Hotel"
+            }
+        );
+    }
+
+    #[test]
+    fn parses_currency_codes() {
+        assert_eq!(
+            parse_currency(" USD 20.00 Hotel"),
+            (Some("USD"), "20.00 Hotel")
+        );
+        assert_eq!(parse_currency(" 20.00 Hotel"), (None, "20.00 Hotel"));
+        assert_eq!(
+            parse_currency(" usd 20.00 Hotel"),
+            (None, "usd 20.00 Hotel")
+        );
     }
 
     #[test]
@@ -163,12 +284,18 @@ mod entry_parsing {
         let five = BigDecimal::from_str("5.00").unwrap();
         let approx_pi = BigDecimal::from_str("3.1415926535").unwrap();
 
-        assert_eq!(parse_decimal(" 5.00 Test").unwrap(), (five.clone(), "Test"));
+        assert_eq!(
+            parse_decimal(" 5.00 Test", Locale::English).unwrap(),
+            (five.clone(), "Test")
+        );
 
-        assert_eq!(parse_decimal(" 5.00  Test").unwrap(), (five, " Test"));
+        assert_eq!(
+            parse_decimal(" 5.00  Test", Locale::English).unwrap(),
+            (five, " Test")
+        );
 
         assert_eq!(
-            parse_decimal("   3.1415926535 Pi").unwrap(),
+            parse_decimal("   3.1415926535 Pi", Locale::English).unwrap(),
             (approx_pi, "Pi")
         );
     }
@@ -176,12 +303,12 @@ mod entry_parsing {
     #[test]
     fn errs_on_invalid_decimals() {
         assert_eq!(
-            parse_decimal("   NaN Pi").unwrap_err(),
+            parse_decimal("   NaN Pi", Locale::English).unwrap_err(),
             ParseError::InvalidDecimal("NaN".to_owned())
         );
 
         assert_eq!(
-            parse_decimal("Hey 3.5").unwrap_err(),
+            parse_decimal("Hey 3.5", Locale::English).unwrap_err(),
             ParseError::InvalidDecimal("Hey".to_owned())
         );
     }
@@ -192,16 +319,31 @@ mod entry_parsing {
         let approx_pi_ws = "3.1415926535  ".to_string();
 
         assert_eq!(
-            parse_decimal(&approx_pi).unwrap_err(),
+            parse_decimal(&approx_pi, Locale::English).unwrap_err(),
             ParseError::NoDescription(approx_pi)
         );
 
         assert_eq!(
-            parse_decimal("   3.1415926535  ").unwrap_err(),
+            parse_decimal("   3.1415926535  ", Locale::English).unwrap_err(),
             ParseError::NoDescription(approx_pi_ws)
         );
     }
 
+    #[test]
+    fn parses_decimal_commas_when_the_locale_uses_them() {
+        let amount = BigDecimal::from_str("25.50").unwrap();
+
+        assert_eq!(
+            parse_decimal("25,50 Açaí", Locale::BrazilianPortuguese).unwrap(),
+            (amount, "Açaí")
+        );
+
+        assert_eq!(
+            parse_decimal("25,50 Açaí", Locale::English).unwrap_err(),
+            ParseError::InvalidDecimal("25,50".to_owned())
+        );
+    }
+
     #[test]
     fn parses_descriptions_correctly() {
         assert_eq!("Petrobrás", parse_description("  Petrobrás"));
@@ -0,0 +1,47 @@
+use std::{
+    io::{self, IsTerminal},
+    path::Path,
+};
+
+use dialoguer::FuzzySelect;
+
+use crate::{config::Config, rename::known_descriptions, Error, Result};
+
+/// A description given as this is picked from the ones used before
+pub const PICK: &str = "@";
+
+/// Lets the user pick one of the descriptions used in `dir`, the most used ones first, with a
+/// fuzzy finder
+pub fn pick_description(dir: &Path, config: &Config) -> Result<String> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(Error::Pick(
+            "there's no terminal to pick a description in".into(),
+        ));
+    }
+
+    // A card without purchases has no folder yet
+    let mut descriptions: Vec<_> = match dir.exists() {
+        true => known_descriptions(dir, config)?.into_iter().collect(),
+        false => Vec::new(),
+    };
+    if descriptions.is_empty() {
+        return Err(Error::Pick("nothing was recorded yet".into()));
+    }
+    descriptions.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    let items: Vec<_> = descriptions
+        .iter()
+        .map(|(description, _)| description)
+        .collect();
+
+    let picked = FuzzySelect::new()
+        .with_prompt("Description")
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .map_err(|dialoguer::Error::IO(err)| err)?;
+
+    match picked {
+        Some(idx) => Ok(descriptions.swap_remove(idx).0),
+        None => Err(Error::Pick("nothing was picked".into())),
+    }
+}
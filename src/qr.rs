@@ -0,0 +1,29 @@
+use qrcode::{render::unicode::Dense1x2, QrCode};
+
+use crate::{
+    config::Config,
+    export::{collect_records, write_records, Format},
+    file::StoredFile,
+    Error, Result,
+};
+
+/// Renders the month in `stored` as a QR code, made of Unicode half blocks
+///
+/// The QR code holds the month exported as JSON, compressed with zstd and then encoded
+/// in base64 so that any scanner can read it as text.
+pub fn render_month(stored: StoredFile, config: &Config) -> Result<String> {
+    let records = collect_records(&[stored], config)?;
+
+    let mut json = Vec::new();
+    write_records(&records, Format::Json, &mut json)?;
+    let compressed = zstd::encode_all(json.as_slice(), 19)?;
+    let payload = base64::encode(compressed);
+
+    let code = QrCode::new(payload.as_bytes()).map_err(|_| Error::TooLargeForQr(payload.len()))?;
+
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
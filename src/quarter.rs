@@ -0,0 +1,102 @@
+use std::fmt;
+
+use crate::file::BookkeepingFile;
+
+/// A quarter of a fiscal year, such as `2024-Q3`
+///
+/// Fiscal years start in the month set by `fiscal_year_starts_in` in config.toml, and are
+/// named after the year they start in: starting them in April, `2024-Q4` is January to March
+/// of 2025.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quarter {
+    pub year: i32,
+    /// From 1 to 4
+    pub number: u32,
+}
+
+impl Quarter {
+    /// The quarter `month` falls in, for fiscal years starting in `first_month`
+    pub fn of(month: &BookkeepingFile, first_month: u32) -> Self {
+        let first_month = first_month.clamp(1, 12);
+        let year = if month.month() < first_month {
+            month.year() - 1
+        } else {
+            month.year()
+        };
+        let offset = (month.month() + 12 - first_month) % 12;
+
+        Self {
+            year,
+            number: offset / 3 + 1,
+        }
+    }
+
+    /// Parses `YYYY-QN`, such as `2024-Q3`
+    pub fn parse(quarter: &str) -> Option<Self> {
+        let (year, number) = quarter.split_once(['-', ' '])?;
+        let number = number.strip_prefix(['Q', 'q'])?;
+
+        let year = year.parse().ok().filter(|year| (1..=9999).contains(year))?;
+        let number = number
+            .parse()
+            .ok()
+            .filter(|number| (1..=4).contains(number))?;
+
+        Some(Self { year, number })
+    }
+
+    /// The three months of this quarter, in order
+    pub fn months(&self, first_month: u32) -> Vec<BookkeepingFile> {
+        let mut month = BookkeepingFile::new(first_month.clamp(1, 12), self.year);
+        for _ in 0..(self.number - 1) * 3 {
+            month = month.next();
+        }
+
+        let mut months = vec![month];
+        for _ in 1..3 {
+            let next = months[months.len() - 1].next();
+            months.push(next);
+        }
+
+        months
+    }
+}
+
+impl fmt::Display for Quarter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-Q{}", self.year, self.number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Quarter;
+    use crate::file::BookkeepingFile;
+
+    #[test]
+    fn spans_fiscal_years() {
+        let quarter = Quarter::parse("2024-Q3").unwrap();
+        assert_eq!(quarter.to_string(), "2024-Q3");
+        assert!(Quarter::parse("2024-Q5").is_none());
+        assert!(Quarter::parse("2024").is_none());
+
+        let months = |quarter: Quarter, first_month| -> Vec<String> {
+            quarter
+                .months(first_month)
+                .iter()
+                .map(|month| month.as_str().to_owned())
+                .collect()
+        };
+        assert_eq!(months(quarter, 1), ["07-2024", "08-2024", "09-2024"]);
+        // Fiscal years starting in April end in March of the next year
+        let last = Quarter::parse("2024-q4").unwrap();
+        assert_eq!(months(last, 4), ["01-2025", "02-2025", "03-2025"]);
+
+        let february = BookkeepingFile::new(2, 2025);
+        assert_eq!(
+            Quarter::of(&february, 1),
+            Quarter::parse("2025-Q1").unwrap()
+        );
+        assert_eq!(Quarter::of(&february, 4), last);
+    }
+}
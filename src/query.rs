@@ -0,0 +1,472 @@
+use bigdecimal::BigDecimal;
+
+use crate::{
+    amount::parse_amount,
+    config::Config,
+    currency::Converter,
+    file::{BookkeepingFile, StoredFile},
+    parser::EntryType,
+    reader::Reader,
+    rules::RuleSet,
+    Error, Result,
+};
+
+/// What a query answers with, from the operations it matched
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregate {
+    /// Every operation, in the order they happened
+    List,
+    Count,
+    Sum,
+    Average,
+    Min,
+    Max,
+}
+
+/// A property of an operation that conditions can look at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Kind,
+    /// In the default currency
+    Amount,
+    Description,
+    Category,
+    /// The ISO 4217 code it was recorded in
+    Currency,
+    Month,
+    Day,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Eq,
+    Ne,
+    /// Text containing the value, ignoring case
+    Contains,
+    NotContains,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// What a field is compared to, already parsed
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    /// Normalized the way config.toml says descriptions are matched
+    Text(String),
+    Kind(EntryType),
+    Amount(BigDecimal),
+    /// As given by `BookkeepingFile::ordinal`
+    Month(i32),
+    Day(u8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub field: Field,
+    pub op: Op,
+    value: Value,
+}
+
+/// A question about the operations, such as `sum amount where kind=take and month>=01-2024`
+///
+/// A query starts with what it computes, `list`, `count`, or `sum`, `avg`, `min` or `max`
+/// followed by `amount`, where takes count as negative, and may end with `where` and
+/// conditions joined by `and`. Conditions compare `kind`, `amount`, `description`,
+/// `category`, `currency`, `month` or `day` to a value with `=`, `!=`, `<`, `<=`, `>`, `>=`,
+/// or `~` and `!~` for containing text. Values with spaces are quoted, as in
+/// `description~'gas station'`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub aggregate: Aggregate,
+    pub conditions: Vec<Condition>,
+}
+
+/// What a query found, folded as operations are matched, so that only `list` keeps them
+pub struct Answer {
+    aggregate: Aggregate,
+    /// Only kept by `list`
+    pub rows: Vec<Row>,
+    pub count: usize,
+    /// With takes negative, and puts and refunds positive
+    pub total: BigDecimal,
+    /// The smallest or largest signed amount, for `min` and `max`
+    pub extreme: Option<BigDecimal>,
+}
+
+/// An operation matched by a query
+pub struct Row {
+    pub file: BookkeepingFile,
+    pub day: u8,
+    pub typ: EntryType,
+    /// Converted into the default currency
+    pub amount: BigDecimal,
+    pub description: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidQuery(message.into())
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    const OPERATORS: [(&str, Op); 8] = [
+        ("!=", Op::Ne),
+        ("!~", Op::NotContains),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("=", Op::Eq),
+        ("~", Op::Contains),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ];
+    let is_special = |ch: char| ch.is_whitespace() || "!=~<>'\"".contains(ch);
+
+    let mut tokens = Vec::new();
+    let mut rest = input.trim_start();
+
+    while let Some(ch) = rest.chars().next() {
+        if let Some((symbol, op)) = OPERATORS
+            .iter()
+            .find(|(symbol, _)| rest.starts_with(symbol))
+        {
+            tokens.push(Token::Op(*op));
+            rest = &rest[symbol.len()..];
+        } else if ch == '\'' || ch == '"' {
+            let end = rest[1..]
+                .find(ch)
+                .ok_or_else(|| invalid(format!("the quote {} is never closed", ch)))?;
+            tokens.push(Token::Quoted(rest[1..=end].to_owned()));
+            rest = &rest[end + 2..];
+        } else if ch == '!' {
+            return Err(invalid("`!` must be followed by `=` or `~`"));
+        } else {
+            let end = rest.find(is_special).unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..end].to_owned()));
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+
+    Ok(tokens)
+}
+
+impl Query {
+    pub fn parse(input: &str, config: &Config) -> Result<Self> {
+        let mut tokens = tokenize(input)?.into_iter();
+        let mut word = |expected: &str| match tokens.next() {
+            Some(Token::Word(word)) => Ok(word.to_lowercase()),
+            _ => Err(invalid(format!("expected {}", expected))),
+        };
+
+        let aggregate = match word("list, count, sum, avg, min or max")?.as_str() {
+            "list" => Aggregate::List,
+            "count" => Aggregate::Count,
+            "sum" => Aggregate::Sum,
+            "avg" | "average" => Aggregate::Average,
+            "min" => Aggregate::Min,
+            "max" => Aggregate::Max,
+            other => {
+                return Err(invalid(format!(
+                    "'{}' isn't list, count, sum, avg, min or max",
+                    other
+                )))
+            }
+        };
+        if !matches!(aggregate, Aggregate::List | Aggregate::Count) && word("amount")? != "amount" {
+            return Err(invalid(
+                "only the amount can be summed, averaged or compared",
+            ));
+        }
+
+        let mut conditions = Vec::new();
+        match tokens.next() {
+            None => {}
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("where") => loop {
+                conditions.push(parse_condition(&mut tokens, config)?);
+                match tokens.next() {
+                    None => break,
+                    Some(Token::Word(word)) if word.eq_ignore_ascii_case("and") => {}
+                    Some(_) => return Err(invalid("conditions must be joined by `and`")),
+                }
+            },
+            Some(_) => return Err(invalid("expected `where` before the conditions")),
+        }
+
+        Ok(Self {
+            aggregate,
+            conditions,
+        })
+    }
+
+    /// Folds every operation in `files` matching the conditions, with `files` sorted
+    /// chronologically
+    pub fn answer(&self, files: Vec<StoredFile>, config: &Config) -> Result<Answer> {
+        let converter = Converter::new(config);
+        let rules = RuleSet::from_config(config);
+        let mut reader = Reader::new(config);
+        let mut answer = Answer {
+            aggregate: self.aggregate,
+            rows: Vec::new(),
+            count: 0,
+            total: BigDecimal::from(0),
+            extreme: None,
+        };
+
+        for stored in files {
+            reader.for_each_entry_in(stored.open()?, |entry| {
+                let row = Row {
+                    file: stored.file.clone(),
+                    day: entry.day,
+                    typ: entry.typ,
+                    amount: converter.convert(entry.amount, entry.currency)?,
+                    description: entry.description.to_owned(),
+                };
+                let category = rules.categorize(entry.description).unwrap_or_default();
+                let currency = entry.currency.unwrap_or(&config.currency);
+
+                if self
+                    .conditions
+                    .iter()
+                    .all(|condition| condition.matches(&row, category, currency, config))
+                {
+                    answer.add(row);
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(answer)
+    }
+}
+
+impl Answer {
+    fn add(&mut self, row: Row) {
+        self.count += 1;
+        let amount = match row.typ {
+            EntryType::Debit => -row.amount.clone(),
+            EntryType::Credit | EntryType::Refund => row.amount.clone(),
+        };
+
+        match self.aggregate {
+            Aggregate::List => self.rows.push(row),
+            Aggregate::Count => {}
+            Aggregate::Sum | Aggregate::Average => self.total += amount,
+            Aggregate::Min | Aggregate::Max => {
+                let replaces = self
+                    .extreme
+                    .as_ref()
+                    .is_none_or(|extreme| match self.aggregate {
+                        Aggregate::Min => amount < *extreme,
+                        _ => amount > *extreme,
+                    });
+                if replaces {
+                    self.extreme = Some(amount);
+                }
+            }
+        }
+    }
+
+    pub fn print(&self, config: &Config) {
+        let amount = match self.aggregate {
+            Aggregate::List => {
+                for row in &self.rows {
+                    println!(
+                        "{:02} {}: {} {} {}",
+                        row.day,
+                        row.file.label(),
+                        row.typ.name(),
+                        config.format_amount(&row.amount),
+                        row.description
+                    );
+                }
+                return;
+            }
+            Aggregate::Count => {
+                println!("{}", self.count);
+                return;
+            }
+            Aggregate::Sum => Some(self.total.clone()),
+            Aggregate::Average if self.count == 0 => None,
+            Aggregate::Average => {
+                let count = BigDecimal::from(self.count as u64);
+                Some((&self.total / count).with_scale(2))
+            }
+            Aggregate::Min | Aggregate::Max => self.extreme.clone(),
+        };
+
+        match amount {
+            Some(amount) => println!("{}", config.format_amount(&amount)),
+            None => println!("No operations matched"),
+        }
+    }
+}
+
+fn parse_condition(tokens: &mut impl Iterator<Item = Token>, config: &Config) -> Result<Condition> {
+    let field = match tokens.next() {
+        Some(Token::Word(word)) => match word.to_lowercase().as_str() {
+            "kind" => Field::Kind,
+            "amount" => Field::Amount,
+            "description" => Field::Description,
+            "category" => Field::Category,
+            "currency" => Field::Currency,
+            "month" => Field::Month,
+            "day" => Field::Day,
+            _ => {
+                return Err(invalid(format!(
+                    "'{}' isn't something operations have",
+                    word
+                )))
+            }
+        },
+        _ => return Err(invalid("expected a field after `where` or `and`")),
+    };
+    let op = match tokens.next() {
+        Some(Token::Op(op)) => op,
+        _ => {
+            return Err(invalid(
+                "expected =, !=, ~, !~, <, <=, > or >= after a field",
+            ))
+        }
+    };
+    let value = match tokens.next() {
+        Some(Token::Word(value) | Token::Quoted(value)) => value,
+        _ => return Err(invalid("expected a value to compare to")),
+    };
+
+    let ordered = !matches!(op, Op::Contains | Op::NotContains);
+    let textual = matches!(op, Op::Eq | Op::Ne | Op::Contains | Op::NotContains);
+    let value = match field {
+        Field::Description | Field::Category | Field::Currency if textual => {
+            Value::Text(config.matching.normalize(&value))
+        }
+        Field::Kind if matches!(op, Op::Eq | Op::Ne) => match value.to_lowercase().as_str() {
+            "take" => Value::Kind(EntryType::Debit),
+            "put" => Value::Kind(EntryType::Credit),
+            "refund" => Value::Kind(EntryType::Refund),
+            _ => return Err(invalid(format!("'{}' isn't take, put or refund", value))),
+        },
+        Field::Amount if ordered => parse_amount(&value, config.locale)
+            .map(Value::Amount)
+            .ok_or(Error::InvalidAmount(value))?,
+        Field::Month if ordered => BookkeepingFile::parse(&value, &BookkeepingFile::current_file())
+            .map(|month| Value::Month(month.ordinal()))
+            .ok_or(Error::InvalidMonth(value))?,
+        Field::Day if ordered => value
+            .parse()
+            .ok()
+            .filter(|day| (1..=31).contains(day))
+            .map(Value::Day)
+            .ok_or_else(|| invalid(format!("'{}' isn't a day", value)))?,
+        _ => {
+            return Err(invalid(format!(
+                "{:?} can't be compared with {:?}",
+                field, op
+            )))
+        }
+    };
+
+    Ok(Condition { field, op, value })
+}
+
+impl Condition {
+    fn matches(&self, row: &Row, category: &str, currency: &str, config: &Config) -> bool {
+        let normalize = |text| config.matching.normalize(text);
+
+        match &self.value {
+            Value::Text(value) => {
+                let text = match self.field {
+                    Field::Category => normalize(category),
+                    Field::Currency => normalize(currency),
+                    _ => normalize(&row.description),
+                };
+                match self.op {
+                    Op::Contains => text.contains(value.as_str()),
+                    Op::NotContains => !text.contains(value.as_str()),
+                    op => compare(op, &text, value),
+                }
+            }
+            Value::Kind(kind) => compare(self.op, &row.typ.name(), &kind.name()),
+            Value::Amount(amount) => compare(self.op, &row.amount, amount),
+            Value::Month(month) => compare(self.op, &row.file.ordinal(), month),
+            Value::Day(day) => compare(self.op, &row.day, day),
+        }
+    }
+}
+
+fn compare<T: PartialOrd + ?Sized>(op: Op, left: &T, right: &T) -> bool {
+    match op {
+        Op::Eq => left == right,
+        Op::Ne => left != right,
+        Op::Lt => left < right,
+        Op::Le => left <= right,
+        Op::Gt => left > right,
+        Op::Ge => left >= right,
+        Op::Contains | Op::NotContains => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{Aggregate, Query};
+    use crate::{archive::list_stored_files, config::Config};
+
+    #[test]
+    fn answers_with_the_matching_operations() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("12-2023"), "03 - 30 Uber to the airport\n").unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 5000 Salary\n06 - 25.50 uber home\n07 - 40 Groceries\n08 ~ 5 Uber refund\n",
+        )
+        .unwrap();
+        let config = Config::default();
+        let run = |input: &str| {
+            let query = Query::parse(input, &config).unwrap();
+            let files = list_stored_files(dir.path()).unwrap();
+            query.answer(files, &config).unwrap()
+        };
+
+        let query = "sum amount where kind=take and description~'UBER' and month>=01-2024";
+        assert_eq!(
+            Query::parse(query, &config).unwrap().aggregate,
+            Aggregate::Sum
+        );
+        let answer = run(query);
+        assert_eq!(answer.count, 1);
+        assert_eq!(answer.total, "-25.50".parse::<BigDecimal>().unwrap());
+
+        assert_eq!(run("count where description ~ uber").count, 3);
+        assert_eq!(run("list where amount > 30 and kind != put").rows.len(), 1);
+        assert_eq!(run("count").count, 5);
+        assert!(run("count").rows.is_empty());
+
+        let amount = |amount: &str| amount.parse::<BigDecimal>().unwrap();
+        assert_eq!(run("sum amount").total, amount("4909.50"));
+        assert_eq!(run("min amount").extreme, Some(amount("-40")));
+        assert_eq!(run("max amount where kind!=put").extreme, Some(amount("5")));
+
+        for broken in [
+            "sum description",
+            "count where",
+            "count where day > 32",
+            "count where kind < take",
+            "count where description~'uber",
+            "list where amount >= 10 or day = 3",
+        ] {
+            assert!(Query::parse(broken, &config).is_err(), "{}", broken);
+        }
+    }
+}
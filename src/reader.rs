@@ -1,43 +1,150 @@
-use std::{path::Path, str};
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    str,
+};
 
 use bigdecimal::{BigDecimal, Zero};
 use fixed_buffer::{deframe_line, FixedBuf};
 use fs_err as fs;
 
 use crate::{
-    parser::{Entry, EntryType},
+    config::Config,
+    crypt::{self, Cipher},
+    currency::Converter,
+    locale::Locale,
+    parser::{Entry, EntryType, Operation},
     Result, Total,
 };
 
 /// A stack-based file reader
 pub struct Reader {
     buf: FixedBuf<512>,
+    locale: Locale,
+    /// Decrypts files that were written encrypted
+    cipher: Option<Cipher>,
 }
 
 impl Reader {
-    pub const fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
         Self {
             buf: FixedBuf::new(),
+            locale: config.locale,
+            cipher: config.cipher.clone(),
         }
     }
 
-    /// Read a bookkeeping file and return the total amount spent and received.
-    pub fn total_from_file(&mut self, path: impl AsRef<Path>) -> Result<Total> {
-        let mut file = fs::File::open(path.as_ref())?;
+    /// Read a bookkeeping file and return the total amount spent and received,
+    /// converted into the currency of `converter`.
+    pub fn total_from_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        converter: &Converter,
+    ) -> Result<Total> {
+        self.total_until_day(path, u8::MAX, converter)
+    }
+
+    /// Like `total_from_file`, but ignoring entries recorded after `last_day`.
+    pub fn total_until_day(
+        &mut self,
+        path: impl AsRef<Path>,
+        last_day: u8,
+        converter: &Converter,
+    ) -> Result<Total> {
         let mut outgoing = BigDecimal::zero();
         let mut incoming = BigDecimal::zero();
 
-        while let Ok(Some(line)) = self.buf.read_frame(&mut file, deframe_line) {
-            let line = str::from_utf8(line)?;
-            let entry = Entry::from_str(line)?;
+        self.for_each_entry(path, |entry| {
+            if entry.day > last_day {
+                return Ok(());
+            }
+
+            let amount = converter.convert(entry.amount, entry.currency)?;
             match entry.typ {
-                EntryType::Debit => outgoing += entry.amount,
-                EntryType::Credit => incoming += entry.amount,
+                EntryType::Debit => outgoing += amount,
+                EntryType::Credit => incoming += amount,
+                EntryType::Refund => outgoing -= amount,
             }
-        }
+
+            Ok(())
+        })?;
 
         Ok(Total { outgoing, incoming })
     }
+
+    /// Read every entry of a bookkeeping file, numbered by line.
+    pub fn operations(&mut self, path: impl AsRef<Path>) -> Result<Vec<Operation>> {
+        let mut operations = Vec::new();
+
+        self.for_each_entry(path, |entry| {
+            operations.push(Operation::from_entry(operations.len() + 1, entry));
+            Ok(())
+        })?;
+
+        Ok(operations)
+    }
+
+    /// Like `operations`, reading the entries from `input`
+    pub fn operations_in(&mut self, input: impl Read) -> Result<Vec<Operation>> {
+        let mut operations = Vec::new();
+
+        self.for_each_entry_in(input, |entry| {
+            operations.push(Operation::from_entry(operations.len() + 1, entry));
+            Ok(())
+        })?;
+
+        Ok(operations)
+    }
+
+    /// Parse every entry of a bookkeeping file, feeding each one to `f`.
+    pub fn for_each_entry(
+        &mut self,
+        path: impl AsRef<Path>,
+        f: impl FnMut(Entry) -> Result<()>,
+    ) -> Result<()> {
+        crate::debug!("reading {}", path.as_ref().display());
+        let file = fs::File::open(path.as_ref())?;
+
+        self.for_each_entry_in(file, f)
+    }
+
+    /// Like `for_each_entry`, but reading the file's contents from `input`.
+    pub fn for_each_entry_in(
+        &mut self,
+        input: impl Read,
+        f: impl FnMut(Entry) -> Result<()>,
+    ) -> Result<()> {
+        let mut input = BufReader::new(input);
+
+        // Encrypted files can't be read line by line, so they're decrypted whole
+        if crypt::is_encrypted(input.fill_buf()?) {
+            let mut contents = Vec::new();
+            input.read_to_end(&mut contents)?;
+            let plaintext = crypt::open(contents, self.cipher.as_ref())?;
+
+            return self.for_each_line(plaintext.as_slice(), f);
+        }
+
+        self.for_each_line(input, f)
+    }
+
+    fn for_each_line(
+        &mut self,
+        mut input: impl Read,
+        mut f: impl FnMut(Entry) -> Result<()>,
+    ) -> Result<()> {
+        // Leftovers from a previous file must not leak into this one
+        self.buf.clear();
+
+        while let Ok(Some(line)) = self.buf.read_frame(&mut input, deframe_line) {
+            let line = str::from_utf8(line)?;
+            let entry = Entry::from_str(line, self.locale)?;
+            crate::debug!("parsed {:?} as {:?}", line, entry);
+            f(entry)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -47,7 +154,7 @@ mod tests {
     use bigdecimal::BigDecimal;
     use tempfile::NamedTempFile;
 
-    use crate::reader::Reader;
+    use crate::{config::Config, currency::Converter, reader::Reader};
 
     #[test]
     fn reads_total_from_file_correctly() {
@@ -57,10 +164,19 @@ mod tests {
         writeln!(dummy, "23 - 10.25 Lunch").unwrap();
         writeln!(dummy, "23 - 10.27 Dinner").unwrap();
 
-        let mut reader = Reader::new();
-        let total = reader.total_from_file(dummy.path()).unwrap();
+        let config = Config::default();
+        let converter = Converter::new(&config);
+        let mut reader = Reader::new(&config);
+        let total = reader.total_from_file(dummy.path(), &converter).unwrap();
 
         assert_eq!(total.incoming, BigDecimal::from_str("500.75").unwrap());
         assert_eq!(total.outgoing, BigDecimal::from_str("20.52").unwrap());
+
+        let partial = reader
+            .total_until_day(dummy.path(), 22, &converter)
+            .unwrap();
+
+        assert_eq!(partial.incoming, BigDecimal::from_str("500.75").unwrap());
+        assert_eq!(partial.outgoing, BigDecimal::from_str("0").unwrap());
     }
 }
@@ -0,0 +1,222 @@
+use std::{cmp::Reverse, collections::HashMap, path::Path, str};
+
+use crate::{
+    config::Config,
+    crypt,
+    file::{list_bookkeeping_files, BookkeepingFile},
+    parser::Entry,
+    reader::Reader,
+    rules::{untagged, Normalization},
+    writer::{check_description, Writer},
+    Error, Result,
+};
+
+/// Every description used in the bookkeeping files of `dir`, with how many times it was used
+pub fn known_descriptions(dir: &Path, config: &Config) -> Result<HashMap<String, usize>> {
+    let mut reader = Reader::new(config);
+    let mut known = HashMap::new();
+
+    for file in list_bookkeeping_files(dir)? {
+        for operation in reader.operations(dir.join(file.as_path()))? {
+            *known.entry(operation.description).or_insert(0) += 1;
+        }
+    }
+
+    Ok(known)
+}
+
+/// The description in `known` that `description` is most likely a misspelling of, if any
+///
+/// Descriptions already in `known` aren't misspelled, nor are the ones too short to tell. Tags
+/// and people are left out of the comparison, as rules add them. When several are just as
+/// close, the one used the most wins.
+pub fn suggest<'k>(
+    description: &str,
+    known: &'k HashMap<String, usize>,
+    normalization: Normalization,
+) -> Option<(&'k str, usize)> {
+    let normalized = normalization.normalize(&untagged(description));
+    let max_distance = max_distance(&normalized)?;
+
+    let mut best = None;
+    for (candidate, &count) in known {
+        let distance = edit_distance(&normalized, &normalization.normalize(&untagged(candidate)));
+        if distance == 0 {
+            return None;
+        }
+        if distance > max_distance {
+            continue;
+        }
+
+        // Ties are broken alphabetically, as `known` has no order
+        let rank = (distance, Reverse(count), candidate);
+        if best.is_none_or(|(best_rank, _)| rank < best_rank) {
+            best = Some((rank, (candidate.as_str(), count)));
+        }
+    }
+
+    best.map(|(_, suggestion)| suggestion)
+}
+
+/// Whether `description` is long enough for `suggest` to ever find it misspelled
+pub fn may_be_misspelled(description: &str, normalization: Normalization) -> bool {
+    max_distance(&normalization.normalize(&untagged(description))).is_some()
+}
+
+/// How far from a known description `normalized` may be to be a misspelling of it
+fn max_distance(normalized: &str) -> Option<usize> {
+    match normalized.chars().count() {
+        0..=3 => None,
+        4..=6 => Some(1),
+        _ => Some(2),
+    }
+}
+
+/// How many characters must be inserted, removed, replaced or swapped with the next one to
+/// turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // `distances[i][j]` is the distance between the first `i` chars of `a` and `j` of `b`
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for i in 0..=a.len() {
+        for j in 0..=b.len() {
+            distances[i][j] = if i == 0 || j == 0 {
+                i + j
+            } else {
+                let replaced = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+                let mut distance = replaced
+                    .min(distances[i - 1][j] + 1)
+                    .min(distances[i][j - 1] + 1);
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    distance = distance.min(distances[i - 2][j - 2] + 1);
+                }
+                distance
+            };
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+/// Replaces the description of every entry of `file` written as `from` with `to`, printing each
+/// of them, and returns how many were
+///
+/// Descriptions are compared as rules are, so `from` may differ in case or accents. Fails,
+/// changing nothing, if `to` is empty, spans several lines, or wouldn't read back as written.
+pub fn rename_in_file(
+    dir: &Path,
+    file: &BookkeepingFile,
+    from: &str,
+    to: &str,
+    config: &Config,
+) -> Result<usize> {
+    let to = to.trim();
+    check_description(to)?;
+
+    let path = dir.join(file.as_path());
+    let contents = crypt::read_file(&path, config.cipher.as_ref())?;
+    let contents = str::from_utf8(&contents)?;
+    let from = config.matching.normalize(from);
+
+    let mut renamed = 0;
+    let mut output = String::with_capacity(contents.len());
+
+    for (idx, line) in contents.lines().enumerate() {
+        let entry = Entry::from_str(line, config.locale)?;
+
+        if config.matching.normalize(entry.description) == from {
+            // The rest of the line is kept as it was written
+            let kept = line
+                .trim_end()
+                .strip_suffix(entry.description)
+                .unwrap_or(line);
+            let line = format!("{}{}", kept, to);
+            // Read back, so that no rename leaves behind a line that isn't this entry
+            if Entry::from_str(&line, config.locale)?.description != to {
+                return Err(Error::InvalidDescription(to.to_owned()));
+            }
+            println!("{}:{} {}", file.as_str(), idx + 1, line);
+            output.push_str(&line);
+            renamed += 1;
+        } else {
+            output.push_str(line);
+        }
+        output.push('\n');
+    }
+
+    if renamed > 0 {
+        Writer::replace_contents(&path, output.into_bytes(), config)?;
+    }
+
+    Ok(renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{edit_distance, rename_in_file, suggest};
+    use crate::{config::Config, file::BookkeepingFile, rules::Normalization};
+
+    #[test]
+    fn suggests_the_closest_known_description() {
+        assert_eq!(edit_distance("ubber", "uber"), 1);
+        assert_eq!(edit_distance("mercado", "mecrado"), 1);
+        assert_eq!(edit_distance("mercado", "merc"), 3);
+
+        let known = HashMap::from([
+            ("Uber".to_owned(), 12),
+            ("Uber Eats".to_owned(), 3),
+            ("Cinema".to_owned(), 1),
+            ("Groceries #food @ana".to_owned(), 4),
+        ]);
+        let normalization = Normalization::default();
+
+        assert_eq!(suggest("Ubber", &known, normalization), Some(("Uber", 12)));
+        assert_eq!(suggest("UBER", &known, normalization), None);
+        assert_eq!(
+            suggest("Cinama", &known, normalization),
+            Some(("Cinema", 1))
+        );
+        assert_eq!(suggest("Padaria", &known, normalization), None);
+        // Tags added by rules don't hide a misspelling, nor make one
+        assert_eq!(
+            suggest("Grocereis", &known, normalization),
+            Some(("Groceries #food @ana", 4))
+        );
+        assert_eq!(suggest("Groceries #market", &known, normalization), None);
+    }
+
+    #[test]
+    fn renames_across_a_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01-2024");
+        fs::write(&path, "05 - 20 Ubber\n06 - 12 Coffee\n07 - 9 ubber\n").unwrap();
+
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        let file = BookkeepingFile::new(1, 2024);
+
+        let renamed = rename_in_file(dir.path(), &file, "Ubber", "Uber", &config).unwrap();
+        assert_eq!(renamed, 2);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "05 - 20 Uber\n06 - 12 Coffee\n07 - 9 Uber\n"
+        );
+
+        for to in ["", " ", "Uber\n06 + 5000 Salary"] {
+            rename_in_file(dir.path(), &file, "Uber", to, &config).unwrap_err();
+        }
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "05 - 20 Uber\n06 - 12 Coffee\n07 - 9 Uber\n"
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::io::{self, Write};
+
+use clap::ArgEnum;
+
+use crate::{
+    config::Config,
+    i18n::tf,
+    status::{Column, Group, GroupBy, Status},
+};
+
+/// Where `report --auto` writes reports in the data folder, unless `reports_folder` is set
+pub const REPORTS_FOLDER: &str = "reports";
+
+/// How a report can be written
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+}
+
+/// Writes the report of a month into `out`: its status and every entry, followed by the
+/// spending of each category in `groups`
+pub fn write_report(
+    status: &Status,
+    groups: &[Group],
+    config: &Config,
+    format: Format,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut markdown = Vec::new();
+    status.render_markdown(config, &Column::ALL, &mut markdown)?;
+    status.render_groups_markdown(groups, GroupBy::Category, config, &mut markdown)?;
+
+    match format {
+        Format::Markdown => out.write_all(&markdown),
+        Format::Html => {
+            let title = tf("Report for {}", &[&status.file.label()]);
+            write_html(&String::from_utf8_lossy(&markdown), &title, out)
+        }
+    }
+}
+
+/// Writes the markdown written by `render_markdown` as a standalone HTML page
+///
+/// Only what reports use is understood: headings, tables and paragraphs.
+fn write_html(markdown: &str, title: &str, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html>")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>{}</title>", escape(title))?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+
+    // The rows of the table being read, which is written once it ends
+    let mut table: Vec<Vec<String>> = Vec::new();
+    for line in markdown.lines().chain([""]) {
+        if line.starts_with('|') {
+            table.push(cells(line));
+            continue;
+        }
+        if !table.is_empty() {
+            write_table(&table, out)?;
+            table.clear();
+        }
+
+        if let Some(heading) = line.strip_prefix("### ") {
+            writeln!(out, "<h3>{}</h3>", escape(heading))?;
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            writeln!(out, "<h2>{}</h2>", escape(heading))?;
+        } else if !line.is_empty() {
+            writeln!(out, "<p>{}</p>", escape(line))?;
+        }
+    }
+
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")
+}
+
+/// Writes a markdown table, given as its header, its alignment row and the rest of its rows
+fn write_table(table: &[Vec<String>], out: &mut impl Write) -> io::Result<()> {
+    let (header, alignment, rows) = match table {
+        [header, alignment, rows @ ..] => (header, alignment, rows),
+        _ => return Ok(()),
+    };
+    let aligns: Vec<_> = alignment
+        .iter()
+        .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+            (true, true) => "center",
+            (false, true) => "right",
+            _ => "left",
+        })
+        .collect();
+
+    let write_row = |out: &mut dyn Write, tag: &str, row: &[String]| -> io::Result<()> {
+        write!(out, "<tr>")?;
+        for (cell, align) in row.iter().zip(&aligns) {
+            write!(
+                out,
+                "<{} style=\"text-align: {}\">{}</{}>",
+                tag,
+                align,
+                escape(cell),
+                tag
+            )?;
+        }
+        writeln!(out, "</tr>")
+    };
+
+    writeln!(out, "<table>")?;
+    write_row(out, "th", header)?;
+    for row in rows {
+        write_row(out, "td", row)?;
+    }
+    writeln!(out, "</table>")
+}
+
+/// The cells of a markdown table row, where `\|` is a pipe inside a cell
+fn cells(row: &str) -> Vec<String> {
+    let row = row.trim().trim_start_matches('|');
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = row.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('|') => cell.push('|'),
+                Some(next) => {
+                    cell.push('\\');
+                    cell.push(next);
+                }
+                None => cell.push('\\'),
+            },
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_owned()),
+            ch => cell.push(ch),
+        }
+    }
+
+    cells
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{write_report, Format};
+    use crate::{
+        config::Config,
+        currency::Converter,
+        file::BookkeepingFile,
+        status::{GroupBy, Status},
+    };
+
+    #[test]
+    fn writes_html_reports() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 100 Salary\n06 - 30 Rent | Condo <2>\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let converter = Converter::new(&config);
+        let file = BookkeepingFile::new(1, 2024);
+        let status = Status::compute(dir.path(), file, 31, &config, &converter).unwrap();
+        let groups = status
+            .groups(GroupBy::Category, &config, &converter)
+            .unwrap();
+
+        let mut report = Vec::new();
+        write_report(&status, &groups, &config, Format::Html, &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+
+        assert!(report.starts_with("<!DOCTYPE html>"));
+        assert!(report.contains("<h2>Status for Jan 2024</h2>\n"));
+        assert!(report.contains(
+            "<tr><td style=\"text-align: right\">2</td>\
+             <td style=\"text-align: right\">06</td>\
+             <td style=\"text-align: center\">-</td>\
+             <td style=\"text-align: right\">R$ 30.00</td>\
+             <td style=\"text-align: right\">R$ 70.00</td>\
+             <td style=\"text-align: left\">Rent | Condo &lt;2&gt;</td></tr>\n"
+        ));
+        assert!(report.contains("<h3>Operations by category</h3>\n"));
+        assert!(report.ends_with("</body>\n</html>\n"));
+    }
+}
@@ -0,0 +1,234 @@
+use serde::Deserialize;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::config::Config;
+
+/// How descriptions and rule patterns are folded before being compared
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Normalization {
+    /// Compare ignoring letter case, e.g. "ACAI" matches "acai"
+    pub ignore_case: bool,
+    /// Compare ignoring diacritics, e.g. "Açaí" matches "acai"
+    pub ignore_accents: bool,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self {
+            ignore_case: true,
+            ignore_accents: true,
+        }
+    }
+}
+
+impl Normalization {
+    pub fn normalize(&self, input: &str) -> String {
+        let folded: String = if self.ignore_accents {
+            input.nfd().filter(|&ch| !is_combining_mark(ch)).collect()
+        } else {
+            input.nfc().collect()
+        };
+
+        let folded = if self.ignore_case {
+            folded.to_lowercase()
+        } else {
+            folded
+        };
+
+        // Collapse runs of whitespace so "Acai  Ltda" matches "acai ltda"
+        folded.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// An auto-categorization rule, as written in the config file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// Text that must appear somewhere in the description, or several separated by `|`,
+    /// as in `uber|99`
+    #[serde(alias = "match")]
+    pub pattern: String,
+    /// Category given to matching entries
+    pub category: String,
+}
+
+/// The rules from the config, with their patterns already normalized
+pub struct RuleSet<'a> {
+    normalization: Normalization,
+    /// The alternatives of each rule's pattern, and its category
+    rules: Vec<(Vec<String>, &'a str)>,
+}
+
+impl<'a> RuleSet<'a> {
+    pub fn from_config(config: &'a Config) -> Self {
+        let normalization = config.matching;
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let alternatives = rule
+                    .pattern
+                    .split('|')
+                    .map(|alternative| normalization.normalize(alternative))
+                    .filter(|alternative| !alternative.is_empty())
+                    .collect();
+                (alternatives, rule.category.as_str())
+            })
+            .collect();
+
+        Self {
+            normalization,
+            rules,
+        }
+    }
+
+    /// The category `description` is tagged with, or else the one of the first rule matching it
+    pub fn categorize<'d>(&self, description: &'d str) -> Option<&'d str>
+    where
+        'a: 'd,
+    {
+        if let Some(tag) = category_tag(description) {
+            return Some(tag);
+        }
+
+        let description = self.normalization.normalize(description);
+
+        self.rules
+            .iter()
+            .find(|(alternatives, _)| {
+                alternatives
+                    .iter()
+                    .any(|alternative| description.contains(alternative.as_str()))
+            })
+            .map(|&(_, category)| category)
+    }
+
+    /// `description` with the `#tag` of the rule matching it, if it has no tag yet
+    pub fn tag(&self, description: &str) -> Option<String> {
+        if category_tag(description).is_some() {
+            return None;
+        }
+
+        let category = self.categorize(description)?;
+        // Tags end at whitespace
+        let tag = category.split_whitespace().collect::<Vec<_>>().join("-");
+
+        Some(format!("{} #{}", description.trim_end(), tag))
+    }
+
+    /// Whether `description` is about `subject`, either because `subject` is the category
+    /// it falls into or because `subject` appears in it
+    pub fn is_about(&self, description: &str, subject: &str) -> bool {
+        let subject = self.normalization.normalize(subject);
+
+        let in_category = self
+            .categorize(description)
+            .is_some_and(|category| self.normalization.normalize(category) == subject);
+
+        in_category || self.normalization.normalize(description).contains(&subject)
+    }
+}
+
+/// `description` tagged by the config's rules, if `auto_tag` is set and a rule matches
+pub fn auto_tag(config: &Config, description: &str) -> Option<String> {
+    if !config.auto_tag {
+        return None;
+    }
+
+    RuleSet::from_config(config).tag(description)
+}
+
+/// A category given explicitly with a `#tag` in the description, e.g. `Dinner out #food`
+pub fn category_tag(description: &str) -> Option<&str> {
+    description
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .find(|tag| !tag.is_empty())
+}
+
+/// The person an entry is attributed to with `@person` in its description, in a book
+/// shared by a household, e.g. `Groceries @alice`
+pub fn person_tag(description: &str) -> Option<&str> {
+    description
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+        .find(|person| !person.is_empty())
+}
+
+/// `description` without its `#tags` and `@people`, to compare what it says
+pub fn untagged(description: &str) -> String {
+    description
+        .split_whitespace()
+        .filter(|word| category_tag(word).is_none() && person_tag(word).is_none())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `description` attributed to `person`, unless it already is to someone
+pub fn attributed(description: &str, person: &str) -> String {
+    if person_tag(description).is_some() {
+        return description.to_owned();
+    }
+
+    // Like tags, people end at whitespace
+    let person = person.split_whitespace().collect::<Vec<_>>().join("-");
+    format!("{} @{}", description.trim_end(), person)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Normalization, RuleSet};
+    use crate::config::Config;
+
+    #[test]
+    fn normalizes_case_accents_and_whitespace() {
+        let normalization = Normalization::default();
+
+        assert_eq!(normalization.normalize("Açaí"), "acai");
+        assert_eq!(normalization.normalize("ACAI  LTDA"), "acai ltda");
+
+        let strict = Normalization {
+            ignore_case: false,
+            ignore_accents: false,
+        };
+        assert_eq!(strict.normalize("Açaí"), "Açaí");
+    }
+
+    #[test]
+    fn categorizes_with_normalized_matching() {
+        let config: Config = toml::from_str(
+            r#"
+            [[rules]]
+            pattern = "Açaí"
+            category = "food"
+
+            [[rules]]
+            match = "uber|99 taxi"
+            category = "getting around"
+            "#,
+        )
+        .unwrap();
+        let rules = RuleSet::from_config(&config);
+
+        assert_eq!(rules.categorize("Açaí"), Some("food"));
+        assert_eq!(rules.categorize("acai"), Some("food"));
+        assert_eq!(rules.categorize("ACAI LTDA"), Some("food"));
+        assert_eq!(rules.categorize("Rent"), None);
+        assert_eq!(rules.categorize("Açaí #dessert"), Some("dessert"));
+        assert_eq!(rules.categorize("UBER *TRIP"), Some("getting around"));
+        assert_eq!(rules.categorize("99 Taxi"), Some("getting around"));
+
+        assert!(rules.is_about("ACAI LTDA", "Food"));
+        assert!(rules.is_about("Açaí da esquina", "esquina"));
+        assert!(!rules.is_about("Rent", "Food"));
+
+        assert_eq!(rules.tag("Açaí").as_deref(), Some("Açaí #food"));
+        assert_eq!(
+            rules.tag("99 Taxi").as_deref(),
+            Some("99 Taxi #getting-around")
+        );
+        assert_eq!(rules.tag("Açaí #dessert"), None);
+        assert_eq!(rules.tag("Rent"), None);
+    }
+}
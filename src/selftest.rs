@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use crate::{
+    archive::list_stored_files,
+    config::Config,
+    export::{collect_records, read_records, write_records, Format},
+    import::import_records,
+    Error, Result,
+};
+
+/// Exports the whole book in every format, imports each export into a throwaway folder and
+/// checks that nothing was lost on the way
+pub fn selftest(dir: &Path, config: &Config) -> Result<()> {
+    let original = collect_records(&list_stored_files(dir)?, config)?;
+
+    for format in [Format::Json, Format::Csv] {
+        let mut exported = Vec::new();
+        write_records(&original, format, &mut exported)?;
+
+        let temp = TempDir::new()?;
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+        import_records(
+            temp.path(),
+            &read_records(format, exported.as_slice())?,
+            &config,
+        )?;
+        let reimported = collect_records(&list_stored_files(temp.path())?, &config)?;
+
+        if let Some(idx) = (0..original.len().max(reimported.len()))
+            .find(|&idx| original.get(idx) != reimported.get(idx))
+        {
+            eprintln!("{:?} round-trip mismatch at operation #{}", format, idx + 1);
+            eprintln!("\tbefore: {:?}", original.get(idx));
+            eprintln!("\tafter:  {:?}", reimported.get(idx));
+            return Err(Error::SelftestFailed(format!("{:?}", format)));
+        }
+
+        println!(
+            "{:?} round-trip: OK ({} operations)",
+            format,
+            original.len()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use bigdecimal::{BigDecimal, Signed};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+    currency::is_currency_code,
+    dates::current_day,
+    diagnostics::Code,
+    file::BookkeepingFile,
+    git::Git,
+    parser::{Entry, EntryType},
+    rules::auto_tag,
+    writer::check_description,
+    Bookkeeper, Error, Result,
+};
+
+/// The body of `POST /take`, `POST /put` and `POST /refund`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewEntry {
+    amount: BigDecimal,
+    description: String,
+    /// ISO 4217 code, if not the default currency
+    currency: Option<String>,
+}
+
+/// Serves a JSON API over `book` on `127.0.0.1:port`, until the process is killed
+///
+/// Changes are committed to `git`, if the book is tracked.
+pub fn serve(book: &Bookkeeper, git: Option<&Git>, port: u16) -> Result<()> {
+    let server = Server::http(("127.0.0.1", port)).map_err(|err| Error::Server(err.to_string()))?;
+    println!("Serving the book at http://127.0.0.1:{}", port);
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match handle(book, git, &mut request) {
+            Ok(response) => response,
+            Err(err) => (status_of(&err), error_body(&err)),
+        };
+        println!("{} {} {}", request.method(), request.url(), status);
+
+        // Safety: both are valid header bytes
+        let json = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(json);
+        // The client may be gone already, which is no reason to stop serving
+        let _ = request.respond(response);
+    }
+
+    Ok(())
+}
+
+/// Routes `request`, returning the response's status code and body
+fn handle(book: &Bookkeeper, git: Option<&Git>, request: &mut Request) -> Result<(u16, Value)> {
+    let segments: Vec<String> = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_owned)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match (request.method(), segments.as_slice()) {
+        (Method::Get, ["months"]) => {
+            let months: Vec<_> = book
+                .months()?
+                .iter()
+                .map(|month| month.as_str().to_owned())
+                .collect();
+            Ok((200, json!(months)))
+        }
+        (Method::Get, ["months", month, "operations"]) => {
+            let month = existing_month(book, month)?;
+            Ok((200, json!(book.operations(&month)?)))
+        }
+        (Method::Get, ["months", month, "status"]) => {
+            let month = existing_month(book, month)?;
+            // Past months are compared to the whole of the month before them
+            let day = if month.as_str() == BookkeepingFile::current_file().as_str() {
+                current_day()
+            } else {
+                u8::MAX
+            };
+            let status = book.status(month, day)?;
+
+            Ok((
+                200,
+                json!({
+                    "month": status.file.as_str(),
+                    "currency": status.currency,
+                    "incoming": status.total.incoming,
+                    "outgoing": status.total.outgoing,
+                    "balance": status.total.balance(),
+                    "opening_balance": status.opening_balance,
+                    "cash_on_hand": status.cash_on_hand(),
+                    "daily_outgoing": status.daily_outgoing(),
+                    "daily_incoming": status.daily_incoming(),
+                    "savings_rate": status.total.savings_rate(),
+                }),
+            ))
+        }
+        (Method::Post, [kind @ ("take" | "put" | "refund")]) => {
+            if let Some(refused) = refuse_write(book, request) {
+                return Ok(refused);
+            }
+            let typ = match *kind {
+                "take" => EntryType::Debit,
+                "refund" => EntryType::Refund,
+                _ => EntryType::Credit,
+            };
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body)?;
+            let new: NewEntry =
+                serde_json::from_str(&body).map_err(|err| Error::Interchange(err.to_string()))?;
+
+            let (amount, description) = (new.amount, new.description.trim());
+            if !amount.is_positive() {
+                return Err(Error::NegativeAmount(amount));
+            }
+            check_description(description)?;
+            if let Some(code) = new
+                .currency
+                .as_deref()
+                .filter(|code| !is_currency_code(code))
+            {
+                return Err(Error::InvalidCurrency(code.to_owned()));
+            }
+
+            let tagged = auto_tag(book.config(), description);
+            let description = tagged.as_deref().unwrap_or(description);
+            let day = current_day();
+            let entry = Entry {
+                currency: new.currency.as_deref(),
+                ..Entry::new(day, typ, amount.clone(), description)
+            };
+            let month = BookkeepingFile::current_file();
+            book.record(&month, entry)?;
+
+            if let (Some(git), false) = (git, book.config().dry_run) {
+                let currency = new
+                    .currency
+                    .as_deref()
+                    .map(|code| format!("{code} "))
+                    .unwrap_or_default();
+                git.commit(&format!("{kind} {currency}{amount} {description}"))?;
+            }
+
+            Ok((201, json!({ "month": month.as_str() })))
+        }
+        _ => Ok((404, json!({ "error": "no such endpoint" }))),
+    }
+}
+
+/// The response to a write that isn't JSON or doesn't carry `serve_token`, if it's refused
+///
+/// Browsers send forms to any address without asking, but neither JSON bodies nor the
+/// `Authorization` header, so a web page can't record entries in the book.
+fn refuse_write(book: &Bookkeeper, request: &Request) -> Option<(u16, Value)> {
+    let header = |name: &str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.as_str().as_str().eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str())
+    };
+
+    let token = match &book.config().serve_token {
+        Some(token) => token,
+        None => {
+            let error = "set `serve_token` in config.toml to record entries";
+            return Some((403, json!({ "error": error })));
+        }
+    };
+    let given = header("Authorization").and_then(|value| value.strip_prefix("Bearer "));
+    if given != Some(token.as_str()) {
+        let error = "expected `Authorization: Bearer` with the `serve_token` of config.toml";
+        return Some((401, json!({ "error": error })));
+    }
+
+    let json = header("Content-Type")
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("application/json"));
+    if !json {
+        let error = "the body must be sent as `Content-Type: application/json`";
+        return Some((415, json!({ "error": error })));
+    }
+
+    None
+}
+
+/// Reads `month` from a URL, `current` being the current month, which must have a file
+fn existing_month(book: &Bookkeeper, month: &str) -> Result<BookkeepingFile> {
+    let month = match month {
+        "current" => BookkeepingFile::current_file(),
+        month => BookkeepingFile::from_file_name(month)
+            .ok_or_else(|| Error::InvalidMonth(month.to_owned()))?,
+    };
+
+    if book.path(&month).exists() {
+        Ok(month)
+    } else {
+        Err(Error::NoSuchMonth(month.as_str().to_owned()))
+    }
+}
+
+fn status_of(err: &Error) -> u16 {
+    match err.code() {
+        Code::NoSuchMonth => 404,
+        Code::InvalidMonth | Code::InvalidAmount | Code::InvalidCurrency | Code::Interchange => 400,
+        _ => 500,
+    }
+}
+
+fn error_body(err: &Error) -> Value {
+    json!({ "error": err.to_string(), "code": err.code().as_str() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{status_of, NewEntry};
+    use crate::Error;
+
+    #[test]
+    fn reads_new_entries_and_maps_errors() {
+        let new: NewEntry =
+            serde_json::from_str(r#"{"amount": "12.50", "description": "Coffee"}"#).unwrap();
+        assert_eq!(new.amount.to_string(), "12.50");
+        assert!(new.currency.is_none());
+        assert!(serde_json::from_str::<NewEntry>(r#"{"amount": "1", "desc": "x"}"#).is_err());
+
+        assert_eq!(status_of(&Error::NoSuchMonth("01-2024".into())), 404);
+        assert_eq!(status_of(&Error::InvalidMonth("2024".into())), 400);
+        assert_eq!(status_of(&Error::GitDisabled), 500);
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+use crate::{
+    config::Config,
+    export::Record,
+    hooks,
+    parser::{Entry, EntryType, Operation},
+    rules::auto_tag,
+    writer::Writer,
+    Error, Result,
+};
+
+const HELP: &str = "\
+    take [CURRENCY] AMOUNT DESCRIPTION
+    put [CURRENCY] AMOUNT DESCRIPTION
+    refund [CURRENCY] AMOUNT DESCRIPTION
+    list       show what will be recorded
+    undo       forget the last take, put or refund
+    redo       bring back what was undone
+    save       record everything and leave (same as end of input)
+    discard    leave without recording anything";
+
+/// Operations recorded during an interactive session, kept in memory until it ends
+#[derive(Default)]
+pub struct Session {
+    pending: Vec<Operation>,
+    /// Undone operations, the most recently undone last
+    undone: Vec<Operation>,
+}
+
+impl Session {
+    pub fn push(&mut self, operation: Operation) {
+        self.pending.push(operation);
+        // A new operation starts a new history, like in any editor
+        self.undone.clear();
+    }
+
+    pub fn undo(&mut self) -> Option<&Operation> {
+        let operation = self.pending.pop()?;
+        self.undone.push(operation);
+        self.undone.last()
+    }
+
+    pub fn redo(&mut self) -> Option<&Operation> {
+        let operation = self.undone.pop()?;
+        self.pending.push(operation);
+        self.pending.last()
+    }
+
+    pub fn pending(&self) -> &[Operation] {
+        &self.pending
+    }
+}
+
+/// Reads commands from `input` until `save`, `discard` or the end of input,
+/// then appends the session's operations to the bookkeeping file at `path`
+pub fn run_session(input: impl BufRead, path: &Path, day: u8, config: &Config) -> Result<()> {
+    let mut session = Session::default();
+    println!("Interactive session, nothing is recorded until you leave. Type `help` for commands.");
+
+    for line in input.lines() {
+        let line = line?;
+        let (command, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+
+        match command {
+            "" => {}
+            "take" | "put" | "refund" => {
+                let book_line = to_book_line(command, rest, day);
+
+                match Entry::from_str(&book_line, config.locale) {
+                    Ok(entry) => {
+                        let id = session.pending().len() + 1;
+                        session.push(tagged(Operation::from_entry(id, entry), config));
+                    }
+                    Err(err) => println!("Can't {}: {}", command, err),
+                }
+            }
+            "list" => {
+                for operation in session.pending() {
+                    print_operation(operation, config);
+                }
+            }
+            "undo" => match session.undo() {
+                Some(operation) => {
+                    print!("Undid ");
+                    print_operation(operation, config);
+                }
+                None => println!("Nothing to undo"),
+            },
+            "redo" => match session.redo() {
+                Some(operation) => {
+                    print!("Redid ");
+                    print_operation(operation, config);
+                }
+                None => println!("Nothing to redo"),
+            },
+            "save" => break,
+            "discard" => {
+                println!("Discarded {} operation(s)", session.pending().len());
+                return Ok(());
+            }
+            "help" => println!("{}", HELP),
+            _ => println!("Unknown command {:?}, type `help` for commands", command),
+        }
+
+        io::stdout().flush()?;
+    }
+
+    let entries: Vec<_> = session.pending().iter().map(Operation::as_entry).collect();
+    if !entries.is_empty() {
+        append(path, &entries, config)?;
+    }
+    crate::info!("Recorded {} operation(s)", entries.len());
+
+    Ok(())
+}
+
+/// Reads `take`, `put` and `refund` commands from `input`, one per line, and appends them all
+/// to the bookkeeping file at `path` at once, returning how many were recorded
+///
+/// Blank lines and lines starting with `#` are skipped. Nothing is recorded if any line is
+/// invalid.
+pub fn run_batch(input: impl BufRead, path: &Path, day: u8, config: &Config) -> Result<usize> {
+    let mut operations = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = |message: String| Error::InvalidBatchLine(idx + 1, message);
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        if !matches!(command, "take" | "put" | "refund") {
+            return Err(invalid(format!("unknown command {:?}", command)));
+        }
+
+        // Everything is parsed before writing, so a mistake in the last line doesn't leave
+        // the others recorded
+        let book_line = to_book_line(command, rest, day);
+        let entry =
+            Entry::from_str(&book_line, config.locale).map_err(|err| invalid(err.to_string()))?;
+        let id = operations.len() + 1;
+        operations.push(tagged(Operation::from_entry(id, entry), config));
+    }
+
+    let entries: Vec<_> = operations.iter().map(Operation::as_entry).collect();
+    if !entries.is_empty() {
+        append(path, &entries, config)?;
+    }
+
+    Ok(entries.len())
+}
+
+/// Reads a block of bookkeeping file lines from `input`, as in `05 - 12.50 Coffee`, and
+/// appends them all to the bookkeeping file at `path` at once, returning how many were recorded
+///
+/// Blank lines are skipped. Nothing is recorded if any line is invalid, and every invalid line
+/// is printed with its number.
+pub fn run_paste(input: impl BufRead, path: &Path, config: &Config) -> Result<usize> {
+    let mut operations = Vec::new();
+    let mut broken = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match Entry::from_str(&line, config.locale) {
+            Ok(entry) => {
+                let id = operations.len() + 1;
+                operations.push(tagged(Operation::from_entry(id, entry), config));
+            }
+            Err(err) => broken.push((idx + 1, err)),
+        }
+    }
+
+    if !broken.is_empty() {
+        for (line, err) in &broken {
+            println!("line {}: {}", line, err);
+        }
+        return Err(Error::BrokenLines(broken.len()));
+    }
+
+    let entries: Vec<_> = operations.iter().map(Operation::as_entry).collect();
+    if !entries.is_empty() {
+        append(path, &entries, config)?;
+    }
+
+    Ok(entries.len())
+}
+
+/// Appends `entries` to the bookkeeping file at `path` at once, then tells the hooks about them
+fn append(path: &Path, entries: &[Entry], config: &Config) -> Result<()> {
+    Writer::append_entries(path, entries, config)?;
+
+    // Bookkeeping files are named after their month
+    let month = path.file_name().unwrap_or_default().to_string_lossy();
+    let records: Vec<_> = entries
+        .iter()
+        .map(|entry| Record::from_entry(&month, entry))
+        .collect();
+    hooks::recorded(&records, config);
+
+    Ok(())
+}
+
+/// `operation`, with its description tagged by the config's rules
+fn tagged(mut operation: Operation, config: &Config) -> Operation {
+    if let Some(description) = auto_tag(config, &operation.description) {
+        operation.description = description;
+    }
+    operation
+}
+
+/// Turns `take`/`put`/`refund` and its arguments into a line of a bookkeeping file
+fn to_book_line(command: &str, rest: &str, day: u8) -> String {
+    let typ = match command {
+        "take" => EntryType::Debit,
+        "refund" => EntryType::Refund,
+        _ => EntryType::Credit,
+    };
+    format!("{} {} {}", day, typ.sign(), rest)
+}
+
+fn print_operation(operation: &Operation, config: &Config) {
+    let sign = operation.typ.sign();
+    let currency = operation.currency.as_deref().unwrap_or(&config.currency);
+
+    println!(
+        "{} {} {}",
+        sign,
+        config.format_amount_in(&operation.amount, currency),
+        operation.description
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{run_batch, run_paste, Session};
+    use crate::{
+        config::Config,
+        parser::{Entry, EntryType, Operation},
+        Error,
+    };
+
+    fn operation(description: &str) -> Operation {
+        Operation::from_entry(
+            1,
+            Entry::new(1, EntryType::Debit, BigDecimal::from(5), description),
+        )
+    }
+
+    fn descriptions(session: &Session) -> Vec<&str> {
+        session
+            .pending()
+            .iter()
+            .map(|operation| operation.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn undoes_and_redoes_operations() {
+        let mut session = Session::default();
+        session.push(operation("Coffee"));
+        session.push(operation("Lunch"));
+
+        assert_eq!(session.undo().unwrap().description, "Lunch");
+        assert_eq!(session.undo().unwrap().description, "Coffee");
+        assert!(session.undo().is_none());
+
+        assert_eq!(session.redo().unwrap().description, "Coffee");
+        assert_eq!(descriptions(&session), ["Coffee"]);
+
+        // Recording something new forgets what was undone
+        session.push(operation("Dinner"));
+        assert!(session.redo().is_none());
+        assert_eq!(descriptions(&session), ["Coffee", "Dinner"]);
+    }
+
+    #[test]
+    fn records_batches_all_or_nothing() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01-2024");
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+
+        let broken = "take 12.50 Coffee\nput 3000\n";
+        assert!(run_batch(broken.as_bytes(), &path, 5, &config).is_err());
+        assert!(!path.exists());
+
+        let batch = "# Catching up\ntake 12.50 Coffee\n\nput USD 30 Refund\n";
+        assert_eq!(run_batch(batch.as_bytes(), &path, 5, &config).unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "5 - 12.50 Coffee\n5 + USD 30 Refund\n"
+        );
+    }
+
+    #[test]
+    fn records_pasted_lines_all_or_nothing() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("01-2024");
+        let config = Config {
+            backups: 0,
+            ..Config::default()
+        };
+
+        let broken = "05 - 12.50 Coffee\n06 * 3 Lunch\n07 - 3\n";
+        assert!(matches!(
+            run_paste(broken.as_bytes(), &path, &config),
+            Err(Error::BrokenLines(2))
+        ));
+        assert!(!path.exists());
+
+        let pasted = "05 - 12.50 Coffee\n\n06 ~ 3 Coffee refund\n";
+        assert_eq!(run_paste(pasted.as_bytes(), &path, &config).unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "5 - 12.50 Coffee\n6 ~ 3 Coffee refund\n"
+        );
+    }
+}
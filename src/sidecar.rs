@@ -0,0 +1,50 @@
+use std::{path::Path, str};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    balances::BALANCES_FILE, bills::BILLS_FILE, checklist::CHECKLIST_FILE, config::Config, crypt,
+    debts::DEBTS_FILE, goals::GOALS_FILE, writer::Writer, Error, Result,
+};
+
+/// The files kept next to the bookkeeping files that `encrypt` encrypts along with them
+///
+/// The index isn't among them, as encrypted books aren't indexed.
+pub const FILES: [&str; 5] = [
+    BALANCES_FILE,
+    GOALS_FILE,
+    BILLS_FILE,
+    CHECKLIST_FILE,
+    DEBTS_FILE,
+];
+
+/// Reads the TOML file at `path`, such as the goals or the debts kept next to the bookkeeping
+/// files, or `T::default()` if there's none yet
+///
+/// It's decrypted like bookkeeping files are, and `invalid` builds the error for contents that
+/// aren't a `T`.
+pub fn load<T: DeserializeOwned + Default>(
+    path: &Path,
+    config: &Config,
+    invalid: impl Fn(String) -> Error,
+) -> Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let contents = crypt::read_file(path, config.cipher.as_ref())?;
+    toml::from_str(str::from_utf8(&contents)?).map_err(|err| invalid(err.to_string()))
+}
+
+/// Writes `value` to the TOML file at `path` the way bookkeeping files are rewritten: backed
+/// up, replaced in one go, and encrypted if the book is
+pub fn save<T: Serialize>(
+    path: &Path,
+    value: &T,
+    config: &Config,
+    invalid: impl Fn(String) -> Error,
+) -> Result<()> {
+    let contents = toml::to_string(value).map_err(|err| invalid(err.to_string()))?;
+
+    Writer::replace_contents(path, contents.into_bytes(), config)
+}
@@ -0,0 +1,175 @@
+use rusqlite::{limits::Limit, params, types::ValueRef, Batch, Connection};
+
+use crate::{
+    bookkeeper::Bookkeeper, currency::Converter, file::BookkeepingFile, rules::RuleSet,
+    status::plain_line, storage::Scanned, Error, Result,
+};
+
+/// The table every operation is loaded into, a row each
+const SCHEMA: &str = "CREATE TABLE ops (
+    date TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL,
+    day INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    amount NUMERIC NOT NULL,
+    currency TEXT NOT NULL,
+    description TEXT NOT NULL,
+    category TEXT
+)";
+
+/// What a statement returned, every value as text
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Runs `statement` over every operation of `book`, archived ones included, loaded into an
+/// in-memory table called `ops`
+///
+/// Amounts are converted into the default currency, and dates are `YYYY-MM-DD`, so they sort.
+/// Only a single statement is run, and it can't attach other databases.
+pub fn query(book: &Bookkeeper, statement: &str) -> Result<Table> {
+    let conn = Connection::open_in_memory()?;
+    conn.execute_batch(SCHEMA)?;
+    load(&conn, book)?;
+    // Otherwise a statement could write to any file the user can
+    conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+
+    let invalid = |err: rusqlite::Error| Error::Sql(err.to_string());
+    let mut batch = Batch::new(&conn, statement);
+    let mut statement = batch
+        .next()
+        .map_err(invalid)?
+        .ok_or_else(|| Error::Sql("there's no statement to run".to_owned()))?;
+    if batch.next().map_err(invalid)?.is_some() {
+        return Err(Error::Sql("only a single statement can be run".to_owned()));
+    }
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+
+    let mut rows = Vec::new();
+    let mut results = statement.query([]).map_err(invalid)?;
+    while let Some(row) = results.next().map_err(invalid)? {
+        let values = (0..columns.len())
+            .map(|index| row.get_ref(index).map(text))
+            .collect::<rusqlite::Result<_>>()?;
+        rows.push(values);
+    }
+
+    Ok(Table { columns, rows })
+}
+
+fn load(conn: &Connection, book: &Bookkeeper) -> Result<()> {
+    let config = book.config();
+    let converter = Converter::new(config);
+    let rules = RuleSet::from_config(config);
+
+    let transaction = conn.unchecked_transaction()?;
+    let mut insert = transaction.prepare(
+        "INSERT INTO ops (date, year, month, day, kind, amount, currency, description, category)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+    let mut current = BookkeepingFile::current_file();
+
+    book.scan(&mut |scanned| {
+        match scanned {
+            Scanned::Month(month) => current = month,
+            Scanned::Entry(entry) => {
+                let amount = converter.convert(entry.amount, entry.currency)?;
+                insert.execute(params![
+                    format!("{}-{:02}-{:02}", current.year(), current.month(), entry.day),
+                    current.year(),
+                    current.month(),
+                    entry.day,
+                    entry.typ.name(),
+                    amount.to_string(),
+                    entry.currency.unwrap_or(&config.currency),
+                    entry.description,
+                    rules.categorize(entry.description),
+                ])?;
+            }
+        }
+
+        Ok(())
+    })?;
+    drop(insert);
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// `value` as printed, with reals rounded so that sums of cents don't show float noise
+fn text(value: ValueRef) -> String {
+    match value {
+        ValueRef::Null => String::new(),
+        ValueRef::Integer(integer) => integer.to_string(),
+        ValueRef::Real(real) => {
+            let rounded = format!("{:.6}", real);
+            rounded
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_owned()
+        }
+        ValueRef::Text(text) | ValueRef::Blob(text) => String::from_utf8_lossy(text).into_owned(),
+    }
+}
+
+impl Table {
+    /// Prints the column names, then every row, tab-separated, with tabs and line breaks in
+    /// values turned into spaces
+    pub fn print(&self) {
+        let print = |fields: &[String]| {
+            let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+            println!("{}", plain_line(&fields));
+        };
+
+        print(&self.columns);
+        for row in &self.rows {
+            print(row);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::query;
+    use crate::{bookkeeper::Bookkeeper, config::Config};
+
+    #[test]
+    fn queries_every_month() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("12-2023"), "03 - 0.10 Uber\n").unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 5000 Salary\n06 - 0.20 Uber\n07 - 40 Groceries #food\n",
+        )
+        .unwrap();
+        let book = Bookkeeper::open(dir.path(), Config::default()).unwrap();
+
+        let table = query(
+            &book,
+            "SELECT description, SUM(amount) FROM ops WHERE kind = 'take'
+             GROUP BY 1 ORDER BY 2 DESC",
+        )
+        .unwrap();
+        assert_eq!(table.columns, ["description", "SUM(amount)"]);
+        assert_eq!(table.rows, [["Groceries #food", "40"], ["Uber", "0.3"]]);
+
+        let table = query(&book, "SELECT date, category FROM ops WHERE day = 7").unwrap();
+        assert_eq!(table.rows, [["2024-01-07", "food"]]);
+
+        assert!(query(&book, "SELECT nothing FROM nowhere").is_err());
+        assert!(query(&book, "SELECT 1; SELECT 2").is_err());
+        assert!(query(&book, "SELECT 1; -- the end").is_ok());
+        let attach = format!("ATTACH '{}' AS other", dir.path().join("db").display());
+        assert!(query(&book, &attach).is_err());
+        assert!(!dir.path().join("db").exists());
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use bigdecimal::BigDecimal;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+
+use crate::{
+    config::Config,
+    file::BookkeepingFile,
+    parser::{Entry, EntryType, Operation},
+    storage::{Scanned, Storage},
+    writer::write_line,
+    Error, Result,
+};
+
+/// The database kept in the data folder when `storage = "sqlite"`
+pub const DATABASE_FILE: &str = "book.sqlite3";
+
+/// Where the book is kept
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    /// A plain text file per month
+    #[default]
+    Files,
+    /// A single SQLite database, quicker to query over years of entries
+    Sqlite,
+}
+
+/// A book kept in an SQLite database, with a row per entry
+///
+/// Entries keep their position in the month, so they're numbered like in bookkeeping files.
+pub struct Database {
+    conn: Connection,
+    path: PathBuf,
+}
+
+impl Database {
+    /// Opens the database in `dir`, creating it if needed
+    pub fn open(dir: &Path) -> Result<Self> {
+        let path = dir.join(DATABASE_FILE);
+        let conn = Connection::open(&path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS operations (
+                year INTEGER NOT NULL,
+                month INTEGER NOT NULL,
+                line INTEGER NOT NULL,
+                day INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                currency TEXT,
+                description TEXT NOT NULL,
+                PRIMARY KEY (year, month, line)
+            )",
+        )?;
+
+        Ok(Self { conn, path })
+    }
+}
+
+impl Storage for Database {
+    fn months(&self) -> Result<Vec<BookkeepingFile>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT DISTINCT year, month FROM operations ORDER BY year, month")?;
+        let months = statement
+            .query_map([], |row| Ok(BookkeepingFile::new(row.get(1)?, row.get(0)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(months)
+    }
+
+    fn load(&self, month: &BookkeepingFile, _: &Config) -> Result<Option<Vec<Operation>>> {
+        let mut statement = self.conn.prepare(
+            "SELECT line, day, kind, amount, currency, description FROM operations
+             WHERE year = ?1 AND month = ?2 ORDER BY line",
+        )?;
+        let rows = statement
+            .query_map(params![month.year(), month.month()], |row| {
+                Ok((
+                    row.get::<_, usize>(0)?,
+                    row.get::<_, u8>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, String>(5)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if rows.is_empty() {
+            return Ok(None);
+        }
+
+        rows.into_iter()
+            .map(|(id, day, kind, amount, currency, description)| {
+                Ok(Operation {
+                    id,
+                    day,
+                    typ: parse_kind(&kind)?,
+                    amount: parse_amount(&amount)?,
+                    currency,
+                    description,
+                })
+            })
+            .collect::<Result<_>>()
+            .map(Some)
+    }
+
+    fn append(&self, month: &BookkeepingFile, entry: Entry, config: &Config) -> Result<()> {
+        if config.dry_run {
+            println!("Would record into {} of the database:", month.as_str());
+            print!("+{}", line_of(&entry)?);
+            return Ok(());
+        }
+
+        let last: Option<usize> = self
+            .conn
+            .query_row(
+                "SELECT MAX(line) FROM operations WHERE year = ?1 AND month = ?2",
+                params![month.year(), month.month()],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+
+        let operation = Operation::from_entry(last.unwrap_or(0) + 1, entry);
+        insert(&self.conn, month, &operation)
+    }
+
+    fn rewrite(
+        &self,
+        month: &BookkeepingFile,
+        operations: &[Operation],
+        config: &Config,
+    ) -> Result<()> {
+        if config.dry_run {
+            println!("Would rewrite {} of the database as:", month.as_str());
+            for operation in operations {
+                print!("{}", line_of(&operation.as_entry())?);
+            }
+            return Ok(());
+        }
+
+        let transaction = self.conn.unchecked_transaction()?;
+        transaction.execute(
+            "DELETE FROM operations WHERE year = ?1 AND month = ?2",
+            params![month.year(), month.month()],
+        )?;
+        for (id, operation) in (1..).zip(operations) {
+            let operation = Operation {
+                id,
+                ..operation.clone()
+            };
+            insert(&transaction, month, &operation)?;
+        }
+
+        Ok(transaction.commit()?)
+    }
+
+    // Every entry is read with a single query, instead of one per month
+    fn scan(&self, _: &Config, each: &mut dyn FnMut(Scanned) -> Result<()>) -> Result<()> {
+        let mut statement = self.conn.prepare(
+            "SELECT year, month, day, kind, amount, currency, description FROM operations
+             ORDER BY year, month, line",
+        )?;
+        let mut rows = statement.query([])?;
+        let mut last = None;
+
+        while let Some(row) = rows.next()? {
+            let month = (row.get::<_, i32>(0)?, row.get::<_, u32>(1)?);
+            if last != Some(month) {
+                each(Scanned::Month(BookkeepingFile::new(month.1, month.0)))?;
+                last = Some(month);
+            }
+
+            let amount: String = row.get(4)?;
+            let currency: Option<String> = row.get(5)?;
+            let description: String = row.get(6)?;
+            each(Scanned::Entry(Entry {
+                day: row.get(2)?,
+                typ: parse_kind(&row.get::<_, String>(3)?)?,
+                amount: parse_amount(&amount)?,
+                currency: currency.as_deref(),
+                description: &description,
+            }))?;
+        }
+
+        Ok(())
+    }
+
+    fn location(&self, _: &BookkeepingFile) -> PathBuf {
+        self.path.clone()
+    }
+
+    // A single row is updated, instead of rewriting the whole month
+    fn edit(
+        &self,
+        month: &BookkeepingFile,
+        id: usize,
+        config: &Config,
+        edit: &mut dyn FnMut(&mut Operation) -> Result<()>,
+    ) -> Result<()> {
+        let mut operation = self
+            .load(month, config)?
+            .unwrap_or_default()
+            .into_iter()
+            .find(|operation| operation.id == id)
+            .ok_or(Error::NoSuchEntry(id))?;
+        edit(&mut operation)?;
+
+        if config.dry_run {
+            println!(
+                "Would change #{} of {} in the database into:",
+                id,
+                month.as_str()
+            );
+            print!("{}", line_of(&operation.as_entry())?);
+            return Ok(());
+        }
+
+        self.conn.execute(
+            "UPDATE operations SET day = ?4, kind = ?5, amount = ?6, currency = ?7,
+             description = ?8 WHERE year = ?1 AND month = ?2 AND line = ?3",
+            params![
+                month.year(),
+                month.month(),
+                id,
+                operation.day,
+                operation.typ.name(),
+                operation.amount.to_string(),
+                operation.currency,
+                operation.description,
+            ],
+        )?;
+
+        Ok(())
+    }
+}
+
+fn insert(conn: &Connection, month: &BookkeepingFile, operation: &Operation) -> Result<()> {
+    conn.execute(
+        "INSERT INTO operations (year, month, line, day, kind, amount, currency, description)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            month.year(),
+            month.month(),
+            operation.id,
+            operation.day,
+            operation.typ.name(),
+            operation.amount.to_string(),
+            operation.currency,
+            operation.description,
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// `entry` as it'd be written in a bookkeeping file, shown in dry runs
+fn line_of(entry: &Entry) -> Result<String> {
+    let mut line = Vec::new();
+    write_line(&mut line, entry)?;
+
+    Ok(String::from_utf8_lossy(&line).into_owned())
+}
+
+fn parse_amount(amount: &str) -> Result<BigDecimal> {
+    BigDecimal::from_str(amount)
+        .map_err(|_| Error::Database(format!("'{}' is not an amount", amount)))
+}
+
+fn parse_kind(kind: &str) -> Result<EntryType> {
+    match kind {
+        "take" => Ok(EntryType::Debit),
+        "put" => Ok(EntryType::Credit),
+        "refund" => Ok(EntryType::Refund),
+        _ => Err(Error::Database(format!(
+            "'{}' is not a kind of entry",
+            kind
+        ))),
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::Database(err.to_string())
+    }
+}
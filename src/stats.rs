@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::{
+    config::Config,
+    currency::Converter,
+    file::{BookkeepingFile, StoredFile},
+    i18n::{t, tf},
+    parser::{Entry, EntryType},
+    reader::Reader,
+    rules::RuleSet,
+    Result, Total,
+};
+
+/// The totals of a single bookkeeping file
+pub struct MonthTotal {
+    pub file: BookkeepingFile,
+    pub total: Total,
+}
+
+/// Statistics aggregated over a set of bookkeeping files
+pub struct Stats {
+    /// Totals for every month, in chronological order
+    pub months: Vec<MonthTotal>,
+    /// Cumulative amount spent per category, or per description for uncategorized entries
+    pub spending: BTreeMap<String, BigDecimal>,
+    /// The amount of every take, converted into the default currency
+    pub takes: Vec<BigDecimal>,
+    /// How many puts were recorded
+    pub puts: usize,
+    /// How many refunds were recorded
+    pub refunds: usize,
+}
+
+/// Builds `Stats` an entry at a time, so that entries needn't be kept around to be counted
+pub struct Tally<'a> {
+    rules: RuleSet<'a>,
+    converter: Converter<'a>,
+    stats: Stats,
+}
+
+impl<'a> Tally<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self {
+            rules: RuleSet::from_config(config),
+            converter: Converter::new(config),
+            stats: Stats {
+                months: Vec::new(),
+                spending: BTreeMap::new(),
+                takes: Vec::new(),
+                puts: 0,
+                refunds: 0,
+            },
+        }
+    }
+
+    /// Starts counting `file`, which must come after the months counted before
+    pub fn month(&mut self, file: BookkeepingFile) {
+        self.stats.months.push(MonthTotal {
+            file,
+            total: Total::zero(),
+        });
+    }
+
+    /// Counts `entry` in the last month started
+    pub fn add(&mut self, entry: Entry) -> Result<()> {
+        let stats = &mut self.stats;
+        let total = &mut stats
+            .months
+            .last_mut()
+            .expect("entries are counted in a month")
+            .total;
+        let amount = self.converter.convert(entry.amount, entry.currency)?;
+
+        match entry.typ {
+            EntryType::Debit => stats.takes.push(amount.clone()),
+            EntryType::Credit => stats.puts += 1,
+            EntryType::Refund => stats.refunds += 1,
+        }
+
+        match entry.typ {
+            EntryType::Debit | EntryType::Refund => {
+                // Refunds offset the spending of their category
+                let amount = if entry.typ == EntryType::Refund {
+                    -amount
+                } else {
+                    amount
+                };
+                let key = self
+                    .rules
+                    .categorize(entry.description)
+                    .unwrap_or(entry.description);
+                *stats
+                    .spending
+                    .entry(key.to_owned())
+                    .or_insert_with(BigDecimal::zero) += &amount;
+                total.outgoing += amount;
+            }
+            EntryType::Credit => total.incoming += amount,
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> Stats {
+        self.stats
+    }
+}
+
+/// The sum of the totals of `months`
+pub fn lifetime_total(months: &[MonthTotal]) -> Total {
+    let mut lifetime = Total::zero();
+
+    for MonthTotal { total, .. } in months {
+        lifetime.incoming += &total.incoming;
+        lifetime.outgoing += &total.outgoing;
+    }
+
+    lifetime
+}
+
+/// How the amounts of takes are spread
+#[derive(Debug, PartialEq, Eq)]
+pub struct Distribution {
+    pub min: BigDecimal,
+    pub max: BigDecimal,
+    pub mean: BigDecimal,
+    pub median: BigDecimal,
+    /// The population standard deviation
+    pub std_dev: BigDecimal,
+}
+
+impl Stats {
+    /// Reads every file in `files`, which are expected to be sorted chronologically
+    pub fn from_files(files: Vec<StoredFile>, config: &Config) -> Result<Self> {
+        let mut reader = Reader::new(config);
+        let mut tally = Tally::new(config);
+
+        for stored in files {
+            tally.month(stored.file.clone());
+            reader.for_each_entry_in(stored.open()?, |entry| tally.add(entry))?;
+        }
+
+        Ok(tally.finish())
+    }
+
+    /// How the amounts of takes are spread, if there's any
+    pub fn take_distribution(&self) -> Option<Distribution> {
+        if self.takes.is_empty() {
+            return None;
+        }
+        let mut takes = self.takes.clone();
+        takes.sort();
+        let count = BigDecimal::from(takes.len() as u64);
+
+        let sum: BigDecimal = takes.iter().sum();
+        let mean = sum / &count;
+
+        let middle = takes.len() / 2;
+        let median = if takes.len().is_multiple_of(2) {
+            (&takes[middle - 1] + &takes[middle]) / BigDecimal::from(2)
+        } else {
+            takes[middle].clone()
+        };
+
+        let variance = takes
+            .iter()
+            .map(|take| {
+                let deviation = take - &mean;
+                &deviation * &deviation
+            })
+            .sum::<BigDecimal>()
+            / &count;
+
+        Some(Distribution {
+            min: takes.first()?.clone(),
+            max: takes.last()?.clone(),
+            mean: mean.with_scale(2),
+            median: median.with_scale(2),
+            std_dev: variance.sqrt()?.with_scale(2),
+        })
+    }
+
+    /// The sum of the totals of every month
+    pub fn lifetime_total(&self) -> Total {
+        lifetime_total(&self.months)
+    }
+
+    /// How much was saved per month, on average
+    pub fn average_monthly_savings(&self) -> BigDecimal {
+        if self.months.is_empty() {
+            return BigDecimal::zero();
+        }
+
+        let months = BigDecimal::from(self.months.len() as u64);
+
+        (self.lifetime_total().balance() / months).with_scale(2)
+    }
+
+    /// The month with the highest balance
+    pub fn best_month(&self) -> Option<&MonthTotal> {
+        self.months.iter().max_by_key(|month| month.total.balance())
+    }
+
+    /// The month with the lowest balance
+    pub fn worst_month(&self) -> Option<&MonthTotal> {
+        self.months.iter().min_by_key(|month| month.total.balance())
+    }
+
+    /// The longest run of consecutive months in which outgoing didn't exceed incoming
+    pub fn longest_under_budget_streak(&self) -> usize {
+        let mut longest = 0;
+        let mut current = 0;
+
+        for MonthTotal { total, .. } in &self.months {
+            if total.outgoing <= total.incoming {
+                current += 1;
+                longest = longest.max(current);
+            } else {
+                current = 0;
+            }
+        }
+
+        longest
+    }
+
+    /// Cumulative spending per category, biggest first
+    pub fn spending_by_category(&self) -> Vec<(&str, &BigDecimal)> {
+        let mut spending: Vec<_> = self
+            .spending
+            .iter()
+            .map(|(category, amount)| (category.as_str(), amount))
+            .collect();
+
+        spending.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        spending
+    }
+
+    /// How much `category` may take of the income, if it has a budget
+    pub fn budget(&self, category: &str, config: &Config) -> Option<BigDecimal> {
+        let percent = config.budgets.get(category)?;
+
+        Some((self.lifetime_total().incoming * percent / BigDecimal::from(100)).with_scale(2))
+    }
+
+    pub fn print(&self, config: &Config) {
+        let lifetime = self.lifetime_total();
+
+        let row = |name, amount: &BigDecimal| {
+            println!("\t{}: {}", t(name), config.format_amount(amount));
+        };
+
+        println!(
+            "{}",
+            tf("Statistics for {} month(s)", &[&self.months.len()])
+        );
+        row("Incoming", &lifetime.incoming);
+        row("Outgoing", &lifetime.outgoing);
+        row("Balance", &lifetime.balance());
+        if let Some(rate) = lifetime.savings_rate() {
+            println!("\t{}", tf("Saved {}% of the income", &[&rate]));
+        }
+        row("Average monthly savings", &self.average_monthly_savings());
+
+        if let (Some(best), Some(worst)) = (self.best_month(), self.worst_month()) {
+            for (name, month) in [("Best month", best), ("Worst month", worst)] {
+                println!(
+                    "\t{}: {} ({})",
+                    t(name),
+                    month.file.label(),
+                    config.format_amount(&month.total.balance())
+                );
+            }
+        }
+
+        println!(
+            "\t{}",
+            tf(
+                "Longest under-budget streak: {} month(s)",
+                &[&self.longest_under_budget_streak()]
+            )
+        );
+        println!(
+            "\t{}",
+            tf(
+                "Entries: {} take(s), {} put(s), {} refund(s)",
+                &[&self.takes.len(), &self.puts, &self.refunds]
+            )
+        );
+
+        if let Some(distribution) = self.take_distribution() {
+            println!("{}", t("Amount of takes"));
+            row("Smallest", &distribution.min);
+            row("Largest", &distribution.max);
+            row("Mean", &distribution.mean);
+            row("Median", &distribution.median);
+            row("Standard deviation", &distribution.std_dev);
+        }
+
+        println!("{}", t("Spending by category"));
+        for (category, amount) in self.spending_by_category() {
+            let budget = self
+                .budget(category, config)
+                .map(|budget| tf(" of {} budgeted", &[&config.format_amount(&budget)]))
+                .unwrap_or_default();
+            println!("\t{}: {}{}", category, config.format_amount(amount), budget);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use crate::{archive::list_stored_files, config::Config};
+
+    use super::{Distribution, Stats};
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn computes_all_time_stats() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("12-2023"),
+            "05 + 100.00 Salary\n10 - 150.00 Rent\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 300.00 Salary\n10 - 150.00 Rent\n12 - 20.00 Lunch\n14 ~ 5.00 Lunch refund\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("02-2024"), "05 + 200.00 Salary\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a bookkeeping file").unwrap();
+
+        let files = list_stored_files(dir.path()).unwrap();
+        let config: Config = toml::from_str(
+            r#"
+            [budgets]
+            Food = "10"
+
+            [[rules]]
+            pattern = "lunch"
+            category = "Food"
+            "#,
+        )
+        .unwrap();
+        let stats = Stats::from_files(files, &config).unwrap();
+
+        let lifetime = stats.lifetime_total();
+        assert_eq!(lifetime.incoming, decimal("600"));
+        assert_eq!(lifetime.outgoing, decimal("315"));
+        assert_eq!(lifetime.savings_rate(), Some(decimal("47.5")));
+        assert_eq!(stats.average_monthly_savings(), decimal("95"));
+        assert_eq!(stats.best_month().unwrap().file.as_str(), "02-2024");
+        assert_eq!(stats.worst_month().unwrap().file.as_str(), "12-2023");
+        assert_eq!(stats.longest_under_budget_streak(), 2);
+        assert_eq!(
+            stats.spending_by_category(),
+            [("Rent", &decimal("300")), ("Food", &decimal("15"))]
+        );
+        assert_eq!(stats.budget("Food", &config), Some(decimal("60")));
+        assert_eq!(stats.budget("Rent", &config), None);
+
+        assert_eq!((stats.takes.len(), stats.puts, stats.refunds), (3, 3, 1));
+        assert_eq!(
+            stats.take_distribution(),
+            Some(Distribution {
+                min: decimal("20"),
+                max: decimal("150"),
+                mean: decimal("106.66"),
+                median: decimal("150"),
+                std_dev: decimal("61.28"),
+            })
+        );
+    }
+}
@@ -0,0 +1,1101 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    io::{self, Write},
+    path::Path,
+};
+
+use bigdecimal::{BigDecimal, Zero};
+use clap::ArgEnum;
+
+use crate::{
+    balances::OpeningBalances,
+    color::Style,
+    config::Config,
+    currency::Converter,
+    dates::cycle_day,
+    diagnostics::Code,
+    file::BookkeepingFile,
+    i18n::{t, tf},
+    parser::{EntryType, Operation},
+    reader::Reader,
+    rules::{person_tag, RuleSet},
+    Result, Total,
+};
+
+/// The state of a month's bookkeeping file
+pub struct Status {
+    pub file: BookkeepingFile,
+    /// Currency every amount was converted into
+    pub currency: String,
+    pub total: Total,
+    /// The cash on hand when the month started, if it was set or carried over by `close`
+    pub opening_balance: Option<BigDecimal>,
+    /// Days of the month gone by, counting today
+    pub elapsed_days: u8,
+    /// Every entry of the month by day, then in the order they were recorded, along with the
+    /// balance right after it
+    pub operations: Vec<(Operation, BigDecimal)>,
+    /// How much was spent in the previous month up to the same day, if it was recorded
+    pub previous_outgoing: Option<(BookkeepingFile, BigDecimal)>,
+}
+
+impl Status {
+    /// Computes the status of `file`, comparing it to the previous month up to `day`
+    pub fn compute(
+        dir: &Path,
+        file: BookkeepingFile,
+        day: u8,
+        config: &Config,
+        converter: &Converter,
+    ) -> Result<Self> {
+        let mut reader = Reader::new(config);
+        let operations = reader.operations(dir.join(file.as_path()))?;
+        let previous_path = dir.join(file.previous().as_path());
+        let previous = if previous_path.exists() {
+            Some(reader.operations(previous_path)?)
+        } else {
+            None
+        };
+        let opening_balance = OpeningBalances::load(dir, config)?.get(&file).cloned();
+
+        Self::from_operations(file, operations, previous, opening_balance, day, converter)
+    }
+
+    /// Computes the status of `file` from its `operations`, comparing it to the `previous`
+    /// month's up to `day`, if it was recorded
+    pub fn from_operations(
+        file: BookkeepingFile,
+        mut operations: Vec<Operation>,
+        previous: Option<Vec<Operation>>,
+        opening_balance: Option<BigDecimal>,
+        day: u8,
+        converter: &Converter,
+    ) -> Result<Self> {
+        let total = total_until_day(&file, &operations, u8::MAX, converter)?;
+        // Entries can be recorded out of order, e.g. when catching up on a receipt
+        operations.sort_by_key(|operation| (cycle_day(&file, operation.day), operation.id));
+
+        let mut balance = BigDecimal::zero();
+        let operations = operations
+            .into_iter()
+            .map(|operation| {
+                let amount =
+                    converter.convert(operation.amount.clone(), operation.currency.as_deref())?;
+                match operation.typ {
+                    EntryType::Debit => balance -= amount,
+                    EntryType::Credit | EntryType::Refund => balance += amount,
+                }
+                Ok((operation, balance.clone()))
+            })
+            .collect::<Result<_>>()?;
+
+        // Past the end of the month, the whole previous one is compared, however long it was
+        let point = if day > file.days() {
+            u8::MAX
+        } else {
+            cycle_day(&file, day)
+        };
+        let elapsed_days = point.clamp(1, file.days());
+        let previous_outgoing = match previous {
+            Some(previous) => {
+                let partial = total_until_day(&file.previous(), &previous, point, converter)?;
+                Some((file.previous(), partial.outgoing))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            opening_balance,
+            elapsed_days,
+            file,
+            currency: converter.target().to_owned(),
+            total,
+            operations,
+            previous_outgoing,
+        })
+    }
+
+    /// Reorders the operations by `key`, keeping them by day where it's a tie
+    ///
+    /// Each one keeps the balance it had by day. Amounts are compared as written, without
+    /// converting them, and regardless of the kind of the entry.
+    pub fn sort(&mut self, key: SortKey, descending: bool) {
+        let file = &self.file;
+        self.operations.sort_by(|(a, _), (b, _)| {
+            let ordering = match key {
+                SortKey::Day => cycle_day(file, a.day).cmp(&cycle_day(file, b.day)),
+                SortKey::Amount => a.amount.cmp(&b.amount),
+                SortKey::Description => a
+                    .description
+                    .to_lowercase()
+                    .cmp(&b.description.to_lowercase()),
+            };
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    /// The operations merged by `by`, the ones that moved the most money first
+    pub fn groups(
+        &self,
+        by: GroupBy,
+        config: &Config,
+        converter: &Converter,
+    ) -> Result<Vec<Group>> {
+        let rules = RuleSet::from_config(config);
+        let mut groups: Vec<Group> = Vec::new();
+        // Index of each group in `groups`, by the normalized name
+        let mut indexes = HashMap::new();
+
+        for (operation, _) in &self.operations {
+            let name = match by {
+                GroupBy::Description => &operation.description,
+                GroupBy::Category => rules
+                    .categorize(&operation.description)
+                    .unwrap_or(&operation.description),
+            };
+            let amount =
+                converter.convert(operation.amount.clone(), operation.currency.as_deref())?;
+            let amount = match operation.typ {
+                EntryType::Debit => -amount,
+                EntryType::Credit | EntryType::Refund => amount,
+            };
+
+            let idx = *indexes
+                .entry(config.matching.normalize(name))
+                .or_insert_with(|| {
+                    groups.push(Group {
+                        name: name.to_owned(),
+                        count: 0,
+                        total: BigDecimal::zero(),
+                    });
+                    groups.len() - 1
+                });
+            groups[idx].count += 1;
+            groups[idx].total += amount;
+        }
+
+        // Stable, so groups of the same size stay in the order they first appeared
+        groups.sort_by_key(|group| Reverse(group.total.abs()));
+        Ok(groups)
+    }
+
+    /// Prints `groups`, made by `by`, as a table following the summary
+    pub fn print_groups(&self, groups: &[Group], by: GroupBy, config: &Config, format: Format) {
+        let mut out = io::stdout().lock();
+        let result = match format {
+            Format::Text => self.render_groups(groups, by, config, &mut out),
+            Format::Markdown => self.render_groups_markdown(groups, by, config, &mut out),
+            Format::Plain => groups.iter().try_for_each(|group| {
+                let fields = [
+                    group.name.as_str(),
+                    &group.count.to_string(),
+                    &group.total.to_string(),
+                ];
+                writeln!(out, "{}", plain_line(&fields))
+            }),
+        };
+
+        if let Err(err) = result {
+            if err.kind() != io::ErrorKind::BrokenPipe {
+                eprintln!("Failed to print the status: {}", err);
+            }
+        }
+    }
+
+    pub fn render_groups(
+        &self,
+        groups: &[Group],
+        by: GroupBy,
+        config: &Config,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        writeln!(out, "{}", tf("Operations by {}", &[&t(by.as_str())]))?;
+        for group in groups {
+            let amount = format!(
+                "{} {:>14}",
+                group.sign(),
+                config.format_amount_in(&group.total.abs(), &self.currency)
+            );
+            let style = if group.total < BigDecimal::zero() {
+                Style::Red
+            } else {
+                Style::Green
+            };
+
+            writeln!(
+                out,
+                "\t{:>3}x {}  {}",
+                group.count,
+                style.paint(amount, config.color),
+                group.name
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn render_groups_markdown(
+        &self,
+        groups: &[Group],
+        by: GroupBy,
+        config: &Config,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        writeln!(out)?;
+        writeln!(out, "### {}", tf("Operations by {}", &[&t(by.as_str())]))?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "| {} | | {} | {} |",
+            t("Count"),
+            t("Amount"),
+            t(by.title())
+        )?;
+        writeln!(out, "|---:|:---:|---:|---|")?;
+        for group in groups {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                group.count,
+                group.sign(),
+                config.format_amount_in(&group.total.abs(), &self.currency),
+                group.name.replace('|', "\\|")
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// How much each person put in and spent, by the `@person` their entries are attributed
+    /// to, the ones who spent the most first
+    ///
+    /// Entries attributed to no one are summed last, as shared.
+    pub fn shares(&self, config: &Config, converter: &Converter) -> Result<Vec<Share>> {
+        let mut shares: Vec<Share> = Vec::new();
+        let mut shared = Share::named(t("(shared)"));
+        // Index of each person in `shares`, by the normalized name
+        let mut indexes = HashMap::new();
+
+        for (operation, _) in &self.operations {
+            let share = match person_tag(&operation.description) {
+                Some(person) => {
+                    let idx = *indexes
+                        .entry(config.matching.normalize(person))
+                        .or_insert_with(|| {
+                            shares.push(Share::named(person));
+                            shares.len() - 1
+                        });
+                    &mut shares[idx]
+                }
+                None => &mut shared,
+            };
+
+            let amount =
+                converter.convert(operation.amount.clone(), operation.currency.as_deref())?;
+            match operation.typ {
+                EntryType::Debit => share.outgoing += amount,
+                EntryType::Credit => share.incoming += amount,
+                EntryType::Refund => share.outgoing -= amount,
+            }
+        }
+
+        shares.sort_by(|a, b| b.outgoing.cmp(&a.outgoing));
+        if !shared.incoming.is_zero() || !shared.outgoing.is_zero() {
+            shares.push(shared);
+        }
+        Ok(shares)
+    }
+
+    /// Prints `shares` as a table following the summary
+    pub fn print_shares(&self, shares: &[Share], config: &Config, format: Format) {
+        let mut out = io::stdout().lock();
+        let result = match format {
+            Format::Text => self.render_shares(shares, config, &mut out),
+            Format::Markdown => self.render_shares_markdown(shares, config, &mut out),
+            Format::Plain => shares.iter().try_for_each(|share| {
+                let fields = [
+                    share.name.as_str(),
+                    &share.incoming.to_string(),
+                    &share.outgoing.to_string(),
+                ];
+                writeln!(out, "{}", plain_line(&fields))
+            }),
+        };
+
+        if let Err(err) = result {
+            if err.kind() != io::ErrorKind::BrokenPipe {
+                eprintln!("Failed to print the status: {}", err);
+            }
+        }
+    }
+
+    pub fn render_shares(
+        &self,
+        shares: &[Share],
+        config: &Config,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        writeln!(out, "{}", t("By person"))?;
+        for share in shares {
+            writeln!(
+                out,
+                "\t{}: {}",
+                share.name,
+                tf(
+                    "put in {}, spent {}",
+                    &[
+                        &config.format_amount_in(&share.incoming, &self.currency),
+                        &config.format_amount_in(&share.outgoing, &self.currency),
+                    ]
+                )
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn render_shares_markdown(
+        &self,
+        shares: &[Share],
+        config: &Config,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        writeln!(out)?;
+        writeln!(out, "### {}", t("By person"))?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "| {} | {} | {} |",
+            t("Person"),
+            t("Put in"),
+            t("Spent")
+        )?;
+        writeln!(out, "|---|---:|---:|")?;
+        for share in shares {
+            writeln!(
+                out,
+                "| {} | {} | {} |",
+                share.name.replace('|', "\\|"),
+                config.format_amount_in(&share.incoming, &self.currency),
+                config.format_amount_in(&share.outgoing, &self.currency)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// The cash on hand now, if the month has an opening balance
+    pub fn cash_on_hand(&self) -> Option<BigDecimal> {
+        Some(self.opening_balance.as_ref()? + self.total.balance())
+    }
+
+    /// How much was spent per elapsed day, on average
+    pub fn daily_outgoing(&self) -> BigDecimal {
+        (&self.total.outgoing / BigDecimal::from(self.elapsed_days)).with_scale(2)
+    }
+
+    /// How much was received per elapsed day, on average
+    pub fn daily_incoming(&self) -> BigDecimal {
+        (&self.total.incoming / BigDecimal::from(self.elapsed_days)).with_scale(2)
+    }
+
+    /// Things worth pointing out about the month, to be reported through `Diagnostics`
+    pub fn warnings(&self, config: &Config) -> Vec<(Code, String)> {
+        let format = |amount: &BigDecimal| config.format_amount_in(amount, &self.currency);
+        let mut warnings = Vec::new();
+
+        if self.total.outgoing > self.total.incoming {
+            warnings.push((
+                Code::Overspent,
+                tf(
+                    "Spent {} more than received in {}",
+                    &[&format(&-self.total.balance()), &self.file.month_name()],
+                ),
+            ));
+        }
+
+        if let Some((previous, outgoing)) = &self.previous_outgoing {
+            if &self.total.outgoing > outgoing {
+                warnings.push((
+                    Code::AheadOfLastMonth,
+                    tf(
+                        "Spent {} more than by this point in {}",
+                        &[
+                            &format(&(&self.total.outgoing - outgoing)),
+                            &previous.month_name(),
+                        ],
+                    ),
+                ));
+            }
+        }
+
+        warnings
+    }
+
+    /// How the spending compares to the previous month at the same point, if it was recorded
+    pub fn comparison(&self) -> Option<String> {
+        let (previous, outgoing) = self.previous_outgoing.as_ref()?;
+        let month = previous.month_name();
+
+        Some(match percent_change(&self.total.outgoing, outgoing) {
+            Some(change) if change.is_zero() => {
+                tf("Spending on par with {} at this point", &[&month])
+            }
+            Some(change) if change > BigDecimal::zero() => {
+                tf("Spending {}% above {} at this point", &[&change, &month])
+            }
+            Some(change) => tf("Spending {}% below {} at this point", &[&-change, &month]),
+            None => tf("Nothing had been spent by this point in {}", &[&month]),
+        })
+    }
+
+    /// Prints the summary, followed by a table of every entry with `columns`, if any
+    pub fn print(&self, config: &Config, columns: &[Column], format: Format) {
+        let mut out = io::stdout().lock();
+        let result = match format {
+            Format::Text => self.render(config, columns, &mut out),
+            Format::Markdown => self.render_markdown(config, columns, &mut out),
+            Format::Plain => self.render_plain(config, &mut out),
+        };
+
+        // Readers such as `head` may stop reading early, which is fine
+        if let Err(err) = result {
+            if err.kind() != io::ErrorKind::BrokenPipe {
+                eprintln!("Failed to print the status: {}", err);
+            }
+        }
+    }
+
+    /// Writes what `print` shows into `out`, which doesn't need to be a terminal
+    pub fn render(
+        &self,
+        config: &Config,
+        columns: &[Column],
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        let color = config.color;
+        let title = tf("Status for {}", &[&self.file.label()]);
+        writeln!(out, "{}", Style::Bold.paint(title, color))?;
+        let format = |amount: &BigDecimal| config.format_amount_in(amount, &self.currency);
+
+        if let Some(opening) = &self.opening_balance {
+            writeln!(out, "\t{}: {}", t("Opening balance"), format(opening))?;
+        }
+        writeln!(
+            out,
+            "\t{}: {}",
+            t("Incoming"),
+            Style::Green.paint(format(&self.total.incoming), color)
+        )?;
+        writeln!(
+            out,
+            "\t{}: {}",
+            t("Outgoing"),
+            Style::Red.paint(format(&self.total.outgoing), color)
+        )?;
+        if let Some(cash) = self.cash_on_hand() {
+            writeln!(
+                out,
+                "\t{}: {}",
+                t("Cash on hand"),
+                Style::Bold.paint(format(&cash), color)
+            )?;
+        }
+        writeln!(
+            out,
+            "\t{}: {}",
+            t("Spent per day"),
+            format(&self.daily_outgoing())
+        )?;
+        writeln!(
+            out,
+            "\t{}: {}",
+            t("Received per day"),
+            format(&self.daily_incoming())
+        )?;
+        if let Some(rate) = self.total.savings_rate() {
+            writeln!(out, "\t{}", tf("Saved {}% of the income", &[&rate]))?;
+        }
+
+        if let Some(comparison) = self.comparison() {
+            writeln!(out, "\t{}", comparison)?;
+        }
+
+        if !columns.is_empty() {
+            writeln!(out, "{}", t("Operations"))?;
+            for (operation, balance) in &self.operations {
+                let currency = operation.currency.as_deref().unwrap_or(&config.currency);
+                let mut row = String::from("\t");
+
+                for (idx, column) in columns.iter().enumerate() {
+                    if idx > 0 {
+                        row.push_str(if *column == Column::Description {
+                            "  "
+                        } else {
+                            " "
+                        });
+                    }
+
+                    let cell = match column {
+                        Column::Id => format!("#{:<3}", operation.id),
+                        Column::Day => format!("{:02}", operation.day),
+                        Column::Amount => {
+                            // Padded before painting, as escape codes would count towards
+                            // the width
+                            let amount = format!(
+                                "{} {:>14}",
+                                operation.typ.sign(),
+                                config.format_amount_in(&operation.amount, currency)
+                            );
+                            let style = match operation.typ {
+                                EntryType::Debit => Style::Red,
+                                EntryType::Credit | EntryType::Refund => Style::Green,
+                            };
+                            style.paint(amount, color)
+                        }
+                        Column::Balance => format!("{:>14}", format(balance)),
+                        Column::Description => operation.description.clone(),
+                    };
+                    row.push_str(&cell);
+                }
+
+                writeln!(out, "{}", row)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes every operation on a line of its own, as tab-separated fields: the month, the
+    /// day, the entry's number, its kind, its amount as recorded, its currency and its
+    /// description
+    ///
+    /// Meant for scripts, so the fields never change between versions: new ones would only be
+    /// added at the end.
+    pub fn render_plain(&self, config: &Config, out: &mut impl Write) -> io::Result<()> {
+        for (operation, _) in &self.operations {
+            let fields = [
+                self.file.as_str(),
+                &format!("{:02}", operation.day),
+                &operation.id.to_string(),
+                operation.typ.name(),
+                &operation.amount.to_string(),
+                operation.currency.as_deref().unwrap_or(&config.currency),
+                &operation.description,
+            ];
+            writeln!(out, "{}", plain_line(&fields))?;
+        }
+
+        Ok(())
+    }
+
+    /// Like `render`, but as GitHub-flavored markdown, to be pasted into notes and wikis
+    pub fn render_markdown(
+        &self,
+        config: &Config,
+        columns: &[Column],
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        writeln!(out, "## {}", tf("Status for {}", &[&self.file.label()]))?;
+        writeln!(out)?;
+        let format = |amount: &BigDecimal| config.format_amount_in(amount, &self.currency);
+
+        writeln!(out, "| | {} |", t("Amount"))?;
+        writeln!(out, "|---|---:|")?;
+        let mut line =
+            |name: &'static str, value: String| writeln!(out, "| {} | {} |", t(name), value);
+        if let Some(opening) = &self.opening_balance {
+            line("Opening balance", format(opening))?;
+        }
+        line("Incoming", format(&self.total.incoming))?;
+        line("Outgoing", format(&self.total.outgoing))?;
+        if let Some(cash) = self.cash_on_hand() {
+            line("Cash on hand", format(&cash))?;
+        }
+        line("Spent per day", format(&self.daily_outgoing()))?;
+        line("Received per day", format(&self.daily_incoming()))?;
+        if let Some(rate) = self.total.savings_rate() {
+            line("Saved", tf("{}% of the income", &[&rate]))?;
+        }
+
+        if let Some(comparison) = self.comparison() {
+            writeln!(out)?;
+            writeln!(out, "{}.", comparison)?;
+        }
+
+        if !columns.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "### {}", t("Operations"))?;
+            writeln!(out)?;
+            let (mut header, mut alignment) = (String::from("|"), String::from("|"));
+            for column in columns {
+                let (name, align) = match column {
+                    Column::Id => (" # |".to_owned(), "---:|"),
+                    Column::Day => (format!(" {} |", t("Day")), "---:|"),
+                    Column::Amount => (format!(" | {} |", t("Amount")), ":---:|---:|"),
+                    Column::Balance => (format!(" {} |", t("Balance")), "---:|"),
+                    Column::Description => (format!(" {} |", t("Description")), "---|"),
+                };
+                header.push_str(&name);
+                alignment.push_str(align);
+            }
+            writeln!(out, "{}", header)?;
+            writeln!(out, "{}", alignment)?;
+
+            for (operation, balance) in &self.operations {
+                let currency = operation.currency.as_deref().unwrap_or(&config.currency);
+                let mut row = String::from("|");
+
+                for column in columns {
+                    let cell = match column {
+                        Column::Id => operation.id.to_string(),
+                        Column::Day => format!("{:02}", operation.day),
+                        Column::Amount => format!(
+                            "{} | {}",
+                            operation.typ.sign(),
+                            config.format_amount_in(&operation.amount, currency)
+                        ),
+                        Column::Balance => format(balance),
+                        // A pipe would end the cell early
+                        Column::Description => operation.description.replace('|', "\\|"),
+                    };
+                    row.push_str(&format!(" {} |", cell));
+                }
+
+                writeln!(out, "{}", row)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The totals of `operations` of `file`, leaving out the ones after `last_day` of its budget
+/// month, as counted by `cycle_day`
+fn total_until_day(
+    file: &BookkeepingFile,
+    operations: &[Operation],
+    last_day: u8,
+    converter: &Converter,
+) -> Result<Total> {
+    let mut total = Total::zero();
+
+    for operation in operations
+        .iter()
+        .filter(|operation| cycle_day(file, operation.day) <= last_day)
+    {
+        let amount = converter.convert(operation.amount.clone(), operation.currency.as_deref())?;
+        match operation.typ {
+            EntryType::Debit => total.outgoing += amount,
+            EntryType::Credit => total.incoming += amount,
+            EntryType::Refund => total.outgoing -= amount,
+        }
+    }
+
+    Ok(total)
+}
+
+/// How `status` can be shown
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Text,
+    Markdown,
+    /// Only the operations, as tab-separated fields that never change, for scripts
+    Plain,
+}
+
+/// What the table of operations can be sorted by, with `--sort`
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortKey {
+    Day,
+    Amount,
+    Description,
+}
+
+/// What `--group-by` merges operations by
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GroupBy {
+    /// The same description, ignoring case and accents as configured
+    Description,
+    /// The category of the rule matching the description, or its tag
+    ///
+    /// Uncategorized operations are merged by description.
+    Category,
+}
+
+impl GroupBy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GroupBy::Description => "description",
+            GroupBy::Category => "category",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            GroupBy::Description => "Description",
+            GroupBy::Category => "Category",
+        }
+    }
+}
+
+/// Operations merged into a single row by `--group-by`
+#[derive(Debug)]
+pub struct Group {
+    /// As first written, among the merged operations
+    pub name: String,
+    pub count: usize,
+    /// The sum of the merged operations, negative for spending, in the status' currency
+    pub total: BigDecimal,
+}
+
+impl Group {
+    fn sign(&self) -> char {
+        if self.total < BigDecimal::zero() {
+            '-'
+        } else {
+            '+'
+        }
+    }
+}
+
+/// What a person put in and spent in a month, with `--by-person`
+#[derive(Debug)]
+pub struct Share {
+    /// As first written, among the person's entries
+    pub name: String,
+    /// In the status' currency
+    pub incoming: BigDecimal,
+    pub outgoing: BigDecimal,
+}
+
+impl Share {
+    fn named(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            incoming: BigDecimal::zero(),
+            outgoing: BigDecimal::zero(),
+        }
+    }
+}
+
+/// Columns of the table of operations, which can be hidden or reordered with `--columns`
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Column {
+    /// The entry's number, as used by `edit`
+    #[clap(alias = "op")]
+    Id,
+    Day,
+    /// The amount, after the sign of the entry
+    Amount,
+    /// The balance after the entry
+    Balance,
+    Description,
+}
+
+impl Column {
+    pub const ALL: [Self; 5] = [
+        Self::Id,
+        Self::Day,
+        Self::Amount,
+        Self::Balance,
+        Self::Description,
+    ];
+}
+
+/// `fields` separated by tabs, with any tab or line break in them turned into a space
+pub fn plain_line(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| field.replace(['\t', '\n', '\r'], " "))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// How much `current` grew over `previous`, as a whole percentage
+///
+/// Returns `None` when `previous` is zero, as any growth over it is infinite
+pub fn percent_change(current: &BigDecimal, previous: &BigDecimal) -> Option<BigDecimal> {
+    if previous.is_zero() {
+        return None;
+    }
+
+    let change = (current - previous) * BigDecimal::from(100) / previous;
+
+    Some(change.with_scale(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{percent_change, Column, GroupBy, SortKey, Status};
+    use crate::{
+        balances::OpeningBalances, config::Config, currency::Converter, file::BookkeepingFile,
+        rules::Rule,
+    };
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn computes_percent_change() {
+        assert_eq!(
+            percent_change(&decimal("112"), &decimal("100")),
+            Some(decimal("12"))
+        );
+        assert_eq!(
+            percent_change(&decimal("50"), &decimal("200")),
+            Some(decimal("-75"))
+        );
+        assert_eq!(percent_change(&decimal("50"), &decimal("0")), None);
+    }
+
+    #[test]
+    fn renders_without_a_terminal() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 100 Salary\n06 - 30 Groceries\n03 - 12 Coffee\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let file = BookkeepingFile::new(1, 2024);
+        let status =
+            Status::compute(dir.path(), file, 31, &config, &Converter::new(&config)).unwrap();
+
+        let mut report = Vec::new();
+        status.render(&config, &Column::ALL, &mut report).unwrap();
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            "Status for Jan 2024\n\
+             \tIncoming: R$ 100.00\n\
+             \tOutgoing: R$ 42.00\n\
+             \tSpent per day: R$ 1.35\n\
+             \tReceived per day: R$ 3.22\n\
+             \tSaved 58.0% of the income\n\
+             Operations\n\
+             \t#3   03 -       R$ 12.00      R$ -12.00  Coffee\n\
+             \t#1   05 +      R$ 100.00       R$ 88.00  Salary\n\
+             \t#2   06 -       R$ 30.00       R$ 58.00  Groceries\n"
+        );
+    }
+
+    #[test]
+    fn renders_only_the_chosen_columns() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("01-2024"), "05 + 100 Salary\n").unwrap();
+
+        let config = Config::default();
+        let file = BookkeepingFile::new(1, 2024);
+        let status =
+            Status::compute(dir.path(), file, 31, &config, &Converter::new(&config)).unwrap();
+
+        let mut report = Vec::new();
+        let columns = [Column::Description, Column::Day, Column::Amount];
+        status.render(&config, &columns, &mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.ends_with("Operations\n\tSalary 05 +      R$ 100.00\n"));
+
+        let mut report = Vec::new();
+        status
+            .render_markdown(&config, &columns, &mut report)
+            .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.ends_with(
+            "| Description | Day | | Amount |\n\
+             |---|---:|:---:|---:|\n\
+             | Salary | 05 | + | R$ 100.00 |\n"
+        ));
+    }
+
+    #[test]
+    fn sorts_operations() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 100 Salary\n06 - 30 groceries\n03 - 12 Coffee\n07 - 30 Bus\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let file = BookkeepingFile::new(1, 2024);
+        let mut status =
+            Status::compute(dir.path(), file, 31, &config, &Converter::new(&config)).unwrap();
+        let descriptions = |status: &Status| -> Vec<_> {
+            status
+                .operations
+                .iter()
+                .map(|(operation, _)| operation.description.clone())
+                .collect()
+        };
+
+        // Ties stay by day
+        status.sort(SortKey::Amount, true);
+        assert_eq!(
+            descriptions(&status),
+            ["Salary", "groceries", "Bus", "Coffee"]
+        );
+        assert_eq!(status.operations[0].1, BigDecimal::from(88));
+
+        status.sort(SortKey::Description, false);
+        assert_eq!(
+            descriptions(&status),
+            ["Bus", "Coffee", "groceries", "Salary"]
+        );
+    }
+
+    #[test]
+    fn groups_operations() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "02 - 5 Coffee\n03 - 30 Market #food\n04 - 6 coffee\n05 + 100 Salary\n",
+        )
+        .unwrap();
+
+        let config = Config {
+            rules: vec![Rule {
+                pattern: "coffee".into(),
+                category: "food".into(),
+            }],
+            ..Config::default()
+        };
+        let converter = Converter::new(&config);
+        let file = BookkeepingFile::new(1, 2024);
+        let status = Status::compute(dir.path(), file, 31, &config, &converter).unwrap();
+
+        let groups = status
+            .groups(GroupBy::Description, &config, &converter)
+            .unwrap();
+        let mut report = Vec::new();
+        status
+            .render_groups(&groups, GroupBy::Description, &config, &mut report)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            "Operations by description\n\
+             \t  1x +      R$ 100.00  Salary\n\
+             \t  1x -       R$ 30.00  Market #food\n\
+             \t  2x -       R$ 11.00  Coffee\n"
+        );
+
+        let groups = status
+            .groups(GroupBy::Category, &config, &converter)
+            .unwrap();
+        assert_eq!(groups[1].name, "food");
+        assert_eq!(groups[1].count, 3);
+        assert_eq!(groups[1].total, BigDecimal::from(-41));
+    }
+
+    #[test]
+    fn splits_the_month_by_person() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "02 - 50 Market @alice\n03 + 3000 Salary @Bob\n04 - 20 Gas @bob\n\
+             05 ~ 10 Market @alice\n06 - 100 Rent\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let converter = Converter::new(&config);
+        let file = BookkeepingFile::new(1, 2024);
+        let status = Status::compute(dir.path(), file, 31, &config, &converter).unwrap();
+
+        let shares = status.shares(&config, &converter).unwrap();
+        let mut report = Vec::new();
+        status.render_shares(&shares, &config, &mut report).unwrap();
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            "By person\n\
+             \talice: put in R$ 0.00, spent R$ 40.00\n\
+             \tBob: put in R$ 3,000.00, spent R$ 20.00\n\
+             \t(shared): put in R$ 0.00, spent R$ 100.00\n"
+        );
+    }
+
+    #[test]
+    fn renders_plain_lines() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 100 Salary\n06 - USD 3.5 Coffee\n",
+        )
+        .unwrap();
+
+        let config = Config {
+            rates: [("USD".to_owned(), BigDecimal::from(5))].into(),
+            ..Config::default()
+        };
+        let file = BookkeepingFile::new(1, 2024);
+        let converter = Converter::new(&config);
+        let status = Status::compute(dir.path(), file, 31, &config, &converter).unwrap();
+
+        let mut plain = Vec::new();
+        status.render_plain(&config, &mut plain).unwrap();
+        assert_eq!(
+            String::from_utf8(plain).unwrap(),
+            "01-2024\t05\t1\tput\t100\tBRL\tSalary\n\
+             01-2024\t06\t2\ttake\t3.5\tUSD\tCoffee\n"
+        );
+    }
+
+    #[test]
+    fn renders_markdown_tables() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("01-2024"),
+            "05 + 100 Salary\n06 - 30 Rent | Condo\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("12-2023"), "06 - 20 Rent\n").unwrap();
+        let config = Config::default();
+        let mut balances = OpeningBalances::load(dir.path(), &config).unwrap();
+        balances.set(&BookkeepingFile::new(1, 2024), BigDecimal::from(1000));
+        balances.save(&config).unwrap();
+
+        let file = BookkeepingFile::new(1, 2024);
+        let status =
+            Status::compute(dir.path(), file, 31, &config, &Converter::new(&config)).unwrap();
+
+        let mut report = Vec::new();
+        status
+            .render_markdown(&config, &Column::ALL, &mut report)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            "## Status for Jan 2024\n\
+             \n\
+             | | Amount |\n\
+             |---|---:|\n\
+             | Opening balance | R$ 1,000.00 |\n\
+             | Incoming | R$ 100.00 |\n\
+             | Outgoing | R$ 30.00 |\n\
+             | Cash on hand | R$ 1,070.00 |\n\
+             | Spent per day | R$ 0.96 |\n\
+             | Received per day | R$ 3.22 |\n\
+             | Saved | 70.0% of the income |\n\
+             \n\
+             Spending 50% above December at this point.\n\
+             \n\
+             ### Operations\n\
+             \n\
+             | # | Day | | Amount | Balance | Description |\n\
+             |---:|---:|:---:|---:|---:|---|\n\
+             | 1 | 05 | + | R$ 100.00 | R$ 100.00 | Salary |\n\
+             | 2 | 06 | - | R$ 30.00 | R$ 70.00 | Rent \\| Condo |\n"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    cell::RefCell, collections::BTreeMap, num::NonZeroUsize, panic::resume_unwind, path::PathBuf,
+    thread,
+};
+
+use crate::{
+    archive::list_stored_files,
+    config::Config,
+    file::{list_bookkeeping_files, BookkeepingFile},
+    index::month_totals,
+    parser::{Entry, Operation},
+    reader::Reader,
+    stats::{MonthTotal, Tally},
+    writer::{write_line, Writer},
+    Error, Result,
+};
+
+/// Where a `Bookkeeper` keeps its entries
+///
+/// Entries are numbered from 1 by their position in the month, whatever keeps them.
+pub trait Storage {
+    /// Every month with entries, not counting archived ones, oldest first
+    fn months(&self) -> Result<Vec<BookkeepingFile>>;
+
+    /// Every entry recorded in `month`, `None` if it never was
+    fn load(&self, month: &BookkeepingFile, config: &Config) -> Result<Option<Vec<Operation>>>;
+
+    /// Appends `entry` to `month`, starting it if needed
+    fn append(&self, month: &BookkeepingFile, entry: Entry, config: &Config) -> Result<()>;
+
+    /// Replaces every entry of `month` with `operations`, renumbering them in order
+    fn rewrite(
+        &self,
+        month: &BookkeepingFile,
+        operations: &[Operation],
+        config: &Config,
+    ) -> Result<()>;
+
+    /// Where `month` is kept, to point to it in messages
+    fn location(&self, month: &BookkeepingFile) -> PathBuf;
+
+    /// Changes the entry numbered `id` in `month` with `edit`
+    fn edit(
+        &self,
+        month: &BookkeepingFile,
+        id: usize,
+        config: &Config,
+        edit: &mut dyn FnMut(&mut Operation) -> Result<()>,
+    ) -> Result<()> {
+        let mut operations = self.load(month, config)?.unwrap_or_default();
+        let operation = operations
+            .iter_mut()
+            .find(|operation| operation.id == id)
+            .ok_or(Error::NoSuchEntry(id))?;
+        edit(operation)?;
+
+        self.rewrite(month, &operations, config)
+    }
+
+    /// The totals of every month, archived ones included, oldest first
+    fn totals(&self, config: &Config) -> Result<Vec<MonthTotal>> {
+        let mut tally = Tally::new(config);
+        self.scan(config, &mut |scanned| match scanned {
+            Scanned::Month(month) => {
+                tally.month(month);
+                Ok(())
+            }
+            Scanned::Entry(entry) => tally.add(entry),
+        })?;
+
+        Ok(tally.finish().months)
+    }
+
+    /// Feeds every month, archived ones included, oldest first, to `each` as it's read: as the
+    /// month starts, then entry by entry, so that they needn't all be kept in memory
+    fn scan(&self, config: &Config, each: &mut dyn FnMut(Scanned) -> Result<()>) -> Result<()> {
+        for month in self.months()? {
+            let operations = self.load(&month, config)?.unwrap_or_default();
+            each(Scanned::Month(month))?;
+            for operation in &operations {
+                each(Scanned::Entry(operation.as_entry()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What `Storage::scan` reads, in order
+pub enum Scanned<'a> {
+    /// The start of a month, whose entries follow
+    Month(BookkeepingFile),
+    Entry(Entry<'a>),
+}
+
+/// A bookkeeping file per month in the data folder, the default
+pub struct Files {
+    dir: PathBuf,
+}
+
+impl Files {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Storage for Files {
+    fn months(&self) -> Result<Vec<BookkeepingFile>> {
+        list_bookkeeping_files(&self.dir)
+    }
+
+    fn load(&self, month: &BookkeepingFile, config: &Config) -> Result<Option<Vec<Operation>>> {
+        let path = self.location(month);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Reader::new(config).operations(path).map(Some)
+    }
+
+    fn append(&self, month: &BookkeepingFile, entry: Entry, config: &Config) -> Result<()> {
+        Writer::write_entry(&self.location(month), entry, config)
+    }
+
+    fn rewrite(
+        &self,
+        month: &BookkeepingFile,
+        operations: &[Operation],
+        config: &Config,
+    ) -> Result<()> {
+        let mut contents = Vec::new();
+        for operation in operations {
+            write_line(&mut contents, &operation.as_entry())?;
+        }
+
+        Writer::replace_contents(&self.location(month), contents, config)
+    }
+
+    fn location(&self, month: &BookkeepingFile) -> PathBuf {
+        self.dir.join(month.as_path())
+    }
+
+    // Only the edited line is rewritten, leaving the others as they were typed
+    fn edit(
+        &self,
+        month: &BookkeepingFile,
+        id: usize,
+        config: &Config,
+        edit: &mut dyn FnMut(&mut Operation) -> Result<()>,
+    ) -> Result<()> {
+        Writer::edit_entry(&self.location(month), id, config, edit)
+    }
+
+    // Only the months that changed since they were last summed are read
+    fn totals(&self, config: &Config) -> Result<Vec<MonthTotal>> {
+        month_totals(&self.dir, config)
+    }
+
+    // Files are parsed a batch at a time, each on its own thread, then fed in order
+    fn scan(&self, config: &Config, each: &mut dyn FnMut(Scanned) -> Result<()>) -> Result<()> {
+        let files = list_stored_files(&self.dir)?;
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+        for batch in files.chunks(threads) {
+            let parsed: Vec<Result<Vec<Operation>>> = thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|stored| scope.spawn(|| Reader::new(config).operations_in(stored.open()?)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap_or_else(|panic| resume_unwind(panic)))
+                    .collect()
+            });
+
+            for (stored, operations) in batch.iter().zip(parsed) {
+                each(Scanned::Month(stored.file.clone()))?;
+                for operation in &operations? {
+                    each(Scanned::Entry(operation.as_entry()))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Entries kept in memory, gone once dropped, for tests
+#[derive(Default)]
+pub struct Memory {
+    /// The entries of every month, by its ordinal
+    months: RefCell<BTreeMap<i32, (BookkeepingFile, Vec<Operation>)>>,
+}
+
+impl Storage for Memory {
+    fn months(&self) -> Result<Vec<BookkeepingFile>> {
+        Ok(self
+            .months
+            .borrow()
+            .values()
+            .map(|(month, _)| month.clone())
+            .collect())
+    }
+
+    fn load(&self, month: &BookkeepingFile, _: &Config) -> Result<Option<Vec<Operation>>> {
+        Ok(self
+            .months
+            .borrow()
+            .get(&month.ordinal())
+            .map(|(_, operations)| operations.clone()))
+    }
+
+    fn append(&self, month: &BookkeepingFile, entry: Entry, config: &Config) -> Result<()> {
+        if config.dry_run {
+            return Ok(());
+        }
+
+        let mut months = self.months.borrow_mut();
+        let (_, operations) = months
+            .entry(month.ordinal())
+            .or_insert_with(|| (month.clone(), Vec::new()));
+        operations.push(Operation::from_entry(operations.len() + 1, entry));
+
+        Ok(())
+    }
+
+    fn rewrite(
+        &self,
+        month: &BookkeepingFile,
+        operations: &[Operation],
+        config: &Config,
+    ) -> Result<()> {
+        if config.dry_run {
+            return Ok(());
+        }
+
+        let operations = (1..)
+            .zip(operations)
+            .map(|(id, operation)| Operation {
+                id,
+                ..operation.clone()
+            })
+            .collect();
+        self.months
+            .borrow_mut()
+            .insert(month.ordinal(), (month.clone(), operations));
+
+        Ok(())
+    }
+
+    fn location(&self, month: &BookkeepingFile) -> PathBuf {
+        PathBuf::from(month.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{Files, Scanned, Storage};
+    use crate::{archive::archive_year_files, config::Config, file::BookkeepingFile};
+
+    #[test]
+    fn scans_files_in_order() {
+        let dir = TempDir::new().unwrap();
+        let mut expected = Vec::new();
+        for year in [2022, 2023] {
+            for month in 1..=12 {
+                let file = BookkeepingFile::new(month, year);
+                let contents = format!("01 - {} Rent\n02 + 10 Refund\n", month);
+                fs::write(dir.path().join(file.as_path()), contents).unwrap();
+                expected.push(format!("{} {}", file.as_str(), month));
+            }
+        }
+        archive_year_files(dir.path(), 2022, false).unwrap();
+
+        let mut scanned = Vec::new();
+        Files::new(dir.path())
+            .scan(&Config::default(), &mut |item| {
+                match item {
+                    Scanned::Month(month) => scanned.push(month.as_str().to_owned()),
+                    Scanned::Entry(entry) if entry.description == "Rent" => {
+                        let month = scanned.last_mut().unwrap();
+                        *month = format!("{} {}", month, entry.amount);
+                    }
+                    Scanned::Entry(_) => {}
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(scanned, expected);
+    }
+}
@@ -0,0 +1,186 @@
+use bigdecimal::{BigDecimal, Zero};
+use serde::Deserialize;
+
+use crate::{
+    config::Config,
+    currency::Converter,
+    file::{BookkeepingFile, StoredFile},
+    parser::EntryType,
+    reader::Reader,
+    rules::RuleSet,
+    Result,
+};
+
+/// Which categories matter for income tax, the `[tax]` table of config.toml
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TaxCategories {
+    /// Categories of takes that can be deducted, such as `health` and `education`
+    pub deductible: Vec<String>,
+    /// Categories of puts that are taxed, such as `salary` and `rent`
+    pub taxable: Vec<String>,
+}
+
+/// An entry counted by the tax report, with its amount in the default currency
+pub struct TaxEntry {
+    pub file: BookkeepingFile,
+    pub day: u8,
+    /// Negative for refunds, which are taken off what was deducted
+    pub amount: BigDecimal,
+    pub description: String,
+}
+
+/// The entries of a category, in the order they happened
+pub struct TaxCategory {
+    pub name: String,
+    pub total: BigDecimal,
+    pub entries: Vec<TaxEntry>,
+}
+
+/// The deductible expenses and taxable income of a year, by category
+pub struct TaxReport {
+    pub year: i32,
+    pub deductible: Vec<TaxCategory>,
+    pub taxable: Vec<TaxCategory>,
+}
+
+impl TaxReport {
+    /// Reads the entries of `year` in `files`, which are expected to be sorted chronologically
+    pub fn from_files(year: i32, files: Vec<StoredFile>, config: &Config) -> Result<Self> {
+        let converter = Converter::new(config);
+        let rules = RuleSet::from_config(config);
+        let mut reader = Reader::new(config);
+        let mut report = Self {
+            year,
+            deductible: group(&config.tax.deductible),
+            taxable: group(&config.tax.taxable),
+        };
+
+        for stored in files
+            .into_iter()
+            .filter(|stored| stored.file.year() == year)
+        {
+            reader.for_each_entry_in(stored.open()?, |entry| {
+                let category = match rules.categorize(entry.description) {
+                    Some(category) => config.matching.normalize(category),
+                    None => return Ok(()),
+                };
+                let (categories, amount) = match entry.typ {
+                    EntryType::Debit => (&mut report.deductible, entry.amount),
+                    EntryType::Refund => (&mut report.deductible, -entry.amount),
+                    EntryType::Credit => (&mut report.taxable, entry.amount),
+                };
+                let found = categories
+                    .iter_mut()
+                    .find(|counted| config.matching.normalize(&counted.name) == category);
+
+                if let Some(counted) = found {
+                    let amount = converter.convert(amount, entry.currency)?;
+                    counted.total += &amount;
+                    counted.entries.push(TaxEntry {
+                        file: stored.file.clone(),
+                        day: entry.day,
+                        amount,
+                        description: entry.description.to_owned(),
+                    });
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(report)
+    }
+
+    pub fn print(&self, config: &Config) {
+        if self.deductible.is_empty() && self.taxable.is_empty() {
+            println!("No tax categories yet, list them in the [tax] table of config.toml");
+            return;
+        }
+
+        println!("Tax report for {}", self.year);
+        print_section("Deductible expenses", &self.deductible, config);
+        print_section("Taxable income", &self.taxable, config);
+    }
+}
+
+fn group(names: &[String]) -> Vec<TaxCategory> {
+    names
+        .iter()
+        .map(|name| TaxCategory {
+            name: name.clone(),
+            total: BigDecimal::zero(),
+            entries: Vec::new(),
+        })
+        .collect()
+}
+
+/// Prints the total of each category, followed by its entries, as they'd be declared
+fn print_section(title: &str, categories: &[TaxCategory], config: &Config) {
+    if categories.is_empty() {
+        return;
+    }
+
+    let total: BigDecimal = categories.iter().map(|category| &category.total).sum();
+    println!("{}: {}", title, config.format_amount(&total));
+    for category in categories {
+        println!(
+            "\t{}: {}",
+            category.name,
+            config.format_amount(&category.total)
+        );
+        for entry in &category.entries {
+            println!(
+                "\t\t{:02} {}: {} {}",
+                entry.day,
+                entry.file.label(),
+                config.format_amount(&entry.amount),
+                entry.description
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::{TaxCategories, TaxReport};
+    use crate::{archive::list_stored_files, config::Config, rules::Rule};
+
+    #[test]
+    fn sums_deductible_and_taxable_categories() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("03-2024"),
+            "05 + 5000 Salary #salary\n06 - 300 Dentist\n07 - 40 Groceries #food\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("04-2024"),
+            "10 - 200 Clinic #health\n11 ~ 50 Clinic refund #health\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("04-2023"), "10 - 999 Clinic #health\n").unwrap();
+
+        let config = Config {
+            rules: vec![Rule {
+                pattern: "dentist".into(),
+                category: "Health".into(),
+            }],
+            tax: TaxCategories {
+                deductible: vec!["health".into()],
+                taxable: vec!["salary".into()],
+            },
+            ..Config::default()
+        };
+        let files = list_stored_files(dir.path()).unwrap();
+        let report = TaxReport::from_files(2024, files, &config).unwrap();
+
+        assert_eq!(report.deductible[0].total, BigDecimal::from(450));
+        assert_eq!(report.deductible[0].entries.len(), 3);
+        assert_eq!(report.taxable[0].total, BigDecimal::from(5000));
+    }
+}
@@ -0,0 +1,136 @@
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::{
+    config::Config,
+    currency::Converter,
+    file::{BookkeepingFile, StoredFile},
+    i18n::tf,
+    parser::EntryType,
+    reader::Reader,
+    Result,
+};
+
+/// A single take, with its amount in the default currency
+pub struct Take {
+    pub file: BookkeepingFile,
+    pub day: u8,
+    pub amount: BigDecimal,
+    pub description: String,
+}
+
+/// Every take of a set of bookkeeping files, biggest first
+pub struct Top {
+    pub takes: Vec<Take>,
+    /// The sum of every take
+    pub spent: BigDecimal,
+}
+
+impl Top {
+    /// Reads every take in `files`, which are expected to be sorted chronologically
+    pub fn from_files(files: Vec<StoredFile>, config: &Config) -> Result<Self> {
+        let converter = Converter::new(config);
+        let mut reader = Reader::new(config);
+        let mut takes = Vec::new();
+
+        for stored in files {
+            reader.for_each_entry_in(stored.open()?, |entry| {
+                if entry.typ == EntryType::Debit {
+                    takes.push(Take {
+                        file: stored.file.clone(),
+                        day: entry.day,
+                        amount: converter.convert(entry.amount, entry.currency)?,
+                        description: entry.description.to_owned(),
+                    });
+                }
+
+                Ok(())
+            })?;
+        }
+
+        let spent = takes.iter().map(|take| &take.amount).sum();
+        // A stable sort, so equal takes stay in chronological order
+        takes.sort_by(|a, b| b.amount.cmp(&a.amount));
+
+        Ok(Self { takes, spent })
+    }
+
+    /// The share of the spending that went into `take`, in percent
+    pub fn share(&self, take: &Take) -> BigDecimal {
+        if self.spent.is_zero() {
+            return BigDecimal::zero();
+        }
+
+        (&take.amount * BigDecimal::from(100) / &self.spent).with_scale(1)
+    }
+
+    pub fn print(&self, count: usize, config: &Config) {
+        println!(
+            "{}",
+            tf(
+                "Largest {} of {} take(s), {} spent in total",
+                &[
+                    &count.min(self.takes.len()),
+                    &self.takes.len(),
+                    &config.format_amount(&self.spent)
+                ]
+            )
+        );
+
+        for take in self.takes.iter().take(count) {
+            println!(
+                "\t{:02} {}: {} {} ({}%)",
+                take.day,
+                take.file.label(),
+                config.format_amount(&take.amount),
+                take.description,
+                self.share(take)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::Top;
+    use crate::{archive::list_stored_files, config::Config};
+
+    #[test]
+    fn sorts_takes_by_amount() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("12-2023"),
+            "05 + 900 Salary\n10 - 150 Rent\n12 - 20 Lunch\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("01-2024"), "03 - 30 Gym\n10 - 150 Rent\n").unwrap();
+
+        let config = Config::default();
+        let top = Top::from_files(list_stored_files(dir.path()).unwrap(), &config).unwrap();
+
+        let takes: Vec<_> = top
+            .takes
+            .iter()
+            .map(|take| (take.file.as_str(), take.day, take.description.as_str()))
+            .collect();
+        assert_eq!(
+            takes,
+            [
+                ("12-2023", 10, "Rent"),
+                ("01-2024", 10, "Rent"),
+                ("01-2024", 3, "Gym"),
+                ("12-2023", 12, "Lunch"),
+            ]
+        );
+        assert_eq!(top.spent, BigDecimal::from(350));
+        assert_eq!(
+            top.share(&top.takes[0]),
+            BigDecimal::from_str("42.8").unwrap()
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::{
+    config::Config,
+    file::{BookkeepingFile, StoredFile},
+    i18n::{t, tf},
+    stats::{MonthTotal, Stats},
+    Result,
+};
+
+/// How the spending went over a run of months
+pub struct Trend {
+    /// The category followed, or `None` for the whole spending
+    pub category: Option<String>,
+    /// The spending of every month, in chronological order
+    pub months: Vec<(BookkeepingFile, BigDecimal)>,
+}
+
+impl Trend {
+    /// Reads the spending of every month in `files`, which are expected to be sorted
+    /// chronologically, only counting `category` if given
+    ///
+    /// Categories are compared as rules compare descriptions, so `food` follows `Food`.
+    pub fn from_files(
+        files: Vec<StoredFile>,
+        category: Option<&str>,
+        config: &Config,
+    ) -> Result<Self> {
+        let normalized = category.map(|category| config.matching.normalize(category));
+        let mut months = Vec::with_capacity(files.len());
+
+        for stored in files {
+            let file = stored.file.clone();
+            let stats = Stats::from_files(vec![stored], config)?;
+
+            let spent = match &normalized {
+                Some(normalized) => stats
+                    .spending
+                    .iter()
+                    .filter(|(name, _)| config.matching.normalize(name) == *normalized)
+                    .map(|(_, amount)| amount)
+                    .sum(),
+                None => stats.lifetime_total().outgoing,
+            };
+            months.push((file, spent));
+        }
+
+        Ok(Self {
+            category: category.map(str::to_owned),
+            months,
+        })
+    }
+
+    /// The whole spending of every month in `totals`, which are expected to be sorted
+    /// chronologically
+    pub fn from_totals(totals: Vec<MonthTotal>) -> Self {
+        Self {
+            category: None,
+            months: totals
+                .into_iter()
+                .map(|month| (month.file, month.total.outgoing))
+                .collect(),
+        }
+    }
+
+    /// How much the spending grows each month, by the line that best fits it
+    ///
+    /// Needs two months at least. Months are placed by when they are, so months with nothing
+    /// recorded in between still count as time passing.
+    pub fn slope(&self) -> Option<BigDecimal> {
+        if self.months.len() < 2 {
+            return None;
+        }
+
+        let count = BigDecimal::from(self.months.len() as u64);
+        let mean_x = self
+            .months
+            .iter()
+            .map(|(file, _)| BigDecimal::from(file.ordinal()))
+            .sum::<BigDecimal>()
+            / &count;
+        let mean_y = self
+            .months
+            .iter()
+            .map(|(_, spent)| spent)
+            .sum::<BigDecimal>()
+            / &count;
+
+        let (mut covariance, mut variance) = (BigDecimal::zero(), BigDecimal::zero());
+        for (file, y) in &self.months {
+            let dx = BigDecimal::from(file.ordinal()) - &mean_x;
+            covariance += &dx * (y - &mean_y);
+            variance += &dx * &dx;
+        }
+
+        Some((covariance / variance).with_scale(2))
+    }
+
+    pub fn print(&self, config: &Config) {
+        let months = self.months.len();
+        let title = match &self.category {
+            Some(category) => tf(
+                "Spending on {} over the last {} month(s)",
+                &[category, &months],
+            ),
+            None => tf("Spending over the last {} month(s)", &[&months]),
+        };
+        println!("{}", title);
+
+        for (file, spent) in &self.months {
+            println!("\t{}: {}", file.label(), config.format_amount(spent));
+        }
+
+        match self.slope() {
+            Some(slope) if slope > BigDecimal::zero() => {
+                println!(
+                    "{}",
+                    tf("Rising by {} a month", &[&config.format_amount(&slope)])
+                );
+            }
+            Some(slope) if slope < BigDecimal::zero() => {
+                println!(
+                    "{}",
+                    tf("Falling by {} a month", &[&config.format_amount(&-slope)])
+                );
+            }
+            Some(_) => println!("{}", t("Holding steady")),
+            None => println!("{}", t("Not enough months to tell a trend")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::TempDir;
+
+    use super::Trend;
+    use crate::{archive::list_stored_files, config::Config};
+
+    #[test]
+    fn fits_a_line_over_the_months() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("01-2024"), "05 - 100 Market\n06 - 50 Bus\n").unwrap();
+        fs::write(dir.path().join("02-2024"), "05 - 130 Market\n06 - 50 Bus\n").unwrap();
+        fs::write(dir.path().join("03-2024"), "05 - 160 Market\n06 - 20 Bus\n").unwrap();
+
+        let config: Config = toml::from_str(
+            r#"
+            [[rules]]
+            pattern = "market"
+            category = "Food"
+            "#,
+        )
+        .unwrap();
+        let files = || list_stored_files(dir.path()).unwrap();
+
+        let food = Trend::from_files(files(), Some("food"), &config).unwrap();
+        assert_eq!(food.slope(), Some(BigDecimal::from(30)));
+
+        let all = Trend::from_files(files(), None, &config).unwrap();
+        assert_eq!(all.slope(), Some(BigDecimal::from(15)));
+
+        // Nothing was recorded in April, so May is two months after March
+        fs::write(dir.path().join("05-2024"), "05 - 220 Market\n").unwrap();
+        let food = Trend::from_files(files(), Some("food"), &config).unwrap();
+        assert_eq!(food.slope(), Some(BigDecimal::from(30)));
+    }
+}
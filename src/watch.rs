@@ -0,0 +1,56 @@
+use std::{path::Path, sync::mpsc, time::Duration};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::{
+    i18n::{language, t},
+    Error, Result,
+};
+
+/// How long changes must stop coming for a redraw, as editors and syncing tools write files in
+/// several steps
+const SETTLE: Duration = Duration::from_millis(150);
+
+/// Calls `redraw` on a cleared screen, then again whenever a file in `dir` changes, until
+/// porquinho is interrupted
+///
+/// Errors of `redraw` are shown instead of ending the watch, as a file being edited by hand
+/// can be briefly broken.
+pub fn watch(dir: &Path, mut redraw: impl FnMut() -> Result<()>) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    loop {
+        print!("\x1b[2J\x1b[H");
+        if let Err(err) = redraw() {
+            eprintln!(
+                "{}[{}]: {}",
+                t("error"),
+                err.code().as_str(),
+                language().error(&err)
+            );
+        }
+
+        // Only the events that change files count, not the ones of reading them
+        loop {
+            let event = match receiver.recv() {
+                Ok(event) => event?,
+                Err(_) => return Ok(()),
+            };
+            if matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                break;
+            }
+        }
+        while receiver.recv_timeout(SETTLE).is_ok() {}
+    }
+}
+
+impl From<notify::Error> for Error {
+    fn from(err: notify::Error) -> Self {
+        Self::Watch(err.to_string())
+    }
+}
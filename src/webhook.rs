@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use ureq::Agent;
+
+use crate::{export::Record, Error, Result};
+
+/// How long a webhook may take to answer, so that a dashboard that's down doesn't hold
+/// recording up
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Posts `record` as JSON to `url`, failing unless it's answered with a success
+pub fn post(url: &str, record: &Record) -> Result<()> {
+    let agent: Agent = Agent::config_builder()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+        .into();
+    let body = serde_json::to_vec(record)?;
+
+    agent
+        .post(url)
+        .header("Content-Type", "application/json")
+        .send(&body[..])
+        .map_err(|err| Error::Webhook(format!("{}: {}", url, err)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, thread};
+
+    use bigdecimal::BigDecimal;
+    use tiny_http::{Response, Server};
+
+    use super::post;
+    use crate::{export::Record, parser::EntryType, Error};
+
+    #[test]
+    fn posts_the_record() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/porquinho", server.server_addr());
+        let answer = |status: u16| {
+            let server = &server;
+            move || {
+                let mut request = server.recv().unwrap();
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                request.respond(Response::empty(status)).unwrap();
+                body
+            }
+        };
+
+        let record = Record {
+            month: "10-2024".into(),
+            day: 3,
+            kind: EntryType::Debit,
+            amount: BigDecimal::from_str("12.50").unwrap(),
+            currency: None,
+            description: "Coffee".into(),
+        };
+        thread::scope(|scope| {
+            let received = scope.spawn(answer(200));
+            post(&url, &record).unwrap();
+            let body = received.join().unwrap();
+            assert!(body.contains(r#""description":"Coffee""#));
+
+            scope.spawn(answer(500));
+            assert!(matches!(post(&url, &record), Err(Error::Webhook(_))));
+        });
+    }
+}
@@ -1,34 +1,406 @@
-use std::{io::Write, path::Path};
+use std::{
+    io::{self, Write},
+    path::Path,
+    str,
+};
+
+use serde::Deserialize;
+use tempfile::NamedTempFile;
 
 use crate::{
-    parser::{Entry, EntryType},
-    Result,
+    backup::backup,
+    config::Config,
+    crypt, index,
+    parser::{Entry, Operation},
+    Error, Result,
 };
 
 use fs_err as fs;
 
+/// How hard we try to make sure a write reached the disk before returning
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Durability {
+    /// Leave flushing to the operating system
+    #[default]
+    None,
+    /// Flush the bookkeeping file after every write
+    FsyncOnWrite,
+    /// Also flush its folder, so a newly created file survives a power loss
+    FsyncDir,
+}
+
 pub struct Writer;
 
 impl Writer {
-    pub fn write_entry(path: &Path, entry: Entry) -> Result<()> {
-        let mut file = fs::OpenOptions::new().append(true).open(path)?;
+    pub fn write_entry(path: &Path, entry: Entry, config: &Config) -> Result<()> {
+        check_description(entry.description)?;
+        // Format the whole line up front, so it reaches the file in a single write
+        let mut line = Vec::new();
+        write_line(&mut line, &entry)?;
+
+        append_lines(path, line, config)
+    }
+
+    /// Appends every entry in `entries` with a single write, creating the file if needed
+    pub fn append_entries(path: &Path, entries: &[Entry], config: &Config) -> Result<()> {
+        let mut lines = Vec::new();
+        for entry in entries {
+            check_description(entry.description)?;
+            write_line(&mut lines, entry)?;
+        }
+
+        append_lines(path, lines, config)
+    }
+
+    /// Rewrites the entry at line `id` with the changes made by `edit`
+    pub fn edit_entry(
+        path: &Path,
+        id: usize,
+        config: &Config,
+        edit: impl FnOnce(&mut Operation) -> Result<()>,
+    ) -> Result<()> {
+        let contents = crypt::read_file(path, config.cipher.as_ref())?;
+        let contents = str::from_utf8(&contents)?;
+        let mut output = Vec::with_capacity(contents.len());
+        let mut edit = Some(edit);
+
+        for (idx, line) in contents.lines().enumerate() {
+            match edit.take() {
+                Some(edit) if idx + 1 == id => {
+                    let mut operation =
+                        Operation::from_entry(id, Entry::from_str(line, config.locale)?);
+                    edit(&mut operation)?;
+                    write_line(&mut output, &operation.as_entry())?;
+                }
+                not_yet => {
+                    edit = not_yet;
+                    writeln!(output, "{}", line)?;
+                }
+            }
+        }
+
+        if edit.is_some() {
+            return Err(Error::NoSuchEntry(id));
+        }
+
+        rewrite(path, contents.as_bytes(), output, config)
+    }
+
+    /// Replaces the whole file at `path` with `contents`, encrypted if needed, creating it if
+    /// there's none yet
+    pub fn replace_contents(path: &Path, contents: Vec<u8>, config: &Config) -> Result<()> {
+        let old = if path.exists() {
+            crypt::read_file(path, config.cipher.as_ref())?
+        } else {
+            Vec::new()
+        };
+
+        rewrite(path, &old, contents, config)
+    }
+
+    /// Writes `contents` into the file at `path` all at once, in plaintext, for files made to be
+    /// read elsewhere, such as a report given an `--output`
+    pub fn write_plain(path: &Path, contents: &[u8], config: &Config) -> Result<()> {
+        replace_file(path, contents, config.durability)
+    }
+
+    /// Encrypts the file at `path` if it's still in plaintext, returns whether it was
+    pub fn encrypt_file(path: &Path, config: &Config) -> Result<bool> {
+        let contents = fs::read(path)?;
+        if config.cipher.is_none() || crypt::is_encrypted(&contents) {
+            return Ok(false);
+        }
+
+        if config.dry_run {
+            println!("Would encrypt {}", path.display());
+            return Ok(true);
+        }
+
+        let sealed = crypt::seal(contents, config.cipher.as_ref())?;
+        replace_file(path, &sealed, config.durability)?;
+
+        Ok(true)
+    }
+}
+
+/// Appends `lines` to the file at `path`, creating it if needed
+///
+/// Encrypted files can't be appended to, so they're rewritten whole instead, and are never
+/// appended plaintext to once `encryption` is taken out of config.toml.
+fn append_lines(path: &Path, lines: Vec<u8>, config: &Config) -> Result<()> {
+    if config.cipher.is_none() && crypt::is_encrypted_file(path)? {
+        return Err(Error::Encryption(format!(
+            "{} is encrypted, but config.toml has no `encryption` set",
+            path.display()
+        )));
+    }
+
+    if config.dry_run {
+        println!("Would append to {}:", path.display());
+        for line in String::from_utf8_lossy(&lines).lines() {
+            println!("+{}", line);
+        }
+        return Ok(());
+    }
 
-        let typ = match entry.typ {
-            EntryType::Debit => "-",
-            EntryType::Credit => "+",
+    crate::debug!("appending {} byte(s) to {}", lines.len(), path.display());
+    backup(path, config.backups)?;
+    index::forget(path, config)?;
+
+    if let Some(cipher) = &config.cipher {
+        let mut contents = if path.exists() {
+            crypt::read_file(path, Some(cipher))?
+        } else {
+            Vec::new()
         };
+        contents.extend(lines);
+
+        return replace_file(path, &cipher.encrypt(&contents)?, config.durability);
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)?;
+    file.write_all(&lines)?;
+    sync_file(file.file(), config.durability)?;
+    sync_dir(path, config.durability)?;
+
+    Ok(())
+}
+
+/// Replaces `old`, the plaintext contents of the file at `path`, with `new`,
+/// or shows how it would change in a dry run
+fn rewrite(path: &Path, old: &[u8], new: Vec<u8>, config: &Config) -> Result<()> {
+    if config.dry_run {
+        print_diff(
+            path,
+            &String::from_utf8_lossy(old),
+            &String::from_utf8_lossy(&new),
+        );
+        return Ok(());
+    }
+
+    backup(path, config.backups)?;
+    index::forget(path, config)?;
+    let new = crypt::seal(new, config.cipher.as_ref())?;
+
+    replace_file(path, &new, config.durability)
+}
+
+/// Prints the lines that differ between `old` and `new`, which are expected to
+/// differ in a single stretch of lines, as edits do
+fn print_diff(path: &Path, old: &str, new: &str) {
+    let old: Vec<_> = old.lines().collect();
+    let new: Vec<_> = new.lines().collect();
+
+    let prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    if prefix == old.len() && old.len() == new.len() {
+        println!("Would leave {} unchanged", path.display());
+        return;
+    }
+
+    println!("Would change {}, from line {}:", path.display(), prefix + 1);
+    for line in &old[prefix..old.len() - suffix] {
+        println!("-{}", line);
+    }
+    for line in &new[prefix..new.len() - suffix] {
+        println!("+{}", line);
+    }
+}
+
+/// Fails if `description` can't be written as one, being empty or spanning several lines
+///
+/// A line break would add lines of its own to the month's file, and an empty description
+/// leaves a line that doesn't read back.
+pub fn check_description(description: &str) -> Result<()> {
+    if description.trim().is_empty() || description.chars().any(char::is_control) {
+        return Err(Error::InvalidDescription(description.to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Writes `entry` as a line of a bookkeeping file
+pub(crate) fn write_line(mut output: impl Write, entry: &Entry) -> io::Result<()> {
+    let typ = entry.typ.sign();
 
-        writeln!(
-            file,
+    match entry.currency {
+        Some(currency) => writeln!(
+            output,
+            "{d} {t} {c} {a} {D}",
+            d = entry.day,
+            t = typ,
+            c = currency,
+            a = entry.amount,
+            D = entry.description
+        ),
+        None => writeln!(
+            output,
             "{d} {t} {a} {D}",
             d = entry.day,
             t = typ,
             a = entry.amount,
             D = entry.description
-        )?;
+        ),
+    }
+}
+
+/// Atomically replaces the contents of `path` with `contents`
+///
+/// The new contents are written to a temporary file which is then renamed over `path`,
+/// so an error (or a panic) midway leaves the original file untouched.
+fn replace_file(path: &Path, contents: &[u8], durability: Durability) -> Result<()> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let mut temp = NamedTempFile::new_in(dir)?;
+
+    temp.write_all(contents)?;
+    sync_file(temp.as_file(), durability)?;
+    temp.persist(path).map_err(|err| err.error)?;
+    sync_dir(path, durability)?;
+
+    Ok(())
+}
+
+/// Flushes `file` unless `durability` is `None`
+fn sync_file(file: &std::fs::File, durability: Durability) -> Result<()> {
+    if durability != Durability::None {
+        file.sync_all()?;
+    }
+
+    Ok(())
+}
+
+/// Flushes the folder containing `path` if `durability` asks for it
+fn sync_dir(path: &Path, durability: Durability) -> Result<()> {
+    // Folders can't be opened as files on Windows, so there's nothing we can flush there
+    #[cfg(unix)]
+    if durability == Durability::FsyncDir {
+        if let Some(parent) = path.parent() {
+            fs::File::open(parent)?.sync_all()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use fs_err as fs;
+    use tempfile::NamedTempFile;
+
+    use super::Writer;
+    use crate::{
+        config::Config,
+        locale::Locale,
+        parser::{Entry, EntryType},
+        Error,
+    };
+
+    /// Tests write to the shared temp folder, which must not get a `backups` folder
+    fn config() -> Config {
+        Config {
+            backups: 0,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn edits_a_single_entry() {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "22 + 200.50 Payment\n23 - 10.25 Lunch\n").unwrap();
+
+        Writer::edit_entry(file.path(), 2, &config(), |operation| {
+            operation.amount += BigDecimal::from_str("5.50").unwrap();
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(file.path()).unwrap(),
+            "22 + 200.50 Payment\n23 - 15.75 Lunch\n"
+        );
+
+        let result = Writer::edit_entry(file.path(), 3, &config(), |_| Ok(()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn dry_runs_leave_the_file_untouched() {
+        let file = NamedTempFile::new().unwrap();
+        let contents = "22 + 200.50 Payment\n";
+        fs::write(file.path(), contents).unwrap();
+
+        let config = Config {
+            dry_run: true,
+            ..config()
+        };
+        Writer::edit_entry(file.path(), 1, &config, |operation| {
+            operation.description = "Salary".into();
+            Ok(())
+        })
+        .unwrap();
+        Writer::replace_contents(file.path(), Vec::new(), &config).unwrap();
+
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), contents);
+    }
+
+    #[test]
+    fn refuses_appending_plaintext_to_encrypted_files() {
+        let file = NamedTempFile::new().unwrap();
+        let contents = "age-encryption.org/v1\n-> X25519 sealed\n";
+        fs::write(file.path(), contents).unwrap();
+
+        let entry = Entry::from_str("05 - 10 Lunch", Locale::default()).unwrap();
+        Writer::write_entry(file.path(), entry, &config()).unwrap_err();
+
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), contents);
+    }
+
+    #[test]
+    fn refuses_descriptions_that_dont_read_back() {
+        let file = NamedTempFile::new().unwrap();
+        let contents = "05 - 10 Lunch\n";
+        fs::write(file.path(), contents).unwrap();
+
+        for description in ["", " ", "Coffee\n01 + 99999 Injected", "Tab\tbed"] {
+            let entry = Entry::new(6, EntryType::Debit, BigDecimal::from(5), description);
+            let err = Writer::append_entries(file.path(), &[entry], &config()).unwrap_err();
+            assert!(matches!(err, Error::InvalidDescription(_)), "{:?}", err);
+        }
+
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), contents);
+    }
+
+    #[test]
+    fn panicking_edit_leaves_the_file_untouched() {
+        let file = NamedTempFile::new().unwrap();
+        let contents = "22 + 200.50 Payment\n23 - 10.25 Lunch\n";
+        fs::write(file.path(), contents).unwrap();
 
-        println!("Updated {}", path.display());
+        let result = std::panic::catch_unwind(|| {
+            Writer::edit_entry(file.path(), 2, &config(), |_| panic!("bug while editing"))
+        });
 
-        Ok(())
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), contents);
     }
 }
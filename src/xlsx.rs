@@ -0,0 +1,190 @@
+use std::io::Write;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+
+use crate::{
+    config::Config, currency::Converter, export::Record, file::BookkeepingFile, parser::EntryType,
+    Error, Result, Total,
+};
+
+/// A month's file name, its records along with their converted amounts, and its total
+type Month<'a> = (&'a str, Vec<(&'a Record, BigDecimal)>, Total);
+
+/// Writes `records` as a spreadsheet, with a sheet per month after a summary of them all
+///
+/// Amounts and totals are written as values instead of formulas, converted into the default
+/// currency, so they show up the same in any spreadsheet app. Month sheets keep what was
+/// recorded, in its own currency, next to each amount.
+pub fn write_workbook(records: &[Record], config: &Config, mut output: impl Write) -> Result<()> {
+    let converter = Converter::new(config);
+    let bold = Format::new().set_bold();
+    let money = Format::new().set_num_format("#,##0.00");
+
+    // Records come grouped by month, in order
+    let mut months: Vec<Month> = Vec::new();
+    for record in records {
+        if months.last().map(|(month, ..)| *month) != Some(record.month.as_str()) {
+            months.push((&record.month, Vec::new(), Total::zero()));
+        }
+        let (_, month_records, total) = months.last_mut().unwrap();
+
+        let amount = converter.convert(record.amount.clone(), record.currency.as_deref())?;
+        // Takes are negative, so that summing a month's column gives its balance
+        let signed = match record.kind {
+            EntryType::Debit => -&amount,
+            EntryType::Credit | EntryType::Refund => amount.clone(),
+        };
+        match record.kind {
+            EntryType::Debit => total.outgoing += amount,
+            EntryType::Credit => total.incoming += amount,
+            EntryType::Refund => total.outgoing -= amount,
+        }
+        month_records.push((record, signed));
+    }
+
+    let mut workbook = Workbook::new();
+
+    let summary = workbook.add_worksheet().set_name("Summary")?;
+    summary.write_row_with_format(0, 0, ["Month", "Incoming", "Outgoing", "Balance"], &bold)?;
+    let mut lifetime = Total::zero();
+    for (row, (month, _, total)) in (1..).zip(&months) {
+        let label = BookkeepingFile::from_file_name(month)
+            .map_or_else(|| month.to_string(), |file| file.label());
+        write_total(summary, row, &label, total, &money)?;
+        lifetime.incoming += &total.incoming;
+        lifetime.outgoing += &total.outgoing;
+    }
+    let last = months.len() as u32 + 1;
+    write_total(summary, last, "Total", &lifetime, &money)?;
+    summary.set_column_width(0, 10)?;
+
+    for (month, records, _) in &months {
+        let sheet = workbook.add_worksheet().set_name(*month)?;
+        sheet.write_row_with_format(
+            0,
+            0,
+            ["Day", "Kind", "Amount", "Recorded as", "Description"],
+            &bold,
+        )?;
+
+        for (row, (record, amount)) in (1..).zip(records) {
+            let kind = match record.kind {
+                EntryType::Debit => "Take",
+                EntryType::Credit => "Put",
+                EntryType::Refund => "Refund",
+            };
+            let currency = record.currency.as_deref().unwrap_or(&config.currency);
+
+            sheet.write_number(row, 0, record.day)?;
+            sheet.write_string(row, 1, kind)?;
+            sheet.write_number_with_format(row, 2, to_f64(amount), &money)?;
+            sheet.write_string(row, 3, format!("{} {}", record.amount, currency))?;
+            sheet.write_string(row, 4, &record.description)?;
+        }
+        sheet.set_column_width(3, 14)?;
+        sheet.set_column_width(4, 40)?;
+    }
+
+    output.write_all(&workbook.save_to_buffer()?)?;
+
+    Ok(())
+}
+
+fn write_total(
+    sheet: &mut Worksheet,
+    row: u32,
+    name: &str,
+    total: &Total,
+    money: &Format,
+) -> Result<()> {
+    sheet.write_string(row, 0, name)?;
+    sheet.write_number_with_format(row, 1, to_f64(&total.incoming), money)?;
+    sheet.write_number_with_format(row, 2, to_f64(&total.outgoing), money)?;
+    sheet.write_number_with_format(row, 3, to_f64(&total.balance()), money)?;
+
+    Ok(())
+}
+
+/// Spreadsheets keep numbers as floats, which hold amounts of money well enough
+fn to_f64(amount: &BigDecimal) -> f64 {
+    amount.to_f64().unwrap_or_default()
+}
+
+impl From<XlsxError> for Error {
+    fn from(err: XlsxError) -> Self {
+        Self::Interchange(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, str::FromStr};
+
+    use bigdecimal::BigDecimal;
+
+    use super::write_workbook;
+    use crate::{config::Config, export::Record, parser::EntryType};
+
+    #[test]
+    fn writes_a_sheet_per_month() {
+        let record = |month: &str, kind, amount, currency: Option<&str>| Record {
+            month: month.into(),
+            day: 3,
+            kind,
+            amount: BigDecimal::from_str(amount).unwrap(),
+            currency: currency.map(str::to_owned),
+            description: "Coffee".into(),
+        };
+        let records = [
+            record("09-2024", EntryType::Debit, "12.50", None),
+            record("10-2024", EntryType::Credit, "3000", None),
+            record("10-2024", EntryType::Refund, "2", None),
+            record("10-2024", EntryType::Debit, "4", Some("USD")),
+        ];
+        let mut config = Config::default();
+        config.rates.insert("USD".into(), BigDecimal::from(5));
+
+        let mut workbook = Vec::new();
+        write_workbook(&records, &config, &mut workbook).unwrap();
+
+        // Spreadsheets are zip archives of a file per sheet
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(workbook)).unwrap();
+        let mut read = |name: &str| {
+            let mut xml = String::new();
+            archive
+                .by_name(&format!("xl/{}.xml", name))
+                .unwrap()
+                .read_to_string(&mut xml)
+                .unwrap();
+            xml
+        };
+        let value = |cell: &str, value: &str| format!(r#"<c r="{}" s="2"><v>{}</v>"#, cell, value);
+
+        // Month rows, then the total: incoming, outgoing and balance
+        let summary = read("worksheets/sheet1");
+        for (cell, expected) in [
+            ("C2", "12.5"),
+            ("D2", "-12.5"),
+            ("B3", "3000"),
+            ("C3", "18"),
+            ("D3", "2982"),
+            ("B4", "3000"),
+            ("C4", "30.5"),
+            ("D4", "2969.5"),
+        ] {
+            assert!(
+                summary.contains(&value(cell, expected)),
+                "{} in {}",
+                cell,
+                summary
+            );
+        }
+
+        // The dollars are converted, and kept as recorded beside
+        let october = read("worksheets/sheet3");
+        assert!(october.contains(&value("C4", "-20")), "{}", october);
+        assert!(read("sharedStrings").contains("<t>4 USD</t>"));
+        assert!(archive.by_name("xl/worksheets/sheet4.xml").is_err());
+    }
+}